@@ -1,69 +1,105 @@
-use std::cmp;
 use std::ops::Add;
 
-use crate::soft_float16::{NAN, NEG_INFINITY, NEG_ZERO, POS_INFINITY, POS_ZERO};
-use crate::SoftFloat16;
+use crate::format::{shift_right_sticky, Format};
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
 
-impl Add for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Add
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        let (sign0, exponent0, significand0) = (
-            Self::sign(self),
-            Self::exponent(self),
-            Self::significand(self),
-        );
-        let (sign1, exponent1, significand1) = (
-            Self::sign(other),
-            Self::exponent(other),
-            Self::significand(other),
-        );
+        Self::add_with_rounding(self, other, RoundingMode::NearestTiesToEven)
+    }
+}
 
-        if (exponent0 == 0x1F && significand0 != 0) || (exponent1 == 0x1F && significand1 != 0) {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn add_with_rounding(v0: Self, v1: Self, rounding_mode: RoundingMode) -> Self {
+        Self::add_with_flags(v0, v1, rounding_mode, TininessDetection::default()).0
+    }
+
+    pub fn add_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::add(
+            Self::raw(v0),
+            Self::raw(v1),
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn add(
+        v0: u128,
+        v1: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+
+        if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
             // NAN + _ or _ + NAN
-            return NAN;
+            return Self::propagate_nan(&[v0, v1]);
         } else if (exponent0 == 0 && significand0 == 0) & (exponent1 == 0 && significand1 == 0) {
             // 0 + 0
-            return if sign0 & sign1 == 0 {
-                POS_ZERO
+            return if sign0 == sign1 {
+                (v0, ExceptionFlags::NONE)
             } else {
-                NEG_ZERO
+                (
+                    Self::zero(rounding_mode.exact_zero_sign()),
+                    ExceptionFlags::NONE,
+                )
             };
         } else if exponent0 == 0 && significand0 == 0 {
             // 0 + _
-            return other;
+            return (v1, ExceptionFlags::NONE);
         } else if exponent1 == 0 && significand1 == 0 {
             // _ + 0
-            return self;
-        } else if (exponent0 == 0x1F && significand0 == 0)
-            && (exponent1 == 0x1F && significand1 == 0)
+            return (v0, ExceptionFlags::NONE);
+        } else if (exponent0 == Self::EXPONENT_MASK && significand0 == 0)
+            && (exponent1 == Self::EXPONENT_MASK && significand1 == 0)
         {
             // oo + oo
-            return if sign0 == sign1 { self } else { NAN };
-        } else if exponent0 == 0x1F && significand0 == 0 {
+            return if sign0 == sign1 {
+                (v0, ExceptionFlags::NONE)
+            } else {
+                (Self::NAN, ExceptionFlags::INVALID)
+            };
+        } else if exponent0 == Self::EXPONENT_MASK {
             // oo + _
-            return self;
-        } else if exponent1 == 0x1F && significand1 == 0 {
+            return (v0, ExceptionFlags::NONE);
+        } else if exponent1 == Self::EXPONENT_MASK {
             // _ + oo
-            return other;
+            return (v1, ExceptionFlags::NONE);
         };
 
-        // numbers only differ in sign, so result is POS_ZERO
+        // numbers only differ in sign, so result is an exact zero
         if sign0 != sign1 && exponent0 == exponent1 && significand0 == significand1 {
-            return POS_ZERO;
+            return (
+                Self::zero(rounding_mode.exact_zero_sign()),
+                ExceptionFlags::NONE,
+            );
         }
 
         // handle denormals and implicit bit
         let (exponent0, significand0) = if exponent0 == 0 {
             (1, significand0)
         } else {
-            (exponent0, significand0 | 0x400)
+            (exponent0, significand0 | Self::IMPLICIT_BIT)
         };
 
         let (exponent1, significand1) = if exponent1 == 0 {
             (1, significand1)
         } else {
-            (exponent1, significand1 | 0x400)
+            (exponent1, significand1 | Self::IMPLICIT_BIT)
         };
 
         // make sure that first number has larger or equal exponent to make
@@ -89,37 +125,22 @@ impl Add for SoftFloat16 {
                 )
             };
 
-        let shift = (exponent0 - exponent1) as u16;
+        let shift = exponent0 - exponent1;
 
-        // if shifting operation would throw out all bits (even beyond guard,
-        // round, sticky) it's like adding zero, so we can just return early
-        if shift >= 13 {
-            return Self::from_bits(sign0 << 15 | exponent0 << 10 | significand0 & 0x3FF);
-        }
-
-        // insert guard, round, sticky bits
+        // insert guard, round, sticky bits and align decimal point of second
+        // number; if shifting operation would throw out all bits (even beyond
+        // guard, round), only the sticky bit remains
         let significand0 = significand0 << 3;
-        let significand1 = significand1 << 3;
-        let sticky_bits = (1 << shift) - 1;
-        let sticky = ((significand1 & sticky_bits) != 0) as u16;
-
-        // align decimal point of second number
-        let significand1 = if shift < 16 {
-            (significand1 >> shift) | sticky
-        } else {
-            0
-        };
+        let significand1 = shift_right_sticky(significand1 << 3, shift);
 
         // if signs are equal add significands, otherwise subtract
         let (sign, exponent, significand) = if sign0 == sign1 {
             let (sign, exponent, significand) = (sign0, exponent0, significand0 + significand1);
-            assert!(significand < (1 << (12 + 3)));
+            assert!(significand < Self::IMPLICIT_BIT << (2 + 3));
 
-            if significand & (1 << (11 + 3)) != 0 {
+            if significand & (Self::IMPLICIT_BIT << (1 + 3)) != 0 {
                 // realign decimal point
-                let sticky = (significand & 1) | sticky;
-                let significand = (significand >> 1) | sticky;
-                (sign, exponent + 1, significand)
+                (sign, exponent + 1, shift_right_sticky(significand, 1))
             } else {
                 (sign, exponent, significand)
             }
@@ -131,15 +152,15 @@ impl Add for SoftFloat16 {
             } else {
                 (sign1, exponent0, significand1 - significand0)
             };
-            assert!(significand < (1 << (11 + 3)));
+            assert!(significand < Self::IMPLICIT_BIT << (1 + 3));
 
-            if significand & (1 << (10 + 3)) == 0 && exponent > 1 {
+            if significand & (Self::IMPLICIT_BIT << 3) == 0 && exponent > 1 {
                 // (try to) realign decimal point
                 significand <<= 1;
                 exponent -= 1;
             }
 
-            if significand & (1 << (10 + 3)) == 0 && exponent > 1 {
+            if significand & (Self::IMPLICIT_BIT << 3) == 0 && exponent > 1 {
                 // continue to (try to) realign decimal point if several leading
                 // digits have been canceled; this can only happen for
                 // - two normal numbers; cancellation occurs -> implicit bits
@@ -149,15 +170,17 @@ impl Add for SoftFloat16 {
                 // zero
                 // in both cases we don't need to worry about sticky bit being
                 // shifted back into significand
-                assert!(SoftFloat16::exponent(self) != 0);
-                assert!(SoftFloat16::exponent(other) != 0);
                 assert!(shift <= 1);
 
-                // (11 + 3)th bit should be one, with a 16bit significand, we
-                // thus want 2 leading zeros
-                let clz = SoftFloat16::clz(significand);
-                assert!(clz > 2);
-                let shift = cmp::min(exponent - 1, clz - 2);
+                // (MANT_BITS + 3)th bit should be one
+                let clz = significand.leading_zeros() as i32;
+                let target = 128 - (MANT_BITS as i32 + 1 + 3);
+                assert!(clz > target);
+                let shift = if exponent - 1 < clz - target {
+                    exponent - 1
+                } else {
+                    clz - target
+                };
                 significand <<= shift;
                 exponent -= shift;
             }
@@ -166,42 +189,21 @@ impl Add for SoftFloat16 {
         };
 
         assert!(exponent > 0);
-        if exponent >= 0x1F {
-            // overflow
-            return if sign == 0 {
-                POS_INFINITY
-            } else {
-                NEG_INFINITY
-            };
-        };
-
-        // rounding (to even)
-        let grs = significand & 0x7;
-        let significand = significand >> 3;
-        let lsb = significand & 1;
-        let rnd = if grs < 0x4 || (grs == 0x4 && lsb == 0) {
-            // round down
-            0
-        } else {
-            // round up
-            1
-        };
-
-        // denormals have exponent 0
-        let exponent = if exponent == 1 && significand < 0x400 {
-            0
-        } else {
-            exponent
-        };
-
-        // cut off implicit bit and allow into exponent
-        Self::from_bits(sign << 15 | (exponent << 10 | significand & 0x3FF) + rnd)
+        Self::round_and_pack(
+            sign,
+            exponent,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{check_exact, check_rounding, XorShift, ROUNDING_MODES};
+    use crate::SoftFloat16;
 
     #[test]
     fn test_add() {
@@ -226,9 +228,9 @@ mod tests {
             let x1 = SoftFloat16::from_bits(v1);
             let y = x0 + x1;
             let y_f = SoftFloat16::from(f32::from(x0) + f32::from(x1));
-            if y == NAN || y_f == NAN {
-                assert!(y == NAN, "{:?}", (v0, v1));
-                assert!(y_f == NAN, "{:?}", (v0, v1));
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{:?}", (v0, v1));
+                assert!(SoftFloat16::is_nan(y_f), "{:?}", (v0, v1));
             } else {
                 assert_eq!(
                     SoftFloat16::to_bits(y),
@@ -251,9 +253,9 @@ mod tests {
                 let x1 = SoftFloat16::from_bits(j);
                 let y = x0 + x1;
                 let y_f = SoftFloat16::from(f32::from(x0) + f32::from(x1));
-                if y == NAN || y_f == NAN {
-                    assert!(y == NAN, "{:?}", (i, j));
-                    assert!(y_f == NAN, "{:?}", (i, j));
+                if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                    assert!(SoftFloat16::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat16::is_nan(y_f), "{:?}", (i, j));
                 } else {
                     assert_eq!(
                         SoftFloat16::to_bits(y),
@@ -265,4 +267,89 @@ mod tests {
             }
         }
     }
+
+    fn check_all_add<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0 + x1;
+                let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0) + f32::from(x1));
+                if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                    assert!(SoftFloat::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat::to_bits(y),
+                        SoftFloat::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_add_binary8() {
+        check_all_add::<2, 5>();
+        check_all_add::<3, 4>();
+        check_all_add::<4, 3>();
+        check_all_add::<5, 2>();
+    }
+
+    fn check_add_rounding(i: u16, j: u16, rounding_mode: RoundingMode) {
+        let x0 = SoftFloat16::from_bits(i);
+        let x1 = SoftFloat16::from_bits(j);
+        let y = SoftFloat16::add_with_rounding(x0, x1, rounding_mode);
+        let nearest = x0 + x1;
+        let exact = f32::from(x0) as f64 + f32::from(x1) as f64;
+        if exact == 0.0 && !(i & 0x7fff == 0 && j & 0x7fff == 0 && i == j) {
+            // exact zero from operands with opposite signs
+            let expected = (rounding_mode.exact_zero_sign() << 15) as u16;
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:?}", (i, j));
+        } else if !exact.is_finite() {
+            check_exact(y, nearest, rounding_mode, &[i, j]);
+        } else {
+            check_rounding(y, nearest, |c| exact.total_cmp(&c), rounding_mode, &[i, j]);
+        }
+    }
+
+    #[test]
+    fn test_add_rounding() {
+        for rounding_mode in ROUNDING_MODES {
+            // exact zeros, overflow and tiny addends
+            check_add_rounding(0x3c00, 0xbc00, rounding_mode);
+            check_add_rounding(0x0000, 0x8000, rounding_mode);
+            check_add_rounding(0x8000, 0x8000, rounding_mode);
+            check_add_rounding(0x7bff, 0x7bff, rounding_mode);
+            check_add_rounding(0xfbff, 0xfbff, rounding_mode);
+            check_add_rounding(0x7bff, 0x5000, rounding_mode);
+            check_add_rounding(0x3c00, 0x0001, rounding_mode);
+            check_add_rounding(0x3c00, 0x8001, rounding_mode);
+        }
+    }
+
+    #[test]
+    fn test_random_add_rounding() {
+        let mut rng = XorShift::new();
+        for _ in 0..100_000 {
+            let (i, j) = (rng.next_u16(), rng.next_u16());
+            for rounding_mode in ROUNDING_MODES {
+                check_add_rounding(i, j, rounding_mode);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_add_rounding() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                for rounding_mode in ROUNDING_MODES {
+                    check_add_rounding(i, j, rounding_mode);
+                }
+            }
+        }
+    }
 }
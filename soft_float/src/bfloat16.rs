@@ -0,0 +1,278 @@
+use crate::{RoundingMode, SoftFloat, SoftFloat16};
+
+// bfloat16, i.e., the upper half of binary32 with the same 8 exponent bits
+// but only 7 significand bits
+pub type SoftBFloat16 = SoftFloat<u16, 8, 7>;
+
+// both directions are narrowing: bfloat16 has the larger range, binary16 the
+// higher precision
+impl From<SoftFloat16> for SoftBFloat16 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftBFloat16> for SoftFloat16 {
+    fn from(value: SoftBFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExceptionFlags, Sqrt, TininessDetection};
+
+    fn check(y: SoftBFloat16, y_f: SoftBFloat16, args: (u16, u16)) {
+        if SoftBFloat16::is_nan(y) || SoftBFloat16::is_nan(y_f) {
+            assert!(SoftBFloat16::is_nan(y), "{:?}", args);
+            assert!(SoftBFloat16::is_nan(y_f), "{:?}", args);
+        } else {
+            assert_eq!(
+                SoftBFloat16::to_bits(y),
+                SoftBFloat16::to_bits(y_f),
+                "{:?}",
+                args
+            );
+        }
+    }
+
+    // reference implementation; rounds the upper half of the bits of an f32
+    // to nearest (ties to even), letting carries propagate into the exponent
+    fn from_f32_reference(value: f32) -> u16 {
+        let bits = value.to_bits();
+        let rnd = 0x7fff + ((bits >> 16) & 1);
+        ((bits + rnd) >> 16) as u16
+    }
+
+    #[test]
+    fn test_bfloat16_from_f32() {
+        for (v, expected) in [
+            (0x3f800000, 0x3f80),
+            (0x3f808000, 0x3f80),
+            (0x3f818000, 0x3f82),
+            (0x3f808001, 0x3f81),
+            (0xbf80ffff, 0xbf81),
+            (0x7f7f7fff, 0x7f7f),
+            (0x7f7f8000, 0x7f80),
+            (0xff7fffff, 0xff80),
+            (0x00008000, 0x0000),
+            (0x00018000, 0x0002),
+            (0x007fffff, 0x0080),
+            (0x7fc00001, 0x7fc0),
+        ] {
+            let y = SoftBFloat16::from(f32::from_bits(v));
+            assert_eq!(SoftBFloat16::to_bits(y), expected, "{:08x}", v);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_from_f32() {
+        for i in 0..u32::MAX {
+            let x = f32::from_bits(i);
+            let y = SoftBFloat16::from(x);
+            if x.is_nan() {
+                assert!(SoftBFloat16::is_nan(y), "{}", i);
+            } else {
+                assert_eq!(SoftBFloat16::to_bits(y), from_f32_reference(x), "{}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_from_f64() {
+        let next_up = |v: f64| f64::from_bits(v.to_bits() + 1);
+        let next_down = |v: f64| f64::from_bits(v.to_bits() - 1);
+
+        // conversion of numbers between x and its successor; the successor
+        // of the largest finite number is infinity, which is where rounding
+        // to nearest overflows
+        for i in 0..0x7f80 {
+            let lo = f32::from(SoftBFloat16::from_bits(i)) as f64;
+            let hi = if i == 0x7f7f {
+                2.0_f64.powi(128)
+            } else {
+                f32::from(SoftBFloat16::from_bits(i + 1)) as f64
+            };
+            let mid = (lo + hi) / 2.0;
+            let even = if i & 1 == 0 { i } else { i + 1 };
+            for (value, expected) in [(next_down(mid), i), (mid, even), (next_up(mid), i + 1)] {
+                let y = SoftBFloat16::from(value);
+                assert_eq!(SoftBFloat16::to_bits(y), expected, "{:?}", (i, value));
+                let y = SoftBFloat16::from(-value);
+                assert_eq!(
+                    SoftBFloat16::to_bits(y),
+                    expected | 0x8000,
+                    "{:?}",
+                    (i, value)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_to_f32() {
+        for i in 0..u16::MAX {
+            let x = SoftBFloat16::from_bits(i);
+            let y = f32::from(x);
+            if SoftBFloat16::is_nan(x) {
+                // signaling NANs are quieted
+                assert_eq!(y.to_bits(), (i as u32) << 16 | 1 << 22, "{}", i);
+            } else {
+                assert_eq!(y.to_bits(), (i as u32) << 16, "{}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_from_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftBFloat16::from(x);
+            let y_f = SoftBFloat16::from(f32::from(x));
+            check(y, y_f, (i, 0));
+        }
+    }
+
+    #[test]
+    fn test_all_to_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftBFloat16::from_bits(i);
+            let y = SoftFloat16::from(x);
+            let y_f = SoftFloat16::from(f32::from(x));
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{}", i);
+                assert!(SoftFloat16::is_nan(y_f), "{}", i);
+            } else {
+                assert_eq!(SoftFloat16::to_bits(y), SoftFloat16::to_bits(y_f), "{}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_softfloat16_flags() {
+        let rm = RoundingMode::NearestTiesToEven;
+        let td = TininessDetection::AfterRounding;
+        for (v, expected, expected_flags) in [
+            (0x3f80, 0x3c00, ExceptionFlags::NONE),
+            // 2^16 is beyond the range of binary16
+            (
+                0x4780,
+                0x7c00,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc780,
+                0xfc00,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            // 2^-24 is the smallest denormal number of binary16
+            (0x3380, 0x0001, ExceptionFlags::NONE),
+            (
+                0x3300,
+                0x0000,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3390,
+                0x0001,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (0x7f81, 0x7e08, ExceptionFlags::INVALID),
+        ] {
+            let x = SoftBFloat16::from_bits(v);
+            let (y, flags) = SoftFloat16::from_soft_float_with_flags(x, rm, td);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:04x}", v);
+            assert_eq!(flags, expected_flags, "{:04x}", v);
+        }
+    }
+
+    #[test]
+    fn test_all_sqrt() {
+        for i in 0..u16::MAX {
+            let x = SoftBFloat16::from_bits(i);
+            let y = SoftBFloat16::sqrt(x);
+            let y_f = SoftBFloat16::from(f32::from(x).sqrt());
+            check(y, y_f, (i, 0));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_add() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftBFloat16::from_bits(i);
+                let x1 = SoftBFloat16::from_bits(j);
+                check(
+                    x0 + x1,
+                    SoftBFloat16::from(f32::from(x0) + f32::from(x1)),
+                    (i, j),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_sub() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftBFloat16::from_bits(i);
+                let x1 = SoftBFloat16::from_bits(j);
+                check(
+                    x0 - x1,
+                    SoftBFloat16::from(f32::from(x0) - f32::from(x1)),
+                    (i, j),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_mul() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftBFloat16::from_bits(i);
+                let x1 = SoftBFloat16::from_bits(j);
+                check(
+                    x0 * x1,
+                    SoftBFloat16::from(f32::from(x0) * f32::from(x1)),
+                    (i, j),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_div() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftBFloat16::from_bits(i);
+                let x1 = SoftBFloat16::from_bits(j);
+                check(
+                    x0 / x1,
+                    SoftBFloat16::from(f32::from(x0) / f32::from(x1)),
+                    (i, j),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_cmp() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftBFloat16::from_bits(i);
+                let x1 = SoftBFloat16::from_bits(j);
+                let y = x0.partial_cmp(&x1);
+                let y_f = f32::from(x0).partial_cmp(&f32::from(x1));
+                assert_eq!(y, y_f, "{:?}", (i, j));
+            }
+        }
+    }
+}
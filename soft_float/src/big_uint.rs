@@ -0,0 +1,330 @@
+use std::cmp::Ordering;
+
+// room for the exact values that occur when converting between decimal and
+// the formats with up to 15 exponent bits: the denominator 10^k of a decimal
+// number just above the smallest denormal binary128 number, which has about
+// 11500 significant digits, and the numerator scaled to it take less than
+// 62000 bits
+const LIMBS: usize = 2048;
+
+// unsigned integer of bounded size, stored as little-endian 32-bit limbs of
+// which the first len are used, without leading zero limbs; used for the exact
+// conversion between decimal and binary numbers, where powers of ten quickly
+// exceed U256; a fixed array rather than a Vec, so parsing works in const fns
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BigUint {
+    limbs: [u32; LIMBS],
+    len: usize,
+}
+
+impl BigUint {
+    pub(crate) const fn zero() -> Self {
+        Self {
+            limbs: [0; LIMBS],
+            len: 0,
+        }
+    }
+
+    pub(crate) const fn from_u128(mut v: u128) -> Self {
+        let mut result = Self::zero();
+        while v != 0 {
+            result.push(v as u32);
+            v >>= 32;
+        }
+        result
+    }
+
+    pub(crate) const fn pow10(exponent: u32) -> Self {
+        let mut v = Self::from_u128(1);
+        v.mul_pow10(exponent);
+        v
+    }
+
+    pub(crate) const fn is_zero(&self) -> bool {
+        self.len == 0
+    }
+
+    // number of significant bits
+    pub(crate) const fn bits(&self) -> u32 {
+        if self.len == 0 {
+            0
+        } else {
+            32 * self.len as u32 - self.limbs[self.len - 1].leading_zeros()
+        }
+    }
+
+    // self = self * m + a
+    pub(crate) const fn mul_add_small(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        let mut i = 0;
+        while i < self.len {
+            let v = self.limbs[i] as u64 * m as u64 + carry;
+            self.limbs[i] = v as u32;
+            carry = v >> 32;
+            i += 1;
+        }
+        if carry != 0 {
+            self.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    // self = self * 10^exponent
+    pub(crate) const fn mul_pow10(&mut self, exponent: u32) {
+        let mut i = 0;
+        while i < exponent / 9 {
+            self.mul_add_small(1_000_000_000, 0);
+            i += 1;
+        }
+        self.mul_add_small(10u32.pow(exponent % 9), 0);
+    }
+
+    pub(crate) const fn shl(&self, shift: u32) -> Self {
+        let mut result = Self::zero();
+        if self.is_zero() {
+            return result;
+        }
+        let (limb_shift, bit_shift) = ((shift / 32) as usize, shift % 32);
+        assert!(limb_shift < LIMBS, "BigUint too large");
+        result.len = limb_shift;
+        let mut carry = 0;
+        let mut i = 0;
+        while i < self.len {
+            let v = (self.limbs[i] as u64) << bit_shift;
+            result.push(v as u32 | carry);
+            carry = (v >> 32) as u32;
+            i += 1;
+        }
+        if carry != 0 {
+            result.push(carry);
+        }
+        result
+    }
+
+    // self = self - other; other must not be larger than self
+    pub(crate) const fn sub_assign(&mut self, other: &Self) {
+        assert!(self.compare(other).is_ge());
+        let mut borrow = 0;
+        let mut i = 0;
+        while i < self.len {
+            let subtrahend = if i < other.len { other.limbs[i] } else { 0 };
+            let v = self.limbs[i] as i64 - subtrahend as i64 - borrow;
+            self.limbs[i] = v as u32;
+            borrow = (v < 0) as i64;
+            i += 1;
+        }
+        self.trim();
+    }
+
+    // quotient and remainder of the division; the quotient must fit into
+    // u128, which is all that is needed when producing one significand or
+    // one decimal digit at a time
+    pub(crate) const fn div_rem(self, other: &Self) -> (u128, Self) {
+        assert!(!other.is_zero());
+        let shift = self.bits().saturating_sub(other.bits());
+        let mut remainder = self;
+        let mut quotient = 0;
+        assert!(shift < 128, "quotient too large");
+        let mut subtrahend = other.shl(shift);
+        let mut i = shift + 1;
+        while i > 0 {
+            i -= 1;
+            if remainder.compare(&subtrahend).is_ge() {
+                remainder.sub_assign(&subtrahend);
+                quotient |= 1 << i;
+            }
+            subtrahend = subtrahend.shr(1);
+        }
+        (quotient, remainder)
+    }
+
+    // self = self / d, returning the remainder
+    pub(crate) const fn div_rem_small(&mut self, d: u32) -> u32 {
+        let mut remainder = 0;
+        let mut i = self.len;
+        while i > 0 {
+            i -= 1;
+            let v = (remainder << 32) | self.limbs[i] as u64;
+            self.limbs[i] = (v / d as u64) as u32;
+            remainder = v % d as u64;
+        }
+        self.trim();
+        remainder as u32
+    }
+
+    // decimal digits without leading zeros, nine at a time
+    pub(crate) fn to_decimal_string(&self) -> String {
+        let mut v = self.clone();
+        let mut chunks = Vec::new();
+        while !v.is_zero() {
+            chunks.push(v.div_rem_small(1_000_000_000));
+        }
+        match chunks.split_last() {
+            Some((first, rest)) => rest
+                .iter()
+                .rev()
+                .fold(first.to_string(), |s, chunk| s + &format!("{:09}", chunk)),
+            None => "0".to_string(),
+        }
+    }
+
+    pub(crate) const fn shr(&self, shift: u32) -> Self {
+        let mut result = Self::zero();
+        let (limb_shift, bit_shift) = ((shift / 32) as usize, shift % 32);
+        if limb_shift >= self.len {
+            return result;
+        }
+        let mut i = limb_shift;
+        while i < self.len {
+            let next = if i + 1 < self.len {
+                self.limbs[i + 1] as u64
+            } else {
+                0
+            };
+            result.limbs[i - limb_shift] =
+                (((next << 32) | self.limbs[i] as u64) >> bit_shift) as u32;
+            i += 1;
+        }
+        result.len = self.len - limb_shift;
+        result.trim();
+        result
+    }
+
+    // the most significant `width` bits (at most 128), shifted left if there
+    // are fewer; the least significant bit is ORed with all bits that are cut
+    // off, compare shift_right_sticky
+    pub(crate) const fn to_u128_sticky(&self, width: u32) -> u128 {
+        assert!(width <= 128);
+        let bits = self.bits();
+        if bits <= width {
+            return self.to_u128() << (width - bits);
+        }
+        let shift = bits - width;
+        let top = self.shr(shift);
+        let sticky = top.shl(shift).compare(self).is_ne();
+        top.to_u128() | sticky as u128
+    }
+
+    // the value must fit into u128
+    pub(crate) const fn to_u128(&self) -> u128 {
+        assert!(self.bits() <= 128);
+        let mut v = 0;
+        let mut i = self.len;
+        while i > 0 {
+            i -= 1;
+            v = (v << 32) | self.limbs[i] as u128;
+        }
+        v
+    }
+
+    // Ord::cmp, which is not const
+    pub(crate) const fn compare(&self, other: &Self) -> Ordering {
+        if self.len != other.len {
+            return if self.len < other.len {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+        let mut i = self.len;
+        while i > 0 {
+            i -= 1;
+            if self.limbs[i] != other.limbs[i] {
+                return if self.limbs[i] < other.limbs[i] {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                };
+            }
+        }
+        Ordering::Equal
+    }
+
+    const fn push(&mut self, limb: u32) {
+        assert!(self.len < LIMBS, "BigUint too large");
+        self.limbs[self.len] = limb;
+        self.len += 1;
+    }
+
+    const fn trim(&mut self) {
+        while self.len > 0 && self.limbs[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.compare(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pow10() {
+        assert_eq!(BigUint::pow10(0), BigUint::from_u128(1));
+        assert_eq!(BigUint::pow10(38), BigUint::from_u128(10u128.pow(38)));
+        assert_eq!(BigUint::pow10(40).bits(), 133);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let (q, r) = BigUint::pow10(40).div_rem(&BigUint::pow10(3).shl(3));
+        assert_eq!(q, 10u128.pow(37) / 8);
+        assert!(r.is_zero());
+
+        let (q, r) = BigUint::from_u128(1000).div_rem(&BigUint::from_u128(7));
+        assert_eq!((q, r), (142, BigUint::from_u128(6)));
+    }
+
+    #[test]
+    fn test_to_decimal_string() {
+        assert_eq!(BigUint::zero().to_decimal_string(), "0");
+        assert_eq!(
+            BigUint::from_u128(1_000_000_007).to_decimal_string(),
+            "1000000007"
+        );
+        assert_eq!(
+            BigUint::from_u128(u128::MAX).to_decimal_string(),
+            u128::MAX.to_string()
+        );
+        assert_eq!(
+            BigUint::pow10(50).to_decimal_string(),
+            format!("1{}", "0".repeat(50))
+        );
+        let mut v = BigUint::pow10(30);
+        assert_eq!(v.div_rem_small(7), (10u128.pow(30) % 7) as u32);
+        assert_eq!(v, BigUint::from_u128(10u128.pow(30) / 7));
+    }
+
+    #[test]
+    fn test_to_u128_sticky() {
+        let v = BigUint::from_u128(0b1011_0000);
+        assert_eq!(v.to_u128_sticky(4), 0b1011);
+        assert_eq!(v.to_u128_sticky(10), 0b10_1100_0000);
+        let v = BigUint::from_u128(0b1011_0001);
+        assert_eq!(v.to_u128_sticky(4), 0b1011);
+        assert_eq!(v.to_u128_sticky(3), 0b101);
+        let v = BigUint::from_u128(0b1010_0001);
+        assert_eq!(v.to_u128_sticky(4), 0b1011);
+
+        // wider than 128 bits
+        let v = BigUint::from_u128(u128::MAX).shl(7);
+        assert_eq!(v.to_u128_sticky(128), u128::MAX);
+        assert_eq!(v.to_u128_sticky(125), u128::MAX >> 3);
+        assert_eq!(v.shr(7), BigUint::from_u128(u128::MAX));
+        let v = BigUint::from_u128(u128::MAX - 1).shl(40);
+        assert_eq!(v.to_u128_sticky(128), u128::MAX - 1);
+        assert_eq!(v.to_u128_sticky(127), u128::MAX >> 1);
+        assert_eq!(v.to_u128_sticky(126), u128::MAX >> 2);
+    }
+}
@@ -1,29 +1,253 @@
 use std::{
     env,
-    fmt::Debug,
     io::{self, BufRead},
-    ops::{Add, Div, Mul, Rem, Sub},
+    sync::OnceLock,
 };
 
-use soft_float::MulAdd;
-use soft_float::RoundTiesEven;
+use soft_float::ExceptionFlags;
+use soft_float::ExtFloat80;
+use soft_float::IntegerOverflow;
+use soft_float::RoundingMode;
+use soft_float::RoundingPrecision;
+use soft_float::SoftFloat;
+use soft_float::SoftFloat128;
 use soft_float::SoftFloat16;
+use soft_float::SoftFloat32;
+use soft_float::SoftFloat64;
+use soft_float::Storage;
+use soft_float::TininessDetection;
+
+// only affects the arithmetic of ExtFloat80, so it is not passed around like
+// the rounding mode
+static ROUNDING_PRECISION: OnceLock<RoundingPrecision> = OnceLock::new();
+
+// only affects rounding and conversions to integers, whether inexact results
+// raise the inexact exception
+static EXACT: OnceLock<bool> = OnceLock::new();
 
 fn main() {
     let args = env::args().collect::<Vec<String>>();
-    assert!(args.len() == 2);
+    assert!(args.len() >= 2);
     let mut split = args[1].split('_');
     let type_in = split.next().unwrap();
     let op = split.next().unwrap();
     let type_out = split.next().unwrap_or(type_in);
 
-    match (type_in, type_out) {
-        ("i32", "softfloat16") => testfloat::<i32, SoftFloat16>(op),
-        ("softfloat16", "i32") => testfloat::<SoftFloat16, i32>(op),
-        ("f32", "softfloat16") => testfloat::<f32, SoftFloat16>(op),
-        ("softfloat16", "f32") => testfloat::<SoftFloat16, f32>(op),
-        ("softfloat16", "softfloat16") => testfloat::<SoftFloat16, SoftFloat16>(op),
-        _ => todo!(),
+    // same options as testfloat_gen and testfloat_ver
+    let mut rounding_mode = RoundingMode::default();
+    let mut tininess_detection = TininessDetection::default();
+    for arg in &args[2..] {
+        match arg.as_str() {
+            "-rnear_even" => rounding_mode = RoundingMode::NearestTiesToEven,
+            "-rnear_maxMag" => rounding_mode = RoundingMode::NearestTiesToAway,
+            "-rminMag" => rounding_mode = RoundingMode::TowardZero,
+            "-rmax" => rounding_mode = RoundingMode::TowardPositive,
+            "-rmin" => rounding_mode = RoundingMode::TowardNegative,
+            "-rodd" => rounding_mode = RoundingMode::ToOdd,
+            "-tininessbefore" => tininess_detection = TininessDetection::BeforeRounding,
+            "-tininessafter" => tininess_detection = TininessDetection::AfterRounding,
+            "-precision32" => ROUNDING_PRECISION.set(RoundingPrecision::Single).unwrap(),
+            "-precision64" => ROUNDING_PRECISION.set(RoundingPrecision::Double).unwrap(),
+            "-precision80" => ROUNDING_PRECISION.set(RoundingPrecision::Extended).unwrap(),
+            "-exact" => EXACT.set(true).unwrap(),
+            "-notexact" => EXACT.set(false).unwrap(),
+            s => panic!("unknown option {}", s),
+        }
+    }
+
+    match op {
+        "to" => match (type_in, type_out) {
+            ("i32", "softfloat16") => {
+                testfloat_convert::<i32, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "i32") => {
+                testfloat_convert::<SoftFloat16, i32>(rounding_mode, tininess_detection)
+            }
+            ("f32", "softfloat16") => {
+                testfloat_convert::<f32, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "f32") => {
+                testfloat_convert::<SoftFloat16, f32>(rounding_mode, tininess_detection)
+            }
+            ("f64", "softfloat16") => {
+                testfloat_convert::<f64, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "f64") => {
+                testfloat_convert::<SoftFloat16, f64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "softfloat32") => {
+                testfloat_convert::<SoftFloat16, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "softfloat64") => {
+                testfloat_convert::<SoftFloat16, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("i32", "softfloat32") => {
+                testfloat_convert::<i32, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "i32") => {
+                testfloat_convert::<SoftFloat32, i32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "softfloat16") => {
+                testfloat_convert::<SoftFloat32, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "softfloat64") => {
+                testfloat_convert::<SoftFloat32, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("i32", "softfloat64") => {
+                testfloat_convert::<i32, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "i32") => {
+                testfloat_convert::<SoftFloat64, i32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "softfloat16") => {
+                testfloat_convert::<SoftFloat64, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "softfloat32") => {
+                testfloat_convert::<SoftFloat64, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "softfloat128") => {
+                testfloat_convert::<SoftFloat16, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "softfloat128") => {
+                testfloat_convert::<SoftFloat32, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "softfloat128") => {
+                testfloat_convert::<SoftFloat64, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("i32", "softfloat128") => {
+                testfloat_convert::<i32, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "i32") => {
+                testfloat_convert::<SoftFloat128, i32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "softfloat16") => {
+                testfloat_convert::<SoftFloat128, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "softfloat32") => {
+                testfloat_convert::<SoftFloat128, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "softfloat64") => {
+                testfloat_convert::<SoftFloat128, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("ui32", "softfloat16") => {
+                testfloat_convert::<u32, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "ui32") => {
+                testfloat_convert::<SoftFloat16, u32>(rounding_mode, tininess_detection)
+            }
+            ("i64", "softfloat16") => {
+                testfloat_convert::<i64, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "i64") => {
+                testfloat_convert::<SoftFloat16, i64>(rounding_mode, tininess_detection)
+            }
+            ("ui64", "softfloat16") => {
+                testfloat_convert::<u64, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "ui64") => {
+                testfloat_convert::<SoftFloat16, u64>(rounding_mode, tininess_detection)
+            }
+            ("ui32", "softfloat32") => {
+                testfloat_convert::<u32, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "ui32") => {
+                testfloat_convert::<SoftFloat32, u32>(rounding_mode, tininess_detection)
+            }
+            ("i64", "softfloat32") => {
+                testfloat_convert::<i64, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "i64") => {
+                testfloat_convert::<SoftFloat32, i64>(rounding_mode, tininess_detection)
+            }
+            ("ui64", "softfloat32") => {
+                testfloat_convert::<u64, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "ui64") => {
+                testfloat_convert::<SoftFloat32, u64>(rounding_mode, tininess_detection)
+            }
+            ("ui32", "softfloat64") => {
+                testfloat_convert::<u32, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "ui32") => {
+                testfloat_convert::<SoftFloat64, u32>(rounding_mode, tininess_detection)
+            }
+            ("i64", "softfloat64") => {
+                testfloat_convert::<i64, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "i64") => {
+                testfloat_convert::<SoftFloat64, i64>(rounding_mode, tininess_detection)
+            }
+            ("ui64", "softfloat64") => {
+                testfloat_convert::<u64, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "ui64") => {
+                testfloat_convert::<SoftFloat64, u64>(rounding_mode, tininess_detection)
+            }
+            ("ui32", "softfloat128") => {
+                testfloat_convert::<u32, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "ui32") => {
+                testfloat_convert::<SoftFloat128, u32>(rounding_mode, tininess_detection)
+            }
+            ("i64", "softfloat128") => {
+                testfloat_convert::<i64, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "i64") => {
+                testfloat_convert::<SoftFloat128, i64>(rounding_mode, tininess_detection)
+            }
+            ("ui64", "softfloat128") => {
+                testfloat_convert::<u64, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "ui64") => {
+                testfloat_convert::<SoftFloat128, u64>(rounding_mode, tininess_detection)
+            }
+            ("i32", "extfloat80") => {
+                testfloat_convert::<i32, ExtFloat80>(rounding_mode, tininess_detection)
+            }
+            ("extfloat80", "i32") => {
+                testfloat_convert::<ExtFloat80, i32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat16", "extfloat80") => {
+                testfloat_convert::<SoftFloat16, ExtFloat80>(rounding_mode, tininess_detection)
+            }
+            ("extfloat80", "softfloat16") => {
+                testfloat_convert::<ExtFloat80, SoftFloat16>(rounding_mode, tininess_detection)
+            }
+            ("softfloat32", "extfloat80") => {
+                testfloat_convert::<SoftFloat32, ExtFloat80>(rounding_mode, tininess_detection)
+            }
+            ("extfloat80", "softfloat32") => {
+                testfloat_convert::<ExtFloat80, SoftFloat32>(rounding_mode, tininess_detection)
+            }
+            ("softfloat64", "extfloat80") => {
+                testfloat_convert::<SoftFloat64, ExtFloat80>(rounding_mode, tininess_detection)
+            }
+            ("extfloat80", "softfloat64") => {
+                testfloat_convert::<ExtFloat80, SoftFloat64>(rounding_mode, tininess_detection)
+            }
+            ("softfloat128", "extfloat80") => {
+                testfloat_convert::<SoftFloat128, ExtFloat80>(rounding_mode, tininess_detection)
+            }
+            ("extfloat80", "softfloat128") => {
+                testfloat_convert::<ExtFloat80, SoftFloat128>(rounding_mode, tininess_detection)
+            }
+            _ => todo!(),
+        },
+        "mulAdd" => match type_in {
+            "softfloat16" => testfloat_mul_add::<SoftFloat16>(rounding_mode, tininess_detection),
+            "softfloat32" => testfloat_mul_add::<SoftFloat32>(rounding_mode, tininess_detection),
+            "softfloat64" => testfloat_mul_add::<SoftFloat64>(rounding_mode, tininess_detection),
+            "softfloat128" => testfloat_mul_add::<SoftFloat128>(rounding_mode, tininess_detection),
+            _ => todo!(),
+        },
+        _ => match type_in {
+            "softfloat16" => testfloat::<SoftFloat16>(op, rounding_mode, tininess_detection),
+            "softfloat32" => testfloat::<SoftFloat32>(op, rounding_mode, tininess_detection),
+            "softfloat64" => testfloat::<SoftFloat64>(op, rounding_mode, tininess_detection),
+            "softfloat128" => testfloat::<SoftFloat128>(op, rounding_mode, tininess_detection),
+            "extfloat80" => testfloat::<ExtFloat80>(op, rounding_mode, tininess_detection),
+            _ => todo!(),
+        },
     };
 }
 
@@ -42,6 +266,36 @@ impl ConvertHexStr for i32 {
     }
 }
 
+impl ConvertHexStr for u32 {
+    fn hex_str_to_float(s: &str) -> Self {
+        u32::from_str_radix(s, 16).expect("should be hex representation of u32")
+    }
+
+    fn float_to_hex_str(v: Self) -> String {
+        format!("{:08x}", v).to_uppercase()
+    }
+}
+
+impl ConvertHexStr for i64 {
+    fn hex_str_to_float(s: &str) -> Self {
+        u64::from_str_radix(s, 16).expect("should be hex representation of i64") as i64
+    }
+
+    fn float_to_hex_str(v: Self) -> String {
+        format!("{:016x}", v).to_uppercase()
+    }
+}
+
+impl ConvertHexStr for u64 {
+    fn hex_str_to_float(s: &str) -> Self {
+        u64::from_str_radix(s, 16).expect("should be hex representation of u64")
+    }
+
+    fn float_to_hex_str(v: Self) -> String {
+        format!("{:016x}", v).to_uppercase()
+    }
+}
+
 impl ConvertHexStr for f32 {
     fn hex_str_to_float(s: &str) -> Self {
         f32::from_bits(u32::from_str_radix(s, 16).expect("should be hex representation of u32"))
@@ -62,194 +316,517 @@ impl ConvertHexStr for f64 {
     }
 }
 
-impl ConvertHexStr for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> ConvertHexStr
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn hex_str_to_float(s: &str) -> Self {
-        Self::from_bits(u16::from_str_radix(s, 16).expect("should be hex representation of u16"))
+        Self::from_bits(T::from_u128(
+            u128::from_str_radix(s, 16).expect("should be hex representation of storage type"),
+        ))
     }
 
     fn float_to_hex_str(v: Self) -> String {
-        format!("{:04x}", SoftFloat16::to_bits(v)).to_uppercase()
-    }
-}
-
-fn testfloat<
-    T_IN: ConvertHexStr
-        + Add<Output = T_IN>
-        + Sub<Output = T_IN>
-        + Mul<Output = T_IN>
-        + MulAdd
-        + Div<Output = T_IN>
-        + Rem<Output = T_IN>
-        + Debug
-        + Copy
-        + RoundTiesEven
-        + PartialEq
-        + PartialOrd,
-    T_OUT: ConvertHexStr + From<T_IN> + Debug + Copy,
->(
+        let width = T::BITS as usize / 4;
+        format!("{:0width$x}", T::to_u128(Self::to_bits(v))).to_uppercase()
+    }
+}
+
+// written by TestFloat with a dot between sign and exponent and the
+// significand
+impl ConvertHexStr for ExtFloat80 {
+    fn hex_str_to_float(s: &str) -> Self {
+        Self::from_bits(
+            u128::from_str_radix(&s.replace('.', ""), 16)
+                .expect("should be hex representation of ExtFloat80"),
+        )
+    }
+
+    fn float_to_hex_str(v: Self) -> String {
+        let bits = Self::to_bits(v);
+        format!("{:04x}.{:016x}", bits >> 64, bits as u64).to_uppercase()
+    }
+}
+
+// operations under test, reporting exception flags alongside the result
+trait Arithmetic: Sized {
+    fn add(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+    fn sub(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+    fn mul(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+    fn div(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+    fn sqrt(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags);
+    fn rem(v0: Self, v1: Self) -> (Self, ExceptionFlags);
+    fn round(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags);
+    fn eq(v0: Self, v1: Self) -> (bool, ExceptionFlags);
+    fn le(v0: Self, v1: Self) -> (bool, ExceptionFlags);
+    fn lt(v0: Self, v1: Self) -> (bool, ExceptionFlags);
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Arithmetic
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn add(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::add_with_flags(v0, v1, rounding_mode, tininess_detection)
+    }
+
+    fn sub(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::sub_with_flags(v0, v1, rounding_mode, tininess_detection)
+    }
+
+    fn mul(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::mul_with_flags(v0, v1, rounding_mode, tininess_detection)
+    }
+
+    fn div(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::div_with_flags(v0, v1, rounding_mode, tininess_detection)
+    }
+
+    fn sqrt(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        Self::sqrt_with_flags(v, rounding_mode)
+    }
+
+    fn rem(v0: Self, v1: Self) -> (Self, ExceptionFlags) {
+        Self::rem_with_flags(v0, v1)
+    }
+
+    fn round(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        if exact() {
+            Self::round_to_integral_exact_with_flags(v, rounding_mode)
+        } else {
+            Self::round_to_integral_with_flags(v, rounding_mode)
+        }
+    }
+
+    fn eq(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::eq_with_flags(v0, v1)
+    }
+
+    fn le(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::le_with_flags(v0, v1)
+    }
+
+    fn lt(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::lt_with_flags(v0, v1)
+    }
+}
+
+impl Arithmetic for ExtFloat80 {
+    fn add(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::add_with_flags(
+            v0,
+            v1,
+            rounding_precision(),
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+
+    fn sub(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::sub_with_flags(
+            v0,
+            v1,
+            rounding_precision(),
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+
+    fn mul(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::mul_with_flags(
+            v0,
+            v1,
+            rounding_precision(),
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+
+    fn div(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::div_with_flags(
+            v0,
+            v1,
+            rounding_precision(),
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+
+    fn sqrt(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        Self::sqrt_with_flags(v, rounding_precision(), rounding_mode)
+    }
+
+    fn rem(v0: Self, v1: Self) -> (Self, ExceptionFlags) {
+        Self::rem_with_flags(v0, v1)
+    }
+
+    fn round(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        if exact() {
+            Self::round_to_integral_exact_with_flags(v, rounding_mode)
+        } else {
+            Self::round_to_integral_with_flags(v, rounding_mode)
+        }
+    }
+
+    fn eq(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::eq_with_flags(v0, v1)
+    }
+
+    fn le(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::le_with_flags(v0, v1)
+    }
+
+    fn lt(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Self::lt_with_flags(v0, v1)
+    }
+}
+
+// separate from Arithmetic, TestFloat has no fused multiply-add for extF80
+trait FusedMulAdd: Sized {
+    fn mul_add(
+        v0: Self,
+        v1: Self,
+        v2: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> FusedMulAdd
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn mul_add(
+        v0: Self,
+        v1: Self,
+        v2: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::mul_add_with_flags(v0, v1, v2, rounding_mode, tininess_detection)
+    }
+}
+
+fn rounding_precision() -> RoundingPrecision {
+    ROUNDING_PRECISION.get().copied().unwrap_or_default()
+}
+
+// conversions under test, reporting exception flags alongside the result
+trait Convert<T>: Sized {
+    fn convert(
+        v: T,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags);
+}
+
+impl<
+        T: Storage,
+        const EXP_BITS: u32,
+        const MANT_BITS: u32,
+        U: Storage,
+        const EXP_BITS1: u32,
+        const MANT_BITS1: u32,
+    > Convert<SoftFloat<U, EXP_BITS1, MANT_BITS1>> for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn convert(
+        v: SoftFloat<U, EXP_BITS1, MANT_BITS1>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_soft_float_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+// invalid conversions return the same values as TestFloat
+macro_rules! impl_convert_integer {
+    ($($t:ty),*) => {
+        $(
+            impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Convert<$t>
+                for SoftFloat<T, EXP_BITS, MANT_BITS>
+            {
+                fn convert(
+                    v: $t,
+                    rounding_mode: RoundingMode,
+                    _tininess_detection: TininessDetection,
+                ) -> (Self, ExceptionFlags) {
+                    Self::from_int_with_flags(v, rounding_mode)
+                }
+            }
+
+            impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>
+                Convert<SoftFloat<T, EXP_BITS, MANT_BITS>> for $t
+            {
+                fn convert(
+                    v: SoftFloat<T, EXP_BITS, MANT_BITS>,
+                    rounding_mode: RoundingMode,
+                    _tininess_detection: TininessDetection,
+                ) -> (Self, ExceptionFlags) {
+                    SoftFloat::to_int_with_flags(
+                        v,
+                        rounding_mode,
+                        exact(),
+                        IntegerOverflow::Sentinel,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_convert_integer!(i32, u32, i64, u64);
+
+fn exact() -> bool {
+    EXACT.get().copied().unwrap_or_default()
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Convert<f32>
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn convert(
+        v: f32,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_f32_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>
+    Convert<SoftFloat<T, EXP_BITS, MANT_BITS>> for f32
+{
+    fn convert(
+        v: SoftFloat<T, EXP_BITS, MANT_BITS>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        SoftFloat::to_f32_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Convert<f64>
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn convert(
+        v: f64,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_f64_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>
+    Convert<SoftFloat<T, EXP_BITS, MANT_BITS>> for f64
+{
+    fn convert(
+        v: SoftFloat<T, EXP_BITS, MANT_BITS>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        SoftFloat::to_f64_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>
+    Convert<SoftFloat<T, EXP_BITS, MANT_BITS>> for ExtFloat80
+{
+    fn convert(
+        v: SoftFloat<T, EXP_BITS, MANT_BITS>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_soft_float_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Convert<ExtFloat80>
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn convert(
+        v: ExtFloat80,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        ExtFloat80::to_soft_float_with_flags(v, rounding_mode, tininess_detection)
+    }
+}
+
+// conversion is always exact
+impl Convert<i32> for ExtFloat80 {
+    fn convert(
+        v: i32,
+        _rounding_mode: RoundingMode,
+        _tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        (Self::from(v), ExceptionFlags::NONE)
+    }
+}
+
+impl Convert<ExtFloat80> for i32 {
+    fn convert(
+        v: ExtFloat80,
+        _rounding_mode: RoundingMode,
+        _tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        ExtFloat80::to_i32_with_flags(v)
+    }
+}
+
+fn testfloat<T: ConvertHexStr + Arithmetic + Copy>(
     op: &str,
+    rounding_mode: RoundingMode,
+    tininess_detection: TininessDetection,
 ) {
     for line in io::stdin().lock().lines() {
         // parse line
         let line = line.expect("should be able to read line from stdin");
         let words = line.split_whitespace().collect::<Vec<&str>>();
 
-        // compute using internal arithmetic
-        // NOTE this code does not check exception flags!!!
-        let s = match op {
-            "add" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = T_IN::add(value0, value1);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            "sub" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = T_IN::sub(value0, value1);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            "mul" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = T_IN::mul(value0, value1);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            "mulAdd" => {
-                let (value0, value1, value2) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                    T_IN::hex_str_to_float(words[2]),
-                );
-                let result = T_IN::mul_add(value0, value1, value2);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            "div" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = T_IN::div(value0, value1);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            // // sqrt
-            // // rem
-            // // eq, le, lt
-            "eq" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = value0 == value1;
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    if result { 1 } else { 0 },
-                    words[3]
-                )
-            }
-            "le" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = value0 <= value1;
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    if result { 1 } else { 0 },
-                    words[3]
-                )
-            }
-            "lt" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = value0 < value1;
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    if result { 1 } else { 0 },
-                    words[3]
-                )
-            }
-            "rem" => {
-                let (value0, value1) = (
-                    T_IN::hex_str_to_float(words[0]),
-                    T_IN::hex_str_to_float(words[1]),
-                );
-                let result = T_IN::rem(value0, value1);
-                format!(
-                    "{} {} {} {}",
-                    words[0],
-                    words[1],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[3]
-                )
-            }
-            "round" => {
-                let value = T_IN::hex_str_to_float(words[0]);
-                let result = T_IN::round_ties_even(value);
-                format!(
-                    "{} {} {}",
-                    words[0],
-                    T_OUT::float_to_hex_str(T_OUT::from(result)),
-                    words[2]
-                )
-            }
-            "to" => {
-                let value = T_IN::hex_str_to_float(words[0]);
-                let result = T_OUT::from(value);
-                format!(
-                    "{} {} {}",
-                    words[0],
-                    T_OUT::float_to_hex_str(result),
-                    words[2]
-                )
+        // compute using internal arithmetic; the expected result and
+        // exception flags in the input are replaced by our own
+        let (inputs, result, flags) = match op {
+            "add" | "sub" | "mul" | "div" | "rem" => {
+                let (value0, value1) =
+                    (T::hex_str_to_float(words[0]), T::hex_str_to_float(words[1]));
+                let (result, flags) = match op {
+                    "add" => T::add(value0, value1, rounding_mode, tininess_detection),
+                    "sub" => T::sub(value0, value1, rounding_mode, tininess_detection),
+                    "mul" => T::mul(value0, value1, rounding_mode, tininess_detection),
+                    "div" => T::div(value0, value1, rounding_mode, tininess_detection),
+                    "rem" => T::rem(value0, value1),
+                    _ => unreachable!(),
+                };
+                (&words[0..2], T::float_to_hex_str(result), flags)
+            }
+            "sqrt" | "round" => {
+                let value = T::hex_str_to_float(words[0]);
+                let (result, flags) = match op {
+                    "sqrt" => T::sqrt(value, rounding_mode),
+                    "round" => T::round(value, rounding_mode),
+                    _ => unreachable!(),
+                };
+                (&words[0..1], T::float_to_hex_str(result), flags)
+            }
+            "eq" | "le" | "lt" => {
+                let (value0, value1) =
+                    (T::hex_str_to_float(words[0]), T::hex_str_to_float(words[1]));
+                let (result, flags) = match op {
+                    "eq" => T::eq(value0, value1),
+                    "le" => T::le(value0, value1),
+                    "lt" => T::lt(value0, value1),
+                    _ => unreachable!(),
+                };
+                (&words[0..2], (result as u8).to_string(), flags)
             }
             _ => todo!(),
         };
 
-        println!("{}", s);
+        println!(
+            "{} {} {:02x}",
+            inputs.join(" "),
+            result,
+            ExceptionFlags::to_bits(flags)
+        );
+    }
+}
+
+fn testfloat_mul_add<T: ConvertHexStr + FusedMulAdd + Copy>(
+    rounding_mode: RoundingMode,
+    tininess_detection: TininessDetection,
+) {
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("should be able to read line from stdin");
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+
+        let (value0, value1, value2) = (
+            T::hex_str_to_float(words[0]),
+            T::hex_str_to_float(words[1]),
+            T::hex_str_to_float(words[2]),
+        );
+        let (result, flags) = T::mul_add(value0, value1, value2, rounding_mode, tininess_detection);
+        println!(
+            "{} {} {:02x}",
+            words[0..3].join(" "),
+            T::float_to_hex_str(result),
+            ExceptionFlags::to_bits(flags)
+        );
+    }
+}
+
+// conversions only need to parse the input, not to compute with it
+fn testfloat_convert<TIn: ConvertHexStr + Copy, TOut: ConvertHexStr + Convert<TIn>>(
+    rounding_mode: RoundingMode,
+    tininess_detection: TininessDetection,
+) {
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("should be able to read line from stdin");
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+
+        let value = TIn::hex_str_to_float(words[0]);
+        let (result, flags) = TOut::convert(value, rounding_mode, tininess_detection);
+        println!(
+            "{} {} {:02x}",
+            words[0],
+            TOut::float_to_hex_str(result),
+            ExceptionFlags::to_bits(flags)
+        );
     }
 }
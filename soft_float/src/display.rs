@@ -0,0 +1,913 @@
+use std::fmt::{self, Debug, Display, LowerExp, LowerHex, UpperExp, UpperHex};
+
+use crate::big_uint::BigUint;
+use crate::format::Format;
+use crate::{RoundingMode, SoftFloat, Storage};
+
+// prints the shortest decimal number that parses back to the same value, in
+// positional notation like f32 does, e.g., "0.1", "65500", or "-0"; with a
+// precision, the exact value is rounded to that many fractional digits (ties
+// to even)
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Display
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = match f.precision() {
+            Some(precision) => Self::decimal(*self, |sign, v| {
+                Format::<EXP_BITS, MANT_BITS>::to_fixed(sign, v, precision, RoundingMode::default())
+            }),
+            None => Self::decimal(*self, |_, v| Format::<EXP_BITS, MANT_BITS>::to_shortest(v)),
+        };
+        Self::pad(*self, f, decimal)
+    }
+}
+
+// scientific notation like f32, e.g., "1.5e-3"; shortest unless a precision
+// is given
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> LowerExp
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = Self::decimal(*self, |sign, v| {
+            Format::<EXP_BITS, MANT_BITS>::to_exponential(
+                sign,
+                v,
+                f.precision(),
+                RoundingMode::default(),
+                'e',
+            )
+        });
+        Self::pad(*self, f, decimal)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> UpperExp
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = Self::decimal(*self, |sign, v| {
+            Format::<EXP_BITS, MANT_BITS>::to_exponential(
+                sign,
+                v,
+                f.precision(),
+                RoundingMode::default(),
+                'E',
+            )
+        });
+        Self::pad(*self, f, decimal)
+    }
+}
+
+// hexadecimal significand and binary exponent like C99's "%a" (glibc), e.g.,
+// "0x1.ffcp+15", or "0x0.004p-14" for subnormals; the fraction bits are
+// padded to full hexadecimal digits, trailing zero digits are omitted
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> LowerHex
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = Self::decimal(*self, |_, v| Format::<EXP_BITS, MANT_BITS>::to_hex(v));
+        Self::pad(*self, f, decimal)
+    }
+}
+
+// like "%A", e.g., "0X1.FFCP+15"
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> UpperHex
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimal = Self::decimal(*self, |_, v| {
+            Format::<EXP_BITS, MANT_BITS>::to_hex(v).to_ascii_uppercase()
+        });
+        Self::pad(*self, f, decimal)
+    }
+}
+
+// shows value and encoding, e.g., "SoftFloat(1, 0x3c00)"
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Debug
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = (Format::<EXP_BITS, MANT_BITS>::BITS as usize).div_ceil(4) + 2;
+        f.debug_tuple("SoftFloat")
+            .field(&format_args!("{}", self))
+            .field(&format_args!("{:#0width$x}", Self::raw(*self)))
+            .finish()
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // v with the given number of fractional digits, e.g., "-0.100" for
+    // precision 3
+    pub fn to_fixed_with_rounding(
+        v: Self,
+        precision: usize,
+        rounding_mode: RoundingMode,
+    ) -> String {
+        let (is_nonnegative, s) = Self::decimal(v, |sign, v| {
+            Format::<EXP_BITS, MANT_BITS>::to_fixed(sign, v, precision, rounding_mode)
+        });
+        Self::with_sign(is_nonnegative, s)
+    }
+
+    // v with the given number of digits after the leading one, e.g.,
+    // "1.000e-1" for precision 3
+    pub fn to_exponential_with_rounding(
+        v: Self,
+        precision: usize,
+        rounding_mode: RoundingMode,
+    ) -> String {
+        let (is_nonnegative, s) = Self::decimal(v, |sign, v| {
+            Format::<EXP_BITS, MANT_BITS>::to_exponential(
+                sign,
+                v,
+                Some(precision),
+                rounding_mode,
+                'e',
+            )
+        });
+        Self::with_sign(is_nonnegative, s)
+    }
+
+    // all digits of v, which is a dyadic rational and hence has a finite
+    // decimal expansion, e.g., "0.000000059604644775390625" for the smallest
+    // subnormal SoftFloat16
+    pub fn to_exact_decimal(v: Self) -> String {
+        let (is_nonnegative, s) = Self::decimal(v, |_, v| {
+            let (digits, exponent) = Format::<EXP_BITS, MANT_BITS>::to_exact_decimal(v);
+            let len = digits.len() as i32;
+            positional(&digits, exponent + len - 1)
+        });
+        Self::with_sign(is_nonnegative, s)
+    }
+
+    // handles NaNs and infinities, and the sign of all other numbers, whose
+    // magnitude is formatted by finite
+    fn decimal(v: Self, finite: impl FnOnce(u32, u128) -> String) -> (bool, String) {
+        let v = Self::raw(v);
+        let (sign, exponent, _) = Format::<EXP_BITS, MANT_BITS>::unpack(v);
+        if Format::<EXP_BITS, MANT_BITS>::is_nan(v) {
+            (true, "NaN".to_string())
+        } else if exponent == Format::<EXP_BITS, MANT_BITS>::EXPONENT_MASK {
+            (sign == 0, "inf".to_string())
+        } else {
+            (sign == 0, finite(sign, v))
+        }
+    }
+
+    // NaNs are printed without sign, even with "{:+}", and padded ignoring the
+    // precision, as f32 does
+    fn pad(
+        v: Self,
+        f: &mut fmt::Formatter<'_>,
+        (is_nonnegative, s): (bool, String),
+    ) -> fmt::Result {
+        if !Self::is_nan(v) {
+            return f.pad_integral(is_nonnegative, "", &s);
+        }
+        let padding = f.width().unwrap_or(0).saturating_sub(s.len());
+        let (before, after) = match f.align() {
+            _ if f.sign_aware_zero_pad() => (padding, 0),
+            Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Center) => (padding / 2, padding - padding / 2),
+            _ => (padding, 0),
+        };
+        let fill = if f.sign_aware_zero_pad() {
+            '0'
+        } else {
+            f.fill()
+        }
+        .to_string();
+        write!(f, "{}{}{}", fill.repeat(before), s, fill.repeat(after))
+    }
+
+    fn with_sign(is_nonnegative: bool, s: String) -> String {
+        if is_nonnegative {
+            s
+        } else {
+            format!("-{}", s)
+        }
+    }
+}
+
+// places the decimal point into digits, which represent
+// d.ddd * 10^exponent
+fn positional(digits: &str, exponent: i32) -> String {
+    let len = digits.len() as i32;
+    if exponent < 0 {
+        format!("0.{}{}", "0".repeat((-exponent - 1) as usize), digits)
+    } else if exponent + 1 >= len {
+        format!("{}{}", digits, "0".repeat((exponent + 1 - len) as usize))
+    } else {
+        let (integer, fraction) = digits.split_at(exponent as usize + 1);
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+// rounds digits * 10^exponent, with the given sign, to a multiple of
+// 10^position, whose digits are returned (without leading zeros)
+fn round_decimal(
+    sign: u32,
+    digits: &str,
+    exponent: i32,
+    position: i32,
+    rounding_mode: RoundingMode,
+) -> String {
+    if position <= exponent {
+        return format!("{}{}", digits, "0".repeat((exponent - position) as usize));
+    }
+
+    // keep at least one (possibly zero) digit
+    let cut = (position - exponent) as usize;
+    let digits = format!(
+        "{}{}",
+        "0".repeat((cut + 1).saturating_sub(digits.len())),
+        digits
+    );
+    let (kept, dropped) = digits.split_at(digits.len() - cut);
+
+    // like in binary, guard is set if at least half a unit in the last place
+    // is cut off, sticky if that is not exactly half or zero
+    let first = dropped.as_bytes()[0] - b'0';
+    let guard = first >= 5;
+    let sticky = !first.is_multiple_of(5) || dropped[1..].bytes().any(|c| c != b'0');
+    let mut kept = kept.as_bytes().to_vec();
+    let lsb = (kept[kept.len() - 1] - b'0') as u32 & 1;
+    if rounding_mode.round_up(sign, lsb, guard, sticky) {
+        let mut i = kept.len();
+        loop {
+            if i == 0 {
+                kept.insert(0, b'1');
+                break;
+            }
+            i -= 1;
+            if kept[i] == b'9' {
+                kept[i] = b'0';
+            } else {
+                kept[i] += 1;
+                break;
+            }
+        }
+    }
+
+    let kept = String::from_utf8(kept).unwrap();
+    match kept.trim_start_matches('0') {
+        "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // the finite v (ignoring its sign) is significand * 2^(exponent - BIAS -
+    // MANT_BITS); subnormals are not normalized, as their neighbors are as
+    // close as the ones of the smallest normal numbers
+    fn unpack_finite(v: u128) -> (i32, u128) {
+        let (_, exponent, significand) = Self::unpack(v);
+        if exponent == 0 {
+            (1, significand)
+        } else {
+            (exponent, significand | Self::IMPLICIT_BIT)
+        }
+    }
+
+    // exact value of the finite v (ignoring its sign) as digits d without
+    // trailing zeros and exponent e, meaning d * 10^e; a fraction m / 2^k is
+    // m * 5^k / 10^k
+    pub(crate) fn to_exact_decimal(v: u128) -> (String, i32) {
+        let (exponent, significand) = Self::unpack_finite(v);
+        if significand == 0 {
+            return ("0".to_string(), 0);
+        }
+        let binary_exponent = exponent - Self::BIAS - MANT_BITS as i32;
+        let (digits, exponent) = if binary_exponent >= 0 {
+            (
+                BigUint::from_u128(significand).shl(binary_exponent as u32),
+                0,
+            )
+        } else {
+            let mut digits = BigUint::from_u128(significand);
+            digits.mul_pow10(-binary_exponent as u32);
+            (digits.shr(-binary_exponent as u32), binary_exponent)
+        };
+        let digits = digits.to_decimal_string();
+        let trimmed = digits.trim_end_matches('0');
+        (
+            trimmed.to_string(),
+            exponent + (digits.len() - trimmed.len()) as i32,
+        )
+    }
+
+    // magnitude of the finite v with the given number of fractional digits
+    fn to_fixed(sign: u32, v: u128, precision: usize, rounding_mode: RoundingMode) -> String {
+        let (digits, exponent) = Self::to_exact_decimal(v);
+        let digits = round_decimal(sign, &digits, exponent, -(precision as i32), rounding_mode);
+        if precision == 0 {
+            return digits;
+        }
+        let digits = format!(
+            "{}{}",
+            "0".repeat((precision + 1).saturating_sub(digits.len())),
+            digits
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - precision);
+        format!("{}.{}", integer, fraction)
+    }
+
+    // magnitude of the finite v in scientific notation, shortest without a
+    // precision
+    fn to_exponential(
+        sign: u32,
+        v: u128,
+        precision: Option<usize>,
+        rounding_mode: RoundingMode,
+        exponent_char: char,
+    ) -> String {
+        let (digits, exponent) = match precision {
+            Some(precision) => {
+                // exponent of the leading digit
+                let (digits, exponent) = Self::to_exact_decimal(v);
+                let leading = exponent + digits.len() as i32 - 1;
+                let position = leading - precision as i32;
+                let mut digits = round_decimal(sign, &digits, exponent, position, rounding_mode);
+                // rounding up may carry into a new leading digit
+                if digits.len() > precision + 1 {
+                    digits.pop();
+                    (digits, leading + 1)
+                } else {
+                    (digits, leading)
+                }
+            }
+            None if Self::unpack_finite(v).1 == 0 => ("0".to_string(), 0),
+            None => {
+                let (digits, exponent) = Self::to_shortest_decimal(v);
+                (digits.to_string(), exponent)
+            }
+        };
+        let (leading, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{}{}{}", leading, exponent_char, exponent)
+        } else {
+            format!("{}.{}{}{}", leading, rest, exponent_char, exponent)
+        }
+    }
+
+    // magnitude of the finite v in hexadecimal
+    fn to_hex(v: u128) -> String {
+        let (_, exponent, significand) = Self::unpack(v);
+        if exponent == 0 && significand == 0 {
+            return "0x0p+0".to_string();
+        }
+        let (leading, exponent) = if exponent == 0 {
+            (0, 1 - Self::BIAS)
+        } else {
+            (1, exponent - Self::BIAS)
+        };
+        let width = MANT_BITS.div_ceil(4) as usize;
+        let fraction = format!("{:0width$x}", significand << (4 * width as u32 - MANT_BITS));
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            format!("0x{}p{:+}", leading, exponent)
+        } else {
+            format!("0x{}.{}p{:+}", leading, fraction, exponent)
+        }
+    }
+
+    // magnitude of the finite v in positional notation, shortest
+    fn to_shortest(v: u128) -> String {
+        if Self::unpack_finite(v).1 == 0 {
+            return "0".to_string();
+        }
+        let (digits, exponent) = Self::to_shortest_decimal(v);
+        positional(&digits.to_string(), exponent)
+    }
+
+    // shortest digits d (without trailing zeros) and exponent e, such that
+    // d.ddd * 10^e rounds to the finite, nonzero v (ignoring its sign); among
+    // several candidates of the same length, the one closest to v is chosen
+    pub(crate) fn to_shortest_decimal(v: u128) -> (u128, i32) {
+        let (exponent, significand) = Self::unpack_finite(v);
+
+        // v = significand * 2^(exponent - BIAS - MANT_BITS); the neighbors
+        // are one unit in the last place away, except for the smaller
+        // neighbor of powers of two, which is half a unit away; the interval
+        // that rounds to v is bounded by the midpoints, which belong to it if
+        // the significand is even; everything is scaled by 4, so that the
+        // midpoints are integers
+        let is_power_of_two = significand == Self::IMPLICIT_BIT && exponent > 1;
+        let (mut value, mut den) = (BigUint::from_u128(4 * significand), BigUint::from_u128(1));
+        let (mut delta_lo, mut delta_hi) = (
+            BigUint::from_u128(if is_power_of_two { 1 } else { 2 }),
+            BigUint::from_u128(2),
+        );
+        let binary_exponent = exponent - Self::BIAS - MANT_BITS as i32 - 2;
+        if binary_exponent >= 0 {
+            value = value.shl(binary_exponent as u32);
+            delta_lo = delta_lo.shl(binary_exponent as u32);
+            delta_hi = delta_hi.shl(binary_exponent as u32);
+        } else {
+            den = den.shl(-binary_exponent as u32);
+        }
+        let inclusive = significand & 1 == 0;
+
+        // decimal exponent of the leading digit, 10^k <= v < 10^(k + 1),
+        // starting from an estimate
+        let mut k =
+            ((value.bits() as f64 - den.bits() as f64) * std::f64::consts::LOG10_2).floor() as i32;
+        while Self::compare_pow10(&value, &den, k).is_lt() {
+            k -= 1;
+        }
+        while Self::compare_pow10(&value, &den, k + 1).is_ge() {
+            k += 1;
+        }
+
+        // try n digits, i.e., multiples of 10^(k - n + 1), the largest one
+        // not above v and the next one
+        for n in 1.. {
+            let p = k - n + 1;
+            let (mut num, mut den, mut delta_lo, mut delta_hi) = (
+                value.clone(),
+                den.clone(),
+                delta_lo.clone(),
+                delta_hi.clone(),
+            );
+            if p < 0 {
+                num.mul_pow10(-p as u32);
+                delta_lo.mul_pow10(-p as u32);
+                delta_hi.mul_pow10(-p as u32);
+            } else {
+                den.mul_pow10(p as u32);
+            }
+            let (q, r) = num.div_rem(&den);
+            if r.is_zero() {
+                return Self::strip_trailing_zeros(q, k);
+            }
+
+            // distances from v to the candidates
+            let mut r_up = den.clone();
+            r_up.sub_assign(&r);
+            let fits = |distance: &BigUint, delta: &BigUint| {
+                if inclusive {
+                    distance <= delta
+                } else {
+                    distance < delta
+                }
+            };
+            let (down, up) = (fits(&r, &delta_lo), fits(&r_up, &delta_hi));
+            let q = match (down, up) {
+                (true, true) => match r.cmp(&r_up) {
+                    std::cmp::Ordering::Less => q,
+                    std::cmp::Ordering::Greater => q + 1,
+                    std::cmp::Ordering::Equal => q + (q & 1),
+                },
+                (true, false) => q,
+                (false, true) => q + 1,
+                (false, false) => continue,
+            };
+            // rounding up may carry into a new leading digit
+            return if q == 10u128.pow(n as u32) {
+                Self::strip_trailing_zeros(q / 10, k + 1)
+            } else {
+                Self::strip_trailing_zeros(q, k)
+            };
+        }
+        unreachable!()
+    }
+
+    // compares value / den with 10^k
+    fn compare_pow10(value: &BigUint, den: &BigUint, k: i32) -> std::cmp::Ordering {
+        if k >= 0 {
+            let mut rhs = den.clone();
+            rhs.mul_pow10(k as u32);
+            value.cmp(&rhs)
+        } else {
+            let mut lhs = value.clone();
+            lhs.mul_pow10(-k as u32);
+            lhs.cmp(den)
+        }
+    }
+
+    fn strip_trailing_zeros(mut digits: u128, exponent: i32) -> (u128, i32) {
+        while digits.is_multiple_of(10) {
+            digits /= 10;
+        }
+        (digits, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ROUNDING_MODES;
+    use crate::{SoftFloat128, SoftFloat16, SoftFloat32, SoftFloat64};
+
+    #[test]
+    fn test_display() {
+        for (v, expected) in [
+            (0x3c00, "1"),
+            (0xbc00, "-1"),
+            (0x3e00, "1.5"),
+            (0x2e66, "0.1"),
+            (0x3555, "0.3333"),
+            (0x7bff, "65500"),
+            (0x7bfe, "65470"),
+            (0x0001, "0.00000006"),
+            (0x03ff, "0.000061"),
+            (0x0400, "0.00006104"),
+            (0x0000, "0"),
+            (0x8000, "-0"),
+            (0x7c00, "inf"),
+            (0xfc00, "-inf"),
+            (0x7e00, "NaN"),
+            (0xfe01, "NaN"),
+        ] {
+            assert_eq!(
+                format!("{}", SoftFloat16::from_bits(v)),
+                expected,
+                "{:#x}",
+                v
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let x = SoftFloat16::from_bits(0x3e00);
+        assert_eq!(format!("{:+}", x), "+1.5");
+        assert_eq!(format!("{:>6}", x), "   1.5");
+        assert_eq!(format!("{:<6}|", -x), "-1.5  |");
+        assert_eq!(format!("{:^7}", SoftFloat16::from_bits(0x7e00)), "  NaN  ");
+
+        // NaNs never show a sign and ignore the precision, like f32
+        for (i, v_f) in [
+            (0x7e00, f32::NAN),
+            (0xfe00, -f32::NAN),
+            (0xfc00, f32::NEG_INFINITY),
+        ] {
+            let x = SoftFloat16::from_bits(i);
+            assert_eq!(format!("{:+}", x), format!("{:+}", v_f));
+            assert_eq!(format!("{:+.2}", x), format!("{:+.2}", v_f));
+            assert_eq!(format!("{:+e}", x), format!("{:+e}", v_f));
+            assert_eq!(format!("{:+E}", x), format!("{:+E}", v_f));
+            assert_eq!(format!("{:+08}", x), format!("{:+08}", v_f));
+            assert_eq!(format!("{:>6}", x), format!("{:>6}", v_f));
+            assert_eq!(format!("{:*<6.1}", x), format!("{:*<6.1}", v_f));
+            assert_eq!(format!("{:^8}", x), format!("{:^8}", v_f));
+        }
+        assert_eq!(format!("{:+}", SoftFloat16::from_bits(0x7e00)), "NaN");
+        assert_eq!(format!("{:+x}", SoftFloat16::from_bits(0xfe00)), "NaN");
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(
+            format!("{:?}", SoftFloat16::from_bits(0x3c00)),
+            "SoftFloat(1, 0x3c00)"
+        );
+        assert_eq!(
+            format!("{:?}", SoftFloat::<u8, 4, 3>::from_bits(0x81)),
+            "SoftFloat(-0.002, 0x81)"
+        );
+    }
+
+    // the host formats f32 and f64 the same way
+    #[test]
+    fn test_display_like_host() {
+        for v in [
+            0.1f64,
+            1.0 / 3.0,
+            2.0f64.powi(-1074),
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            123456789.0,
+            1e23,
+            5e-324,
+        ] {
+            let x = SoftFloat64::from_bits(v.to_bits());
+            assert_eq!(format!("{}", x), format!("{}", v));
+            let x = SoftFloat32::from_bits((v as f32).to_bits());
+            assert_eq!(format!("{}", x), format!("{}", v as f32));
+        }
+    }
+
+    #[test]
+    fn test_display_binary128() {
+        for (v, expected) in [
+            (0x3fff << 112, "1"),
+            (0x3ffb999999999999999999999999999a, "0.1"),
+            (
+                0x3ffd5555555555555555555555555555,
+                "0.3333333333333333333333333333333333",
+            ),
+        ] {
+            assert_eq!(format!("{}", SoftFloat128::from_bits(v)), expected);
+        }
+    }
+
+    #[test]
+    fn test_display_precision() {
+        let x = SoftFloat16::from_bits(0x2e66);
+        assert_eq!(format!("{:.3}", x), "0.100");
+        assert_eq!(format!("{:.20}", x), "0.09997558593750000000");
+        assert_eq!(format!("{:.0}", SoftFloat16::from_bits(0x3e00)), "2");
+        assert_eq!(format!("{:.0}", SoftFloat16::from_bits(0x4100)), "2");
+        assert_eq!(format!("{:.2}", SoftFloat16::from_bits(0x8001)), "-0.00");
+        assert_eq!(format!("{:.1}", SoftFloat16::from_bits(0x7bff)), "65504.0");
+        assert_eq!(
+            format!("{:08.2}", SoftFloat16::from_bits(0xbe00)),
+            "-0001.50"
+        );
+        assert_eq!(format!("{:+.1}", SoftFloat16::from_bits(0x3e00)), "+1.5");
+        assert_eq!(format!("{:.2}", SoftFloat16::from_bits(0xfc00)), "-inf");
+    }
+
+    #[test]
+    fn test_exp() {
+        for (v, expected) in [
+            (0x3c00, "1e0"),
+            (0x3e00, "1.5e0"),
+            (0x2e66, "1e-1"),
+            (0x7bff, "6.55e4"),
+            (0x0001, "6e-8"),
+            (0x0000, "0e0"),
+            (0x8000, "-0e0"),
+            (0xfc00, "-inf"),
+        ] {
+            assert_eq!(
+                format!("{:e}", SoftFloat16::from_bits(v)),
+                expected,
+                "{:#x}",
+                v
+            );
+        }
+        assert_eq!(format!("{:E}", SoftFloat16::from_bits(0xbe00)), "-1.5E0");
+        assert_eq!(format!("{:.2e}", SoftFloat16::from_bits(0x7bff)), "6.55e4");
+        assert_eq!(format!("{:.3e}", SoftFloat16::from_bits(0x0000)), "0.000e0");
+        // 9.5 rounds to 10, 8.5 to 8
+        assert_eq!(format!("{:.0e}", SoftFloat16::from_bits(0x48c0)), "1e1");
+        assert_eq!(format!("{:.0e}", SoftFloat16::from_bits(0x4840)), "8e0");
+        assert_eq!(format!("{:.1E}", SoftFloat16::from_bits(0x0001)), "6.0E-8");
+        assert_eq!(
+            format!("{:>10.1e}", SoftFloat16::from_bits(0x3c00)),
+            "     1.0e0"
+        );
+    }
+
+    #[test]
+    fn test_to_exact_decimal() {
+        for (v, expected) in [
+            (0x0001, "0.000000059604644775390625"),
+            (0x2e66, "0.0999755859375"),
+            (0x7bff, "65504"),
+            (0x3c00, "1"),
+            (0x8000, "-0"),
+            (0xbe00, "-1.5"),
+            (0x7c00, "inf"),
+            (0x7e00, "NaN"),
+        ] {
+            assert_eq!(
+                SoftFloat16::to_exact_decimal(SoftFloat16::from_bits(v)),
+                expected
+            );
+        }
+        assert_eq!(
+            SoftFloat64::to_exact_decimal(SoftFloat64::from_bits(0.1f64.to_bits())),
+            "0.1000000000000000055511151231257827021181583404541015625"
+        );
+        assert_eq!(
+            SoftFloat32::to_exact_decimal(SoftFloat32::from_bits(f32::MAX.to_bits())),
+            "340282346638528859811704183484516925440"
+        );
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        for (v, precision, expected) in [
+            (0x2e66, 2, ["0.10", "0.10", "0.09", "0.10", "0.09", "0.09"]),
+            (
+                0xae66,
+                2,
+                ["-0.10", "-0.10", "-0.09", "-0.09", "-0.10", "-0.09"],
+            ),
+            (0x4100, 0, ["2", "3", "2", "3", "2", "3"]),
+            (0x3e00, 0, ["2", "2", "1", "2", "1", "1"]),
+            (
+                0x3c00,
+                3,
+                ["1.000", "1.000", "1.000", "1.000", "1.000", "1.000"],
+            ),
+            (0x0001, 1, ["0.0", "0.0", "0.0", "0.1", "0.0", "0.1"]),
+        ] {
+            for (rounding_mode, expected) in ROUNDING_MODES.into_iter().zip(expected) {
+                let x = SoftFloat16::from_bits(v);
+                assert_eq!(
+                    SoftFloat16::to_fixed_with_rounding(x, precision, rounding_mode),
+                    expected,
+                    "{:?}",
+                    (v, rounding_mode)
+                );
+            }
+        }
+        // 65504 with two significant digits
+        let x = SoftFloat16::from_bits(0x7bff);
+        for (rounding_mode, expected) in ROUNDING_MODES
+            .into_iter()
+            .zip(["6.6e4", "6.6e4", "6.5e4", "6.6e4", "6.5e4", "6.5e4"])
+        {
+            assert_eq!(
+                SoftFloat16::to_exponential_with_rounding(x, 1, rounding_mode),
+                expected
+            );
+        }
+    }
+
+    // rounds the exact value v * 10^-24, which covers all values of the
+    // tested formats, to the given number of fractional digits
+    fn reference_fixed(sign: bool, v: u128, precision: u32, rounding_mode: RoundingMode) -> String {
+        let unit = 10u128.pow(24 - precision);
+        let (q, r) = (v / unit, v % unit);
+        let up = match rounding_mode {
+            RoundingMode::NearestTiesToEven => 2 * r > unit || (2 * r == unit && q % 2 == 1),
+            RoundingMode::NearestTiesToAway => 2 * r >= unit,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => r != 0 && !sign,
+            RoundingMode::TowardNegative => r != 0 && sign,
+            RoundingMode::ToOdd => r != 0 && q % 2 == 0,
+        };
+        let q = (q + up as u128).to_string();
+        let q = format!("{:0>1$}", q, precision as usize + 1);
+        let (integer, fraction) = q.split_at(q.len() - precision as usize);
+        let sign = if sign { "-" } else { "" };
+        if fraction.is_empty() {
+            format!("{}{}", sign, integer)
+        } else {
+            format!("{}{}.{}", sign, integer, fraction)
+        }
+    }
+
+    fn check_decimal<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        x: SoftFloat<T, EXP_BITS, MANT_BITS>,
+    ) {
+        let v = f64::from(x);
+        if !v.is_finite() {
+            return;
+        }
+
+        // exact value as an integer multiple of 10^-24
+        let exact = (v.abs() * (1u64 << 24) as f64) as u128 * 5u128.pow(24);
+        let expected = reference_fixed(v.is_sign_negative(), exact, 24, RoundingMode::default());
+        let expected = expected.trim_end_matches('0').trim_end_matches('.');
+        assert_eq!(SoftFloat::to_exact_decimal(x), expected, "{}", v);
+
+        for precision in 0..=8 {
+            for rounding_mode in ROUNDING_MODES {
+                assert_eq!(
+                    SoftFloat::to_fixed_with_rounding(x, precision as usize, rounding_mode),
+                    reference_fixed(v.is_sign_negative(), exact, precision, rounding_mode),
+                    "{:?}",
+                    (v, precision, rounding_mode)
+                );
+            }
+            // the host rounds exactly, ties to even
+            let p = precision as usize;
+            assert_eq!(format!("{:.p$}", x), format!("{:.p$}", v));
+            assert_eq!(format!("{:.p$e}", x), format!("{:.p$e}", v));
+        }
+    }
+
+    #[test]
+    fn test_all_decimal_binary8() {
+        for i in 0..=u8::MAX {
+            check_decimal(SoftFloat::<u8, 2, 5>::from_bits(i));
+            check_decimal(SoftFloat::<u8, 3, 4>::from_bits(i));
+            check_decimal(SoftFloat::<u8, 4, 3>::from_bits(i));
+            check_decimal(SoftFloat::<u8, 5, 2>::from_bits(i));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_decimal() {
+        for i in 0..=u16::MAX {
+            check_decimal(SoftFloat16::from_bits(i));
+        }
+    }
+
+    #[test]
+    fn test_hex() {
+        for (v, expected) in [
+            (0x3c00, "0x1p+0"),
+            (0x7bff, "0x1.ffcp+15"),
+            (0xc200, "-0x1.8p+1"),
+            (0x3555, "0x1.554p-2"),
+            (0x0400, "0x1p-14"),
+            (0x03ff, "0x0.ffcp-14"),
+            (0x0001, "0x0.004p-14"),
+            (0x0000, "0x0p+0"),
+            (0x8000, "-0x0p+0"),
+            (0xfc00, "-inf"),
+            (0x7e00, "NaN"),
+        ] {
+            assert_eq!(
+                format!("{:x}", SoftFloat16::from_bits(v)),
+                expected,
+                "{:#x}",
+                v
+            );
+        }
+        assert_eq!(
+            format!("{:X}", SoftFloat16::from_bits(0xfbff)),
+            "-0X1.FFCP+15"
+        );
+        assert_eq!(
+            format!("{:>12x}", SoftFloat16::from_bits(0x3c00)),
+            "      0x1p+0"
+        );
+
+        // like glibc's printf("%a")
+        let x = SoftFloat64::from_bits(0.1f64.to_bits());
+        assert_eq!(format!("{:x}", x), "0x1.999999999999ap-4");
+        let x = SoftFloat64::from_bits(1);
+        assert_eq!(format!("{:x}", x), "0x0.0000000000001p-1022");
+        let x = SoftFloat::<u8, 4, 3>::from_bits(0x76);
+        assert_eq!(format!("{:x}", x), "0x1.cp+7");
+    }
+
+    fn check_hex<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        x: SoftFloat<T, EXP_BITS, MANT_BITS>,
+    ) {
+        for s in [format!("{:x}", x), format!("{:X}", x)] {
+            let y = s.parse::<SoftFloat<T, EXP_BITS, MANT_BITS>>().unwrap();
+            if SoftFloat::is_nan(x) {
+                assert!(SoftFloat::is_nan(y), "{}", s);
+            } else {
+                assert_eq!(
+                    T::to_u128(SoftFloat::to_bits(y)),
+                    T::to_u128(SoftFloat::to_bits(x)),
+                    "{}",
+                    s
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_hex_binary8() {
+        for i in 0..=u8::MAX {
+            check_hex(SoftFloat::<u8, 2, 5>::from_bits(i));
+            check_hex(SoftFloat::<u8, 3, 4>::from_bits(i));
+            check_hex(SoftFloat::<u8, 4, 3>::from_bits(i));
+            check_hex(SoftFloat::<u8, 5, 2>::from_bits(i));
+        }
+    }
+
+    #[test]
+    fn test_all_hex() {
+        for i in 0..=u16::MAX {
+            check_hex(SoftFloat16::from_bits(i));
+        }
+    }
+
+    fn check_display<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        x: SoftFloat<T, EXP_BITS, MANT_BITS>,
+    ) {
+        let s = format!("{}", x);
+        let y = s.parse::<SoftFloat<T, EXP_BITS, MANT_BITS>>().unwrap();
+        if SoftFloat::is_nan(x) {
+            assert!(SoftFloat::is_nan(y), "{}", s);
+            return;
+        }
+        assert_eq!(
+            T::to_u128(SoftFloat::to_bits(y)),
+            T::to_u128(SoftFloat::to_bits(x)),
+            "{}",
+            s
+        );
+    }
+
+    #[test]
+    fn test_all_display_binary8() {
+        for i in 0..=u8::MAX {
+            check_display(SoftFloat::<u8, 2, 5>::from_bits(i));
+            check_display(SoftFloat::<u8, 3, 4>::from_bits(i));
+            check_display(SoftFloat::<u8, 4, 3>::from_bits(i));
+            check_display(SoftFloat::<u8, 5, 2>::from_bits(i));
+        }
+    }
+
+    // the output parses back to the same number, and is never longer than
+    // the one of f32, which may need more digits since its rounding interval
+    // is narrower
+    #[test]
+    #[ignore]
+    fn test_all_display() {
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            check_display(x);
+            let digits = |s: &str| s.trim_start_matches(['-', '0', '.']).replace('.', "").len();
+            assert!(
+                digits(&format!("{}", x)) <= digits(&format!("{}", f32::from(x))),
+                "{:#x}",
+                i
+            );
+        }
+    }
+}
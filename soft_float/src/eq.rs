@@ -1,35 +1,69 @@
-use crate::SoftFloat16;
+use std::hash::{Hash, Hasher};
 
-impl PartialEq for SoftFloat16 {
+use crate::format::Format;
+use crate::{ExceptionFlags, SoftFloat, Storage};
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> PartialEq
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn eq(&self, other: &Self) -> bool {
-        let (sign0, exponent0, significand0) = (
-            Self::sign(*self),
-            Self::exponent(*self),
-            Self::significand(*self),
-        );
-        let (sign1, exponent1, significand1) = (
-            Self::sign(*other),
-            Self::exponent(*other),
-            Self::significand(*other),
-        );
+        Self::eq_with_flags(*self, *other).0
+    }
+}
 
-        if (exponent0 == 0x1F && significand0 != 0) || (exponent1 == 0x1F && significand1 != 0) {
+// consistent with PartialEq, i.e., both zeros hash alike; NANs, which equal
+// nothing, hash by their encoding; compare TotalOrder for an Eq type
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Hash
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let v = Self::raw(*self);
+        let v = if v & !Format::<EXP_BITS, MANT_BITS>::SIGN_BIT == 0 {
+            0
+        } else {
+            v
+        };
+        v.hash(state);
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // quiet comparison, i.e., comparing with a quiet NAN does not raise the
+    // invalid exception
+    pub fn eq_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        Format::<EXP_BITS, MANT_BITS>::eq(Self::raw(v0), Self::raw(v1))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn eq(v0: u128, v1: u128) -> (bool, ExceptionFlags) {
+        if Self::is_signaling(v0) || Self::is_signaling(v1) {
+            return (false, ExceptionFlags::INVALID);
+        }
+
+        let (_, exponent0, significand0) = Self::unpack(v0);
+        let (_, exponent1, significand1) = Self::unpack(v1);
+
+        let result = if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
             false
         } else if exponent0 == 0 && significand0 == 0 && exponent1 == 0 && significand1 == 0 {
             true
         } else {
-            if sign0 == sign1 && exponent0 == exponent1 && significand0 == significand1 {
-                true
-            } else {
-                false
-            }
-        }
+            v0 == v1
+        };
+
+        (result, ExceptionFlags::NONE)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
     use super::*;
+    use crate::SoftFloat16;
 
     #[test]
     #[ignore]
@@ -44,4 +78,47 @@ mod tests {
             }
         }
     }
+
+    fn check_all_eq<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0 == x1;
+                let y_f = f32::from(x0) == f32::from(x1);
+                assert_eq!(y, y_f, "{:?}", (i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_eq_binary8() {
+        check_all_eq::<2, 5>();
+        check_all_eq::<3, 4>();
+        check_all_eq::<4, 3>();
+        check_all_eq::<5, 2>();
+    }
+
+    fn hash(v: SoftFloat16) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        v.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // values that compare equal have the same hash
+    #[test]
+    fn test_hash() {
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            if SoftFloat16::is_nan(x) {
+                continue;
+            }
+            let zero = SoftFloat16::from_bits(0x0000);
+            assert_eq!(x == zero, hash(x) == hash(zero), "{:?}", i);
+        }
+        assert_eq!(
+            hash(SoftFloat16::from_bits(0x8000)),
+            hash(SoftFloat16::from_bits(0x0000))
+        );
+    }
 }
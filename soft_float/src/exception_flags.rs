@@ -0,0 +1,313 @@
+use std::ops::{BitOr, BitOrAssign};
+
+// exceptions according to IEEE 754, sec. 7; the bit layout matches the one
+// used by Berkeley SoftFloat and TestFloat
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExceptionFlags(u8);
+
+impl ExceptionFlags {
+    pub const NONE: Self = Self(0x00);
+    pub const INEXACT: Self = Self(0x01);
+    pub const UNDERFLOW: Self = Self(0x02);
+    pub const OVERFLOW: Self = Self(0x04);
+    pub const DIVIDE_BY_ZERO: Self = Self(0x08);
+    pub const INVALID: Self = Self(0x10);
+
+    pub fn from_bits(v: u8) -> Self {
+        Self(v & 0x1F)
+    }
+
+    pub fn to_bits(v: Self) -> u8 {
+        v.0
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    // same as `|`, which is not available in const contexts
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOr for ExceptionFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl BitOrAssign for ExceptionFlags {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = self.union(other);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::XorShift;
+    use crate::{RoundingMode, SoftFloat16, TininessDetection};
+
+    // derives the flags of a finite, nonzero result from the exact result
+    // (or its correctly rounded f64 approximation if is_exact is false)
+    fn reference(
+        exact: f64,
+        is_exact: bool,
+        y: SoftFloat16,
+        tininess_detection: TininessDetection,
+    ) -> ExceptionFlags {
+        let y_f = f32::from(y) as f64;
+        let mut flags = ExceptionFlags::NONE;
+        if y_f.is_infinite() {
+            return ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT;
+        }
+        if !is_exact || y_f != exact {
+            flags |= ExceptionFlags::INEXACT;
+            // the exact result would round to 2^-14 given an unbounded
+            // exponent range if it is at least 2^-14 - 2^-26
+            let min_positive = match tininess_detection {
+                TininessDetection::BeforeRounding => (2.0_f64).powi(-14),
+                TininessDetection::AfterRounding => (2.0_f64).powi(-14) - (2.0_f64).powi(-26),
+            };
+            if exact.abs() < min_positive {
+                flags |= ExceptionFlags::UNDERFLOW;
+            }
+        }
+        flags
+    }
+
+    fn check(i: u16, j: u16, td: TininessDetection) {
+        let x0 = SoftFloat16::from_bits(i);
+        let x1 = SoftFloat16::from_bits(j);
+        let (x0_f, x1_f) = (f32::from(x0) as f64, f32::from(x1) as f64);
+        if !x0_f.is_finite() || !x1_f.is_finite() {
+            return;
+        }
+        let rm = RoundingMode::NearestTiesToEven;
+
+        // sums and products are exact in f64
+        let (y, flags) = SoftFloat16::add_with_flags(x0, x1, rm, td);
+        if x0_f + x1_f != 0.0 {
+            assert_eq!(flags, reference(x0_f + x1_f, true, y, td), "{:?}", (i, j));
+        }
+
+        let (y, flags) = SoftFloat16::mul_with_flags(x0, x1, rm, td);
+        if x0_f * x1_f != 0.0 {
+            assert_eq!(flags, reference(x0_f * x1_f, true, y, td), "{:?}", (i, j));
+        }
+
+        // quotients are exact if multiplying back yields the dividend
+        let (y, flags) = SoftFloat16::div_with_flags(x0, x1, rm, td);
+        if x0_f != 0.0 && x1_f != 0.0 {
+            let is_exact = (f32::from(y) as f64) * x1_f == x0_f;
+            assert_eq!(
+                flags,
+                reference(x0_f / x1_f, is_exact, y, td),
+                "{:?}",
+                (i, j)
+            );
+        }
+    }
+
+    #[test]
+    fn test_flags() {
+        let rm = RoundingMode::NearestTiesToEven;
+        let td = TininessDetection::AfterRounding;
+        let nan = SoftFloat16::from_bits(0x7e00);
+        let inf = SoftFloat16::from_bits(0x7c00);
+        let max = SoftFloat16::from_bits(0x7bff);
+        let zero = SoftFloat16::from_bits(0x0000);
+        let one = SoftFloat16::from_bits(0x3c00);
+        let min_positive = SoftFloat16::from_bits(0x0400);
+
+        for (flags, expected) in [
+            (
+                SoftFloat16::add_with_flags(one, one, rm, td).1,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::add_with_flags(inf, -inf, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::add_with_flags(nan, one, rm, td).1,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::add_with_flags(max, max, rm, td).1,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::add_with_flags(max, max, RoundingMode::TowardZero, td).1,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::sub_with_flags(inf, inf, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::mul_with_flags(inf, zero, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::mul_with_flags(min_positive, min_positive, rm, td).1,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::div_with_flags(zero, zero, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::div_with_flags(inf, inf, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::div_with_flags(one, zero, rm, td).1,
+                ExceptionFlags::DIVIDE_BY_ZERO,
+            ),
+            (
+                SoftFloat16::div_with_flags(inf, zero, rm, td).1,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::mul_add_with_flags(inf, zero, nan, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::mul_add_with_flags(inf, one, -inf, rm, td).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::sqrt_with_flags(-one, rm).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::sqrt_with_flags(-zero, rm).1,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::sqrt_with_flags(SoftFloat16::from_bits(0x4000), rm).1,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::rem_with_flags(inf, one).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::rem_with_flags(one, zero).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::rem_with_flags(max, min_positive).1,
+                ExceptionFlags::NONE,
+            ),
+            (SoftFloat16::eq_with_flags(nan, one).1, ExceptionFlags::NONE),
+            (
+                SoftFloat16::le_with_flags(nan, one).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::lt_with_flags(one, nan).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::to_i32_with_flags(nan).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::to_i32_with_flags(inf).1,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::from_f32_with_flags(1e-8, rm, td).1,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::from_f32_with_flags(65520.0, rm, td).1,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                SoftFloat16::from_i32_with_flags(2049, rm).1,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            assert_eq!(flags, expected);
+        }
+    }
+
+    #[test]
+    fn test_tininess_detection() {
+        let (before, after) = (
+            TininessDetection::BeforeRounding,
+            TininessDetection::AfterRounding,
+        );
+        let underflow = ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT;
+
+        // 0x03ff * (1 + 2^-10) is just below 2^-14 but rounds to it, so it
+        // is tiny before but not after rounding
+        let x0 = SoftFloat16::from_bits(0x03ff);
+        let x1 = SoftFloat16::from_bits(0x3c01);
+        for (rm, td, expected, expected_flags) in [
+            (RoundingMode::NearestTiesToEven, before, 0x0400, underflow),
+            (
+                RoundingMode::NearestTiesToEven,
+                after,
+                0x0400,
+                ExceptionFlags::INEXACT,
+            ),
+            // with rounding toward zero the result stays denormal
+            (RoundingMode::TowardZero, before, 0x03ff, underflow),
+            (RoundingMode::TowardZero, after, 0x03ff, underflow),
+        ] {
+            let (y, flags) = SoftFloat16::mul_with_flags(x0, x1, rm, td);
+            assert_eq!(SoftFloat16::to_bits(y), expected);
+            assert_eq!(flags, expected_flags, "{:?}", (rm, td));
+
+            let x2 = SoftFloat16::from_bits(0x0000);
+            let (y, flags) = SoftFloat16::mul_add_with_flags(x0, x1, x2, rm, td);
+            assert_eq!(SoftFloat16::to_bits(y), expected);
+            assert_eq!(flags, expected_flags, "{:?}", (rm, td));
+
+            let x = f32::from(x0) * f32::from(SoftFloat16::from_bits(0x3c01));
+            let (y, flags) = SoftFloat16::from_f32_with_flags(x, rm, td);
+            assert_eq!(SoftFloat16::to_bits(y), expected);
+            assert_eq!(flags, expected_flags, "{:?}", (rm, td));
+        }
+
+        // quotients of numbers with 11 significant bits are never that close
+        // to 2^-14 and sums of denormal numbers are exact and hence never
+        // underflow
+        let x0 = SoftFloat16::from_bits(0x03ff);
+        let x1 = SoftFloat16::from_bits(0x0001);
+        for td in [before, after] {
+            let (y, flags) = SoftFloat16::add_with_flags(x0, x1, RoundingMode::default(), td);
+            assert_eq!(SoftFloat16::to_bits(y), 0x0400);
+            assert_eq!(flags, ExceptionFlags::NONE);
+        }
+    }
+
+    #[test]
+    fn test_random_flags() {
+        let mut rng = XorShift::new();
+        let mut next = || rng.next_u16();
+        for _ in 0..100_000 {
+            let (i, j) = (next(), next());
+            check(i, j, TininessDetection::BeforeRounding);
+            check(i, j, TininessDetection::AfterRounding);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_flags() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                check(i, j, TininessDetection::BeforeRounding);
+                check(i, j, TininessDetection::AfterRounding);
+            }
+        }
+    }
+}
@@ -0,0 +1,1044 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use crate::format::Format;
+use crate::{
+    Ceil, ExceptionFlags, Floor, MulAdd, Round, RoundTiesEven, RoundingMode, RoundingPrecision,
+    SoftFloat, SoftFloat16, SoftFloat32, SoftFloat64, Sqrt, Storage, TininessDetection, Trunc,
+};
+
+// x87 double extended precision, held in the 80 least significant bits:
+// sign, 15 exponent bits, and a 64-bit significand whose most significant
+// bit, the integer bit, is stored explicitly
+//
+// the integer bit is set for normal numbers, infinities and NANs and cleared
+// for zeros and denormals; other encodings are handled like the 80387 and
+// later do: pseudo-denormals (exponent 0, integer bit set) are read as the
+// normal number with exponent 1 they represent, while unnormals,
+// pseudo-infinities and pseudo-NANs (nonzero exponent, integer bit cleared)
+// are invalid operands; results are always canonical
+#[derive(Clone, Copy, Debug)]
+pub struct ExtFloat80(u128);
+
+// apart from the explicit integer bit, the layout is the same as the one of
+// the IEEE-like format with 15 exponent and 63 significand bits, which
+// implements the arithmetic
+type Ext = Format<15, 63>;
+
+const INTEGER_BIT: u128 = 1 << 63;
+
+impl ExtFloat80 {
+    // bits beyond 80 are cleared
+    pub fn from_bits(v: u128) -> Self {
+        Self(v & ((1 << 80) - 1))
+    }
+
+    pub fn to_bits(v: Self) -> u128 {
+        v.0
+    }
+
+    pub fn sign(v: Self) -> u128 {
+        v.0 >> 79
+    }
+
+    pub fn exponent(v: Self) -> u128 {
+        (v.0 >> 64) & 0x7FFF
+    }
+
+    // including the integer bit
+    pub fn significand(v: Self) -> u128 {
+        v.0 & u64::MAX as u128
+    }
+
+    // pseudo-NANs are NANs, too
+    pub fn is_nan(v: Self) -> bool {
+        Self::exponent(v) == 0x7FFF && Self::significand(v) & !INTEGER_BIT != 0
+    }
+
+    // unnormals, pseudo-infinities and pseudo-NANs
+    pub fn is_unsupported(v: Self) -> bool {
+        Self::exponent(v) != 0 && Self::significand(v) & INTEGER_BIT == 0
+    }
+
+    // encoding as passed to the algorithms in Format, None for unsupported
+    // encodings
+    fn raw(v: Self) -> Option<u128> {
+        let sign = Self::sign(v) as u32;
+        let exponent = Self::exponent(v) as i32;
+        let significand = Self::significand(v);
+        match (exponent, significand & INTEGER_BIT != 0) {
+            // pseudo-denormal
+            (0, true) => Some(Ext::pack(sign, 1, significand & !INTEGER_BIT)),
+            (0, false) => Some(Ext::pack(sign, 0, significand)),
+            (_, true) => Some(Ext::pack(sign, exponent, significand & !INTEGER_BIT)),
+            (_, false) => None,
+        }
+    }
+
+    // inserts the integer bit between exponent and fraction
+    fn from_raw(v: u128) -> Self {
+        let (_, exponent, _) = Ext::unpack(v);
+        let v = (v & !Ext::SIGNIFICAND_MASK) << 1 | v & Ext::SIGNIFICAND_MASK;
+        if exponent != 0 {
+            Self(v | INTEGER_BIT)
+        } else {
+            Self(v)
+        }
+    }
+
+    // applies an operation of Ext to the operands and rounds its result to
+    // the given precision; unsupported encodings are invalid operands that
+    // yield the default NAN
+    fn apply<const N: usize>(
+        operands: [Self; N],
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+        op: impl Fn([u128; N], RoundingMode) -> (u128, ExceptionFlags),
+    ) -> (Self, ExceptionFlags) {
+        let operands = operands.map(Self::raw);
+        if operands.contains(&None) {
+            return (Self::from_raw(Ext::NAN), ExceptionFlags::INVALID);
+        }
+        let operands = operands.map(Option::unwrap);
+
+        let op = |rounding_mode| op(operands, rounding_mode);
+        let (v, flags) = match rounding_precision {
+            RoundingPrecision::Single => {
+                Self::round_to_precision::<23>(op, rounding_mode, tininess_detection)
+            }
+            RoundingPrecision::Double => {
+                Self::round_to_precision::<52>(op, rounding_mode, tininess_detection)
+            }
+            RoundingPrecision::Extended => op(rounding_mode),
+        };
+        (Self::from_raw(v), flags)
+    }
+
+    // rounding to odd with 64 bits followed by rounding to MANT_BITS + 1
+    // bits yields the same result as rounding the exact result once, since
+    // the intermediate result has at least two more bits; Format<15,
+    // MANT_BITS> has the same exponent range as the extended format, so
+    // denormals and overflow are handled the way precision control does
+    fn round_to_precision<const MANT_BITS: u32>(
+        op: impl Fn(RoundingMode) -> (u128, ExceptionFlags),
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (v, flags) = op(RoundingMode::ToOdd);
+        let (_, exponent, significand) = Ext::unpack(v);
+        if exponent == 0 && significand == 0 {
+            // zeros are exact, but the sign of an exact zero sum depends on
+            // the rounding mode
+            return op(rounding_mode);
+        } else if Ext::is_nan(v) {
+            return (v, flags);
+        }
+
+        let (narrow, mut narrow_flags) =
+            Format::<15, MANT_BITS>::convert::<15, 63>(v, rounding_mode, tininess_detection);
+        // overflow of the intermediate result goes unnoticed when rounding
+        // its largest finite value down, and division by zero is exact
+        for flag in [ExceptionFlags::OVERFLOW, ExceptionFlags::DIVIDE_BY_ZERO] {
+            if flags.contains(flag) {
+                narrow_flags |= flag;
+            }
+        }
+        // conversion back is exact
+        let (v, _) = Ext::convert::<15, MANT_BITS>(
+            narrow,
+            RoundingMode::NearestTiesToEven,
+            tininess_detection,
+        );
+        (v, narrow_flags)
+    }
+
+    pub fn add_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1],
+            rounding_precision,
+            rounding_mode,
+            tininess_detection,
+            |[v0, v1], rounding_mode| Ext::add(v0, v1, rounding_mode, tininess_detection),
+        )
+    }
+
+    pub fn sub_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1],
+            rounding_precision,
+            rounding_mode,
+            tininess_detection,
+            |[v0, v1], rounding_mode| Ext::sub(v0, v1, rounding_mode, tininess_detection),
+        )
+    }
+
+    pub fn mul_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1],
+            rounding_precision,
+            rounding_mode,
+            tininess_detection,
+            |[v0, v1], rounding_mode| Ext::mul(v0, v1, rounding_mode, tininess_detection),
+        )
+    }
+
+    pub fn div_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1],
+            rounding_precision,
+            rounding_mode,
+            tininess_detection,
+            |[v0, v1], rounding_mode| Ext::div(v0, v1, rounding_mode, tininess_detection),
+        )
+    }
+
+    // the x87 has no fused multiply-add, but the result is rounded to the
+    // given precision all the same
+    pub fn mul_add_with_flags(
+        v0: Self,
+        v1: Self,
+        v2: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1, v2],
+            rounding_precision,
+            rounding_mode,
+            tininess_detection,
+            |[v0, v1, v2], rounding_mode| {
+                Ext::mul_add(v0, v1, v2, rounding_mode, tininess_detection)
+            },
+        )
+    }
+
+    pub fn sqrt_with_flags(
+        v: Self,
+        rounding_precision: RoundingPrecision,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v],
+            rounding_precision,
+            rounding_mode,
+            TininessDetection::default(),
+            |[v], rounding_mode| Ext::sqrt(v, rounding_mode),
+        )
+    }
+
+    // the remainder and rounding to integral values are exact, so the
+    // rounding precision does not apply
+    pub fn rem_with_flags(v0: Self, v1: Self) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v0, v1],
+            RoundingPrecision::Extended,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+            |[v0, v1], _| Ext::rem(v0, v1),
+        )
+    }
+
+    pub fn round_ties_even_with_flags(v: Self) -> (Self, ExceptionFlags) {
+        Self::round_to_integral_with_flags(v, RoundingMode::NearestTiesToEven)
+    }
+
+    // see SoftFloat::round_to_integral_with_flags
+    pub fn round_to_integral_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v],
+            RoundingPrecision::Extended,
+            rounding_mode,
+            TininessDetection::default(),
+            |[v], rounding_mode| Ext::round_to_integral(v, rounding_mode),
+        )
+    }
+
+    // see SoftFloat::round_to_integral_exact_with_flags
+    pub fn round_to_integral_exact_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::apply(
+            [v],
+            RoundingPrecision::Extended,
+            rounding_mode,
+            TininessDetection::default(),
+            |[v], rounding_mode| Ext::round_to_integral_exact(v, rounding_mode),
+        )
+    }
+
+    // quiet comparison, see SoftFloat::eq_with_flags; unsupported encodings
+    // are invalid operands
+    pub fn eq_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        match (Self::raw(v0), Self::raw(v1)) {
+            (Some(v0), Some(v1)) => Ext::eq(v0, v1),
+            _ => (false, ExceptionFlags::INVALID),
+        }
+    }
+
+    // signaling comparisons, see SoftFloat::le_with_flags
+    pub fn le_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        match v0.partial_cmp(&v1) {
+            None => (false, ExceptionFlags::INVALID),
+            Some(ordering) => (ordering != Ordering::Greater, ExceptionFlags::NONE),
+        }
+    }
+
+    pub fn lt_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        match v0.partial_cmp(&v1) {
+            None => (false, ExceptionFlags::INVALID),
+            Some(ordering) => (ordering == Ordering::Less, ExceptionFlags::NONE),
+        }
+    }
+
+    // conversions are not affected by the rounding precision; converting
+    // formats with at most 64 significand bits (including the implicit bit)
+    // and 15 exponent bits is exact
+    pub fn from_soft_float_with_flags<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        value: SoftFloat<T, EXP_BITS, MANT_BITS>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        let (v, flags) = Ext::convert::<EXP_BITS, MANT_BITS>(
+            SoftFloat::raw(value),
+            rounding_mode,
+            tininess_detection,
+        );
+        (Self::from_raw(v), flags)
+    }
+
+    pub fn to_soft_float_with_flags<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        v: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (SoftFloat<T, EXP_BITS, MANT_BITS>, ExceptionFlags) {
+        let v = match Self::raw(v) {
+            Some(v) => v,
+            None => {
+                return (
+                    SoftFloat::from_raw(Format::<EXP_BITS, MANT_BITS>::NAN),
+                    ExceptionFlags::INVALID,
+                )
+            }
+        };
+        SoftFloat::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::convert::<15, 63>(
+            v,
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+
+    // rounds to nearest, ties to even, see SoftFloat::to_i32_with_flags
+    pub fn to_i32_with_flags(v: Self) -> (i32, ExceptionFlags) {
+        match Self::raw(v) {
+            Some(v) => Ext::to_i32(v),
+            None => (i32::MIN, ExceptionFlags::INVALID),
+        }
+    }
+}
+
+// conversion is always exact
+impl From<i32> for ExtFloat80 {
+    fn from(value: i32) -> Self {
+        let (v, _) = Ext::from_integer(
+            (value < 0) as u32,
+            value.unsigned_abs() as u128,
+            RoundingMode::NearestTiesToEven,
+        );
+        Self::from_raw(v)
+    }
+}
+
+impl From<ExtFloat80> for i32 {
+    fn from(value: ExtFloat80) -> Self {
+        ExtFloat80::to_i32_with_flags(value).0
+    }
+}
+
+// conversion is always exact
+impl From<SoftFloat16> for ExtFloat80 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl From<ExtFloat80> for SoftFloat16 {
+    fn from(value: ExtFloat80) -> Self {
+        ExtFloat80::to_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+// conversion is always exact
+impl From<f32> for ExtFloat80 {
+    fn from(value: f32) -> Self {
+        Self::from_soft_float_with_flags(
+            SoftFloat32::from_bits(value.to_bits()),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl From<ExtFloat80> for f32 {
+    fn from(value: ExtFloat80) -> Self {
+        let (v, _): (SoftFloat32, _) = ExtFloat80::to_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        );
+        f32::from_bits(SoftFloat32::to_bits(v))
+    }
+}
+
+// conversion is always exact
+impl From<f64> for ExtFloat80 {
+    fn from(value: f64) -> Self {
+        Self::from_soft_float_with_flags(
+            SoftFloat64::from_bits(value.to_bits()),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl From<ExtFloat80> for f64 {
+    fn from(value: ExtFloat80) -> Self {
+        let (v, _): (SoftFloat64, _) = ExtFloat80::to_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        );
+        f64::from_bits(SoftFloat64::to_bits(v))
+    }
+}
+
+// operators round to nearest, ties to even, with extended precision, the
+// default of the x87 control word
+
+impl Add for ExtFloat80 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::add_with_flags(
+            self,
+            other,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl Sub for ExtFloat80 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::sub_with_flags(
+            self,
+            other,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl Mul for ExtFloat80 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::mul_with_flags(
+            self,
+            other,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl Div for ExtFloat80 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self::div_with_flags(
+            self,
+            other,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl Rem for ExtFloat80 {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        Self::rem_with_flags(self, other).0
+    }
+}
+
+impl Neg for ExtFloat80 {
+    type Output = Self;
+
+    // only flips the sign bit, like fchs
+    fn neg(self) -> Self {
+        Self(self.0 ^ 1 << 79)
+    }
+}
+
+impl PartialEq for ExtFloat80 {
+    fn eq(&self, other: &Self) -> bool {
+        Self::eq_with_flags(*self, *other).0
+    }
+}
+
+impl PartialOrd for ExtFloat80 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Ext::partial_cmp(Self::raw(*self)?, Self::raw(*other)?)
+    }
+}
+
+impl Sqrt for ExtFloat80 {
+    fn sqrt(v: Self) -> Self {
+        Self::sqrt_with_flags(
+            v,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+        )
+        .0
+    }
+}
+
+impl RoundTiesEven for ExtFloat80 {
+    fn round_ties_even(v: Self) -> Self {
+        Self::round_ties_even_with_flags(v).0
+    }
+}
+
+impl Round for ExtFloat80 {
+    fn round(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::NearestTiesToAway).0
+    }
+}
+
+impl Floor for ExtFloat80 {
+    fn floor(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardNegative).0
+    }
+}
+
+impl Ceil for ExtFloat80 {
+    fn ceil(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardPositive).0
+    }
+}
+
+impl Trunc for ExtFloat80 {
+    fn trunc(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardZero).0
+    }
+}
+
+impl MulAdd for ExtFloat80 {
+    fn mul_add(v0: Self, v1: Self, v2: Self) -> Self {
+        Self::mul_add_with_flags(
+            v0,
+            v1,
+            v2,
+            RoundingPrecision::default(),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{XorShift, ROUNDING_MODES};
+
+    const TININESS_DETECTIONS: [TininessDetection; 2] = [
+        TininessDetection::BeforeRounding,
+        TininessDetection::AfterRounding,
+    ];
+
+    // special values of the IEEE-like format with 15 exponent and MANT_BITS
+    // significand bits (both signs) followed by pseudo-random
+    // numbers, half of which lie close to one; without NANs, which would be
+    // quieted when converted
+    fn operands<const MANT_BITS: u32>(n: usize) -> Vec<u128> {
+        type F<const M: u32> = Format<15, M>;
+        let (bias, mask) = (F::<MANT_BITS>::BIAS, F::<MANT_BITS>::SIGNIFICAND_MASK);
+        let special = [
+            (0, 0),
+            (0, 1),
+            (0, mask),
+            (1, 0),
+            (1, 1),
+            (bias - MANT_BITS as i32 - 1, 0),
+            (bias - 1, mask),
+            (bias, 0),
+            (bias, 1),
+            (bias, mask),
+            (bias + MANT_BITS as i32, mask),
+            (0x7FFE, mask),
+            (0x7FFF, 0),
+        ]
+        .into_iter()
+        .flat_map(|(e, s)| [F::<MANT_BITS>::pack(0, e, s), F::<MANT_BITS>::pack(1, e, s)]);
+
+        let mut rng = XorShift::new();
+        let mut next = move || rng.next_u64();
+        let random = (0..n).map(|i| {
+            let bits = (next() as u128) << 64 | next() as u128;
+            let (sign, exponent, significand) = F::<MANT_BITS>::unpack(bits);
+            let exponent = if i % 2 == 0 {
+                bias - 64 + exponent % 128
+            } else {
+                exponent
+            };
+            F::<MANT_BITS>::pack(sign, exponent, significand)
+        });
+
+        special
+            .chain(random)
+            .filter(|&v| !F::<MANT_BITS>::is_nan(v))
+            .collect()
+    }
+
+    fn check(y: ExtFloat80, y_expected: ExtFloat80, args: &[u128]) {
+        if ExtFloat80::is_nan(y) || ExtFloat80::is_nan(y_expected) {
+            assert!(ExtFloat80::is_nan(y), "{:020x?}", args);
+            assert!(ExtFloat80::is_nan(y_expected), "{:020x?}", args);
+        } else {
+            assert_eq!(
+                ExtFloat80::to_bits(y),
+                ExtFloat80::to_bits(y_expected),
+                "{:020x?}",
+                args
+            );
+        }
+        // all results are canonical
+        assert!(!ExtFloat80::is_unsupported(y), "{:020x?}", args);
+    }
+
+    #[test]
+    fn test_encodings() {
+        let one = ExtFloat80::from_bits(0x3FFF_8000_0000_0000_0000);
+        let nan = ExtFloat80::from_bits(0x7FFF_C000_0000_0000_0000);
+
+        // pseudo-denormals are read as normal numbers, denormals that become
+        // normal get their integer bit set
+        for (x0, x1, y) in [
+            (
+                0x0000_8000_0000_0000_0001,
+                0x0000_0000_0000_0000_0000,
+                0x0001_8000_0000_0000_0001,
+            ),
+            (
+                0x0000_4000_0000_0000_0000,
+                0x0000_4000_0000_0000_0000,
+                0x0001_8000_0000_0000_0000,
+            ),
+            (
+                0x8000_8000_0000_0000_0000,
+                0x0000_0000_0000_0000_0001,
+                0x8000_7FFF_FFFF_FFFF_FFFF,
+            ),
+        ] {
+            let (x0, x1) = (ExtFloat80::from_bits(x0), ExtFloat80::from_bits(x1));
+            let (z, flags) = ExtFloat80::add_with_flags(
+                x0,
+                x1,
+                RoundingPrecision::Extended,
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::AfterRounding,
+            );
+            assert_eq!(ExtFloat80::to_bits(z), y);
+            assert_eq!(flags, ExceptionFlags::NONE);
+        }
+        let pseudo_denormal = ExtFloat80::from_bits(0x0000_8000_0000_0000_0000);
+        assert!(pseudo_denormal == ExtFloat80::from_bits(0x0001_8000_0000_0000_0000));
+        assert!(!ExtFloat80::is_unsupported(pseudo_denormal));
+
+        // unnormals, pseudo-infinities and pseudo-NANs are invalid operands
+        for x in [
+            0x3FFF_4000_0000_0000_0000,
+            0x0001_0000_0000_0000_0000,
+            0xFFFF_0000_0000_0000_0000,
+            0x7FFF_4000_0000_0000_0000,
+        ] {
+            let x = ExtFloat80::from_bits(x);
+            assert!(ExtFloat80::is_unsupported(x));
+            for (y, flags) in [
+                ExtFloat80::add_with_flags(
+                    x,
+                    one,
+                    RoundingPrecision::Extended,
+                    RoundingMode::NearestTiesToEven,
+                    TininessDetection::AfterRounding,
+                ),
+                ExtFloat80::mul_with_flags(
+                    one,
+                    x,
+                    RoundingPrecision::Single,
+                    RoundingMode::NearestTiesToEven,
+                    TininessDetection::AfterRounding,
+                ),
+                ExtFloat80::sqrt_with_flags(
+                    x,
+                    RoundingPrecision::Double,
+                    RoundingMode::NearestTiesToEven,
+                ),
+                ExtFloat80::rem_with_flags(one, x),
+                ExtFloat80::round_ties_even_with_flags(x),
+            ] {
+                assert_eq!(ExtFloat80::to_bits(y), ExtFloat80::to_bits(nan));
+                assert_eq!(flags, ExceptionFlags::INVALID);
+            }
+            assert_eq!(
+                ExtFloat80::eq_with_flags(x, x),
+                (false, ExceptionFlags::INVALID)
+            );
+            assert_eq!(
+                ExtFloat80::to_i32_with_flags(x),
+                (i32::MIN, ExceptionFlags::INVALID)
+            );
+            let (y, flags): (SoftFloat16, _) = ExtFloat80::to_soft_float_with_flags(
+                x,
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::AfterRounding,
+            );
+            assert!(SoftFloat16::is_nan(y));
+            assert_eq!(flags, ExceptionFlags::INVALID);
+            assert_eq!(ExtFloat80::to_bits(-x), ExtFloat80::to_bits(x) ^ 1 << 79);
+        }
+    }
+
+    #[test]
+    fn test_precision() {
+        // 1 + 2^-30 needs 31 bits
+        let x0 = ExtFloat80::from(1.0_f64);
+        let x1 = ExtFloat80::from(2.0_f64.powi(-30));
+        for (rounding_precision, y, flags) in [
+            (RoundingPrecision::Single, 1.0, ExceptionFlags::INEXACT),
+            (
+                RoundingPrecision::Double,
+                1.0 + 2.0_f64.powi(-30),
+                ExceptionFlags::NONE,
+            ),
+        ] {
+            let (z, z_flags) = ExtFloat80::add_with_flags(
+                x0,
+                x1,
+                rounding_precision,
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::AfterRounding,
+            );
+            assert_eq!(f64::from(z), y);
+            assert_eq!(z_flags, flags);
+        }
+
+        // the exponent range is not reduced: 2^-200 * 2^-200 is a normal
+        // number even with single precision
+        let x = ExtFloat80::from(2.0_f64.powi(-200));
+        let (z, flags) = ExtFloat80::mul_with_flags(
+            x,
+            x,
+            RoundingPrecision::Single,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        );
+        assert_eq!(ExtFloat80::to_bits(z), 0x3E6F_8000_0000_0000_0000);
+        assert_eq!(flags, ExceptionFlags::NONE);
+
+        // the sign of an exact zero sum depends on the rounding mode only
+        for rounding_precision in [RoundingPrecision::Single, RoundingPrecision::Double] {
+            let (z, _) = ExtFloat80::sub_with_flags(
+                x0,
+                x0,
+                rounding_precision,
+                RoundingMode::TowardNegative,
+                TininessDetection::AfterRounding,
+            );
+            assert_eq!(ExtFloat80::to_bits(z), 0x8000_0000_0000_0000_0000);
+        }
+    }
+
+    // with extended precision, results are those of the IEEE-like format
+    // with the same number of bits, apart from the integer bit
+    #[test]
+    fn test_extended() {
+        type Wide = SoftFloat<u128, 15, 63>;
+        let operands = operands::<63>(60);
+        for &i in &operands {
+            let x0 = ExtFloat80::from_raw(i);
+            let w0 = Wide::from_raw(i);
+            for rounding_mode in ROUNDING_MODES {
+                let (y, flags) =
+                    ExtFloat80::sqrt_with_flags(x0, RoundingPrecision::Extended, rounding_mode);
+                let (w, w_flags) = Wide::sqrt_with_flags(w0, rounding_mode);
+                check(y, ExtFloat80::from_raw(Wide::raw(w)), &[i]);
+                assert_eq!(flags, w_flags, "{:020x?}", i);
+            }
+
+            for &j in &operands {
+                let x1 = ExtFloat80::from_raw(j);
+                let w1 = Wide::from_raw(j);
+                let (y, flags) = ExtFloat80::rem_with_flags(x0, x1);
+                let (w, w_flags) = Wide::rem_with_flags(w0, w1);
+                check(y, ExtFloat80::from_raw(Wide::raw(w)), &[i, j]);
+                assert_eq!(flags, w_flags, "{:020x?}", (i, j));
+                assert_eq!(x0.partial_cmp(&x1), w0.partial_cmp(&w1));
+
+                for rounding_mode in ROUNDING_MODES {
+                    for tininess_detection in TININESS_DETECTIONS {
+                        for (y, w) in [
+                            (
+                                ExtFloat80::add_with_flags(
+                                    x0,
+                                    x1,
+                                    RoundingPrecision::Extended,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Wide::add_with_flags(w0, w1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::mul_with_flags(
+                                    x0,
+                                    x1,
+                                    RoundingPrecision::Extended,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Wide::mul_with_flags(w0, w1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::div_with_flags(
+                                    x0,
+                                    x1,
+                                    RoundingPrecision::Extended,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Wide::div_with_flags(w0, w1, rounding_mode, tininess_detection),
+                            ),
+                        ] {
+                            check(y.0, ExtFloat80::from_raw(Wide::raw(w.0)), &[i, j]);
+                            assert_eq!(y.1, w.1, "{:020x?}", (i, j, rounding_mode));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // with single and double precision, results for operands representable
+    // with that precision are those of the IEEE-like format with 15 exponent
+    // and MANT_BITS significand bits
+    fn check_precision<T: Storage, const MANT_BITS: u32>(
+        rounding_precision: RoundingPrecision,
+        n: usize,
+    ) {
+        type Narrow<T, const M: u32> = SoftFloat<T, 15, M>;
+        let widen = |v: Narrow<T, MANT_BITS>| {
+            ExtFloat80::from_soft_float_with_flags(
+                v,
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::default(),
+            )
+            .0
+        };
+        let operands = operands::<MANT_BITS>(n);
+        for &i in &operands {
+            let v0 = Narrow::<T, MANT_BITS>::from_raw(i);
+            let x0 = widen(v0);
+            for rounding_mode in ROUNDING_MODES {
+                let (y, flags) = ExtFloat80::sqrt_with_flags(x0, rounding_precision, rounding_mode);
+                let (v, v_flags) = Narrow::sqrt_with_flags(v0, rounding_mode);
+                check(y, widen(v), &[i]);
+                assert_eq!(flags, v_flags, "{:020x?}", (i, rounding_mode));
+            }
+
+            for &j in &operands {
+                let v1 = Narrow::<T, MANT_BITS>::from_raw(j);
+                let x1 = widen(v1);
+                for rounding_mode in ROUNDING_MODES {
+                    for tininess_detection in TININESS_DETECTIONS {
+                        for (y, v) in [
+                            (
+                                ExtFloat80::add_with_flags(
+                                    x0,
+                                    x1,
+                                    rounding_precision,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Narrow::add_with_flags(v0, v1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::sub_with_flags(
+                                    x0,
+                                    x1,
+                                    rounding_precision,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Narrow::sub_with_flags(v0, v1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::mul_with_flags(
+                                    x0,
+                                    x1,
+                                    rounding_precision,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Narrow::mul_with_flags(v0, v1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::div_with_flags(
+                                    x0,
+                                    x1,
+                                    rounding_precision,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Narrow::div_with_flags(v0, v1, rounding_mode, tininess_detection),
+                            ),
+                            (
+                                ExtFloat80::mul_add_with_flags(
+                                    x0,
+                                    x1,
+                                    x0,
+                                    rounding_precision,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                                Narrow::mul_add_with_flags(
+                                    v0,
+                                    v1,
+                                    v0,
+                                    rounding_mode,
+                                    tininess_detection,
+                                ),
+                            ),
+                        ] {
+                            check(y.0, widen(v.0), &[i, j]);
+                            assert_eq!(
+                                y.1,
+                                v.1,
+                                "{:020x?}",
+                                (i, j, rounding_mode, tininess_detection)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_precision() {
+        check_precision::<u64, 23>(RoundingPrecision::Single, 60);
+    }
+
+    #[test]
+    fn test_double_precision() {
+        check_precision::<u128, 52>(RoundingPrecision::Double, 60);
+    }
+
+    #[test]
+    fn test_conversions() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = ExtFloat80::from(x);
+            assert_eq!(f64::from(y).to_bits(), (f32::from(x) as f64).to_bits());
+            // round trip
+            if !SoftFloat16::is_nan(x) {
+                assert_eq!(SoftFloat16::to_bits(SoftFloat16::from(y)), i);
+            }
+        }
+
+        let mut rng = XorShift::new();
+        for _ in 0..100000 {
+            let state = rng.next_u64();
+            let x = f64::from_bits(state);
+            if x.is_nan() {
+                continue;
+            }
+            let y = ExtFloat80::from(x);
+            assert_eq!(f64::from(y).to_bits(), state, "{:016x}", state);
+            assert_eq!(
+                f32::from(y).to_bits(),
+                (x as f32).to_bits(),
+                "{:016x}",
+                state
+            );
+            assert_eq!(
+                f64::from(ExtFloat80::from(x as f32)).to_bits(),
+                (x as f32 as f64).to_bits()
+            );
+        }
+
+        // rounding to f64 once, not via an intermediate format
+        let x = ExtFloat80::from_bits(0x3FFF_8000_0000_0000_0400);
+        assert_eq!(f64::from(x), 1.0);
+        let x = ExtFloat80::from_bits(0x3FFF_8000_0000_0000_0401);
+        assert_eq!(f64::from(x), 1.0 + f64::EPSILON);
+
+        for i in [0, 1, -1, 1 << 30, i32::MAX, i32::MIN] {
+            assert_eq!(i32::from(ExtFloat80::from(i)), i);
+            assert_eq!(f64::from(ExtFloat80::from(i)), i as f64);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{ExceptionFlags, RoundingMode, TininessDetection};
+
+// binary interchange format with EXP_BITS exponent and MANT_BITS (explicitly
+// stored) significand bits, see IEEE 754, sec. 3.6; implements the algorithms
+// of all operations independent of the storage type of SoftFloat, i.e.,
+// encodings are passed around as u128; the algorithms of the arithmetic
+// operations, comparisons and conversions are const fns, which SoftFloat16
+// exposes in const contexts
+//
+// significands are handled as integers that look like 1.x{MANT_BITS} with
+// three additional guard, round, sticky bits; multiplication, the fused
+// multiply-add and the square root keep twice as many bits in a U256, so
+// encodings of up to 128 bits (binary128) are supported
+pub(crate) struct Format<const EXP_BITS: u32, const MANT_BITS: u32>;
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const IS_VALID: () = assert!(
+        EXP_BITS >= 2 && EXP_BITS <= 16 && MANT_BITS >= 2 && 1 + EXP_BITS + MANT_BITS <= 128,
+        "unsupported number of exponent or significand bits"
+    );
+
+    pub(crate) const BITS: u32 = 1 + EXP_BITS + MANT_BITS;
+
+    pub(crate) const BIAS: i32 = (1 << (EXP_BITS - 1)) - 1;
+
+    // biased exponent of infinity and NANs
+    pub(crate) const EXPONENT_MASK: i32 = (1 << EXP_BITS) - 1;
+
+    pub(crate) const SIGNIFICAND_MASK: u128 = (1 << MANT_BITS) - 1;
+
+    pub(crate) const IMPLICIT_BIT: u128 = 1 << MANT_BITS;
+
+    // the most significant bit of the significand of a NAN distinguishes
+    // quiet (set) from signaling (cleared) NANs, see IEEE 754, sec. 6.2.1
+    pub(crate) const QUIET_BIT: u128 = 1 << (MANT_BITS - 1);
+
+    pub(crate) const SIGN_BIT: u128 = 1 << (EXP_BITS + MANT_BITS);
+
+    // default NAN, returned by invalid operations
+    pub(crate) const NAN: u128 = (Self::EXPONENT_MASK as u128) << MANT_BITS | Self::QUIET_BIT;
+
+    // splits an encoding into sign, biased exponent and significand (without
+    // implicit bit)
+    pub(crate) const fn unpack(v: u128) -> (u32, i32, u128) {
+        (
+            (v >> (EXP_BITS + MANT_BITS)) as u32 & 1,
+            (v >> MANT_BITS) as i32 & Self::EXPONENT_MASK,
+            v & Self::SIGNIFICAND_MASK,
+        )
+    }
+
+    pub(crate) const fn pack(sign: u32, exponent: i32, significand: u128) -> u128 {
+        (sign as u128) << (EXP_BITS + MANT_BITS) | (exponent as u128) << MANT_BITS | significand
+    }
+
+    pub(crate) const fn zero(sign: u32) -> u128 {
+        Self::pack(sign, 0, 0)
+    }
+
+    pub(crate) const fn infinity(sign: u32) -> u128 {
+        Self::pack(sign, Self::EXPONENT_MASK, 0)
+    }
+
+    // handles denormals and implicit bit; the resulting significand looks
+    // like 1.x{MANT_BITS}, denormals end up with exponents below one
+    pub(crate) const fn normalize(exponent: i32, significand: u128) -> (i32, u128) {
+        if exponent == 0 {
+            let mut exponent = 1;
+            let mut significand = significand;
+            assert!(significand != 0);
+            while significand & Self::IMPLICIT_BIT == 0 {
+                significand <<= 1;
+                exponent -= 1;
+            }
+            (exponent, significand)
+        } else {
+            (exponent, significand | Self::IMPLICIT_BIT)
+        }
+    }
+
+    // rounds a significand that looks like 1.x{MANT_BITS} with three
+    // additional guard, round, sticky bits and combines it with sign and
+    // (biased) exponent; an exponent below one is representable by shifting
+    // the significand to obtain a denormal number, an exponent of
+    // EXPONENT_MASK or above leads to overflow
+    pub(crate) const fn round_and_pack(
+        sign: u32,
+        exponent: i32,
+        significand: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        // a result is tiny before rounding if it is below the smallest normal
+        // number; after rounding, only numbers that look like 1.1{MANT_BITS}
+        // with exponent zero can be rounded up to a normal number (as if the
+        // exponent range were unbounded) and are not tiny
+        let is_tiny =
+            if exponent <= 0 || (exponent == 1 && significand & (Self::IMPLICIT_BIT << 3) == 0) {
+                match tininess_detection {
+                    TininessDetection::BeforeRounding => true,
+                    TininessDetection::AfterRounding => {
+                        let significand = match exponent {
+                            1 => significand << 1,
+                            0 => significand,
+                            _ => 0,
+                        };
+                        !(significand >> 3 == (Self::IMPLICIT_BIT << 1) - 1
+                            && rounding_mode.round_up(
+                                sign,
+                                1,
+                                significand & 0x4 != 0,
+                                significand & 0x3 != 0,
+                            ))
+                    }
+                }
+            } else {
+                false
+            };
+
+        let (exponent, significand) = if exponent <= 0 {
+            // must convert to denormal number; make exponent representable by
+            // shifting significand
+            (1, shift_right_sticky(significand, 1 - exponent))
+        } else if exponent >= Self::EXPONENT_MASK {
+            // overflow
+            let flags = ExceptionFlags::OVERFLOW.union(ExceptionFlags::INEXACT);
+            return if rounding_mode.overflows_to_infinity(sign) {
+                (Self::infinity(sign), flags)
+            } else {
+                (
+                    Self::pack(sign, Self::EXPONENT_MASK - 1, Self::SIGNIFICAND_MASK),
+                    flags,
+                )
+            };
+        } else {
+            (exponent, significand)
+        };
+
+        // rounding
+        let grs = significand & 0x7;
+        let significand = significand >> 3;
+        let lsb = (significand & 1) as u32;
+        let rnd = rounding_mode.round_up(sign, lsb, grs & 0x4 != 0, grs & 0x3 != 0) as u128;
+
+        let mut flags = ExceptionFlags::NONE;
+        if grs != 0 {
+            flags = flags.union(ExceptionFlags::INEXACT);
+            if is_tiny {
+                flags = flags.union(ExceptionFlags::UNDERFLOW);
+            }
+        }
+
+        // denormals have exponent 0
+        let exponent = if exponent == 1 && significand < Self::IMPLICIT_BIT {
+            0
+        } else {
+            exponent
+        };
+
+        // cut off implicit bit and allow overflow into exponent
+        let v = Self::pack(sign, exponent, significand & Self::SIGNIFICAND_MASK) + rnd;
+        if Self::unpack(v).1 == Self::EXPONENT_MASK {
+            // rounding overflowed into infinity
+            flags = flags.union(ExceptionFlags::OVERFLOW);
+        }
+
+        (v, flags)
+    }
+}
+
+// shifts right and ORs all bits shifted out into the least significant bit
+pub(crate) const fn shift_right_sticky(significand: u128, shift: i32) -> u128 {
+    assert!(shift >= 0);
+    if shift >= 128 {
+        (significand != 0) as u128
+    } else {
+        let sticky_bits = (1 << shift) - 1;
+        let sticky = (significand & sticky_bits != 0) as u128;
+        (significand >> shift) | sticky
+    }
+}
@@ -0,0 +1,749 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+use crate::format::Format;
+use crate::{
+    Ceil, ExceptionFlags, Floor, MulAdd, OverflowMode, Round, RoundTiesEven, RoundingMode,
+    SoftFloat, SoftFloat16, Sqrt, TininessDetection, Trunc,
+};
+
+// 8-bit formats of the OCP 8-bit Floating Point Specification (OFP8); E5M2
+// follows IEEE 754 conventions and is an instance of SoftFloat
+pub type SoftFloat8E5M2 = SoftFloat<u8, 5, 2>;
+
+impl SoftFloat8E5M2 {
+    pub fn from_f32_with_overflow(
+        value: f32,
+        rounding_mode: RoundingMode,
+        overflow_mode: OverflowMode,
+    ) -> (Self, ExceptionFlags) {
+        let (v, flags) =
+            Self::from_f32_with_flags(value, rounding_mode, TininessDetection::default());
+        (Self::saturate(v, overflow_mode), flags)
+    }
+
+    pub fn from_soft_float16_with_overflow(
+        value: SoftFloat16,
+        rounding_mode: RoundingMode,
+        overflow_mode: OverflowMode,
+    ) -> (Self, ExceptionFlags) {
+        let (v, flags) =
+            Self::from_soft_float_with_flags(value, rounding_mode, TininessDetection::default());
+        (Self::saturate(v, overflow_mode), flags)
+    }
+
+    // replaces infinities by the largest finite number of the same sign
+    fn saturate(v: Self, overflow_mode: OverflowMode) -> Self {
+        let bits = Self::to_bits(v);
+        if overflow_mode == OverflowMode::Saturating && bits & 0x7f == 0x7c {
+            Self::from_bits(bits - 1)
+        } else {
+            v
+        }
+    }
+}
+
+impl From<SoftFloat16> for SoftFloat8E5M2 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+// conversion is always exact
+impl From<SoftFloat8E5M2> for SoftFloat16 {
+    fn from(value: SoftFloat8E5M2) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+// E4M3 deviates from IEEE 754: there are no infinities and the largest
+// exponent holds normal numbers, except for S.1111.111, which is NAN; the
+// largest finite number is thus 448
+#[derive(Clone, Copy, Debug)]
+pub struct SoftFloat8E4M3(u8);
+
+const E4M3_NAN: u8 = 0x7f;
+const E4M3_MAX: u8 = 0x7e;
+
+impl SoftFloat8E4M3 {
+    pub fn from_bits(v: u8) -> Self {
+        Self(v)
+    }
+
+    pub fn to_bits(v: Self) -> u8 {
+        v.0
+    }
+
+    pub fn sign(v: Self) -> u8 {
+        v.0 >> 7
+    }
+
+    pub fn exponent(v: Self) -> u8 {
+        (v.0 >> 3) & 0xF
+    }
+
+    pub fn significand(v: Self) -> u8 {
+        v.0 & 0x7
+    }
+
+    pub fn is_nan(v: Self) -> bool {
+        v.0 & 0x7f == E4M3_NAN
+    }
+
+    pub fn from_f32_with_overflow(
+        value: f32,
+        rounding_mode: RoundingMode,
+        overflow_mode: OverflowMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::narrow::<8, 23>(value.to_bits() as u128, rounding_mode, overflow_mode)
+    }
+
+    pub fn from_soft_float16_with_overflow(
+        value: SoftFloat16,
+        rounding_mode: RoundingMode,
+        overflow_mode: OverflowMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::narrow::<5, 10>(SoftFloat::raw(value), rounding_mode, overflow_mode)
+    }
+
+    // rounds a number in the format with EXP_BITS and MANT_BITS bits; apart
+    // from the largest exponent, the layout is the same as the IEEE-like
+    // format with 4 exponent and 3 significand bits, and for numbers with
+    // the largest exponent (2^8 and above) the same as the one with 5
+    // exponent bits since all of these numbers are normal
+    fn narrow<const EXP_BITS: u32, const MANT_BITS: u32>(
+        v: u128,
+        rounding_mode: RoundingMode,
+        overflow_mode: OverflowMode,
+    ) -> (Self, ExceptionFlags) {
+        let (sign, exponent, significand) = Format::<EXP_BITS, MANT_BITS>::unpack(v);
+        let sign_bit = (sign as u8) << 7;
+
+        if exponent == Format::<EXP_BITS, MANT_BITS>::EXPONENT_MASK && significand != 0 {
+            // NAN; there is no payload, so the sign is all that is kept
+            let flags = if Format::<EXP_BITS, MANT_BITS>::is_signaling(v) {
+                ExceptionFlags::INVALID
+            } else {
+                ExceptionFlags::NONE
+            };
+            return (Self(sign_bit | E4M3_NAN), flags);
+        } else if exponent == Format::<EXP_BITS, MANT_BITS>::EXPONENT_MASK {
+            // oo; there is no infinity to convert to
+            return match overflow_mode {
+                OverflowMode::NonSaturating => (Self(sign_bit | E4M3_NAN), ExceptionFlags::INVALID),
+                OverflowMode::Saturating => (Self(sign_bit | E4M3_MAX), ExceptionFlags::NONE),
+            };
+        }
+
+        let unbiased_exponent = exponent - Format::<EXP_BITS, MANT_BITS>::BIAS;
+        if unbiased_exponent < 8 {
+            let (bits, flags) = Format::<4, 3>::convert::<EXP_BITS, MANT_BITS>(
+                v,
+                rounding_mode,
+                TininessDetection::default(),
+            );
+            return if Format::<4, 3>::unpack(bits).1 == Format::<4, 3>::EXPONENT_MASK {
+                // rounding up to 2^8 looks like an overflow in the IEEE-like
+                // format, but the result is a normal number
+                (Self(bits as u8), ExceptionFlags::INEXACT)
+            } else {
+                (Self(bits as u8), flags)
+            };
+        }
+
+        let (bits, flags) = Format::<5, 3>::convert::<EXP_BITS, MANT_BITS>(
+            v,
+            rounding_mode,
+            TininessDetection::default(),
+        );
+        let (_, exponent, significand) = Format::<5, 3>::unpack(bits);
+        let exponent = exponent - Format::<5, 3>::BIAS + 7;
+        if exponent > 15 || (exponent == 15 && significand == 0x7) {
+            // overflow
+            let flags = ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT;
+            return if overflow_mode == OverflowMode::NonSaturating
+                && rounding_mode.overflows_to_infinity(sign)
+            {
+                (Self(sign_bit | E4M3_NAN), flags)
+            } else {
+                (Self(sign_bit | E4M3_MAX), flags)
+            };
+        }
+
+        (
+            Self(sign_bit | (exponent as u8) << 3 | significand as u8),
+            flags,
+        )
+    }
+}
+
+impl From<f32> for SoftFloat8E4M3 {
+    fn from(value: f32) -> Self {
+        Self::from_f32_with_overflow(
+            value,
+            RoundingMode::NearestTiesToEven,
+            OverflowMode::NonSaturating,
+        )
+        .0
+    }
+}
+
+impl From<SoftFloat16> for SoftFloat8E4M3 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float16_with_overflow(
+            value,
+            RoundingMode::NearestTiesToEven,
+            OverflowMode::NonSaturating,
+        )
+        .0
+    }
+}
+
+// conversion is always exact
+impl From<SoftFloat8E4M3> for SoftFloat16 {
+    fn from(value: SoftFloat8E4M3) -> Self {
+        let bits = SoftFloat8E4M3::to_bits(value) as u128;
+        let (sign, exponent, significand) = Format::<4, 3>::unpack(bits);
+
+        if SoftFloat8E4M3::is_nan(value) {
+            Self::from_raw(Format::<5, 10>::NAN | (sign as u128) << 15)
+        } else if exponent == 0xF {
+            // numbers with the largest exponent are normal
+            Self::from_raw(Format::<5, 10>::pack(
+                sign,
+                8 + Format::<5, 10>::BIAS,
+                significand << 7,
+            ))
+        } else {
+            Self::from_raw(
+                Format::<5, 10>::convert::<4, 3>(
+                    bits,
+                    RoundingMode::NearestTiesToEven,
+                    TininessDetection::default(),
+                )
+                .0,
+            )
+        }
+    }
+}
+
+// conversion is always exact
+impl From<SoftFloat8E4M3> for f32 {
+    fn from(value: SoftFloat8E4M3) -> Self {
+        f32::from(SoftFloat16::from(value))
+    }
+}
+
+// arithmetic is carried out in binary16, which holds all E4M3 numbers and
+// has more than twice their precision; thus, rounding the binary16 result of
+// +, -, *, / and sqrt once more yields the correctly rounded result, and the
+// remainder as well as rounding to integral values are exact anyway
+
+impl Add for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::from(SoftFloat16::from(self) + SoftFloat16::from(other))
+    }
+}
+
+impl Sub for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::from(SoftFloat16::from(self) - SoftFloat16::from(other))
+    }
+}
+
+impl Mul for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::from(SoftFloat16::from(self) * SoftFloat16::from(other))
+    }
+}
+
+impl Div for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        Self::from(SoftFloat16::from(self) / SoftFloat16::from(other))
+    }
+}
+
+impl Rem for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn rem(self, other: Self) -> Self {
+        Self::from(SoftFloat16::from(self) % SoftFloat16::from(other))
+    }
+}
+
+impl Neg for SoftFloat8E4M3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(self.0 ^ 0x80)
+    }
+}
+
+impl PartialEq for SoftFloat8E4M3 {
+    fn eq(&self, other: &Self) -> bool {
+        SoftFloat16::from(*self) == SoftFloat16::from(*other)
+    }
+}
+
+impl PartialOrd for SoftFloat8E4M3 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        SoftFloat16::from(*self).partial_cmp(&SoftFloat16::from(*other))
+    }
+}
+
+impl Sqrt for SoftFloat8E4M3 {
+    fn sqrt(v: Self) -> Self {
+        Self::from(SoftFloat16::sqrt(SoftFloat16::from(v)))
+    }
+}
+
+impl RoundTiesEven for SoftFloat8E4M3 {
+    fn round_ties_even(v: Self) -> Self {
+        Self::from(SoftFloat16::round_ties_even(SoftFloat16::from(v)))
+    }
+}
+
+impl Round for SoftFloat8E4M3 {
+    fn round(v: Self) -> Self {
+        Self::from(SoftFloat16::round(SoftFloat16::from(v)))
+    }
+}
+
+impl Floor for SoftFloat8E4M3 {
+    fn floor(v: Self) -> Self {
+        Self::from(SoftFloat16::floor(SoftFloat16::from(v)))
+    }
+}
+
+impl Ceil for SoftFloat8E4M3 {
+    fn ceil(v: Self) -> Self {
+        Self::from(SoftFloat16::ceil(SoftFloat16::from(v)))
+    }
+}
+
+impl Trunc for SoftFloat8E4M3 {
+    fn trunc(v: Self) -> Self {
+        Self::from(SoftFloat16::trunc(SoftFloat16::from(v)))
+    }
+}
+
+impl MulAdd for SoftFloat8E4M3 {
+    // binary16 is too narrow to hold the exact result, which may occupy up
+    // to 36 bits, so the fused multiply-add is carried out in binary64,
+    // where it is exact
+    fn mul_add(v0: Self, v1: Self, v2: Self) -> Self {
+        let widen = |v: Self| {
+            Format::<11, 52>::convert::<5, 10>(
+                SoftFloat::raw(SoftFloat16::from(v)),
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::default(),
+            )
+            .0
+        };
+        let (v, _) = Format::<11, 52>::mul_add(
+            widen(v0),
+            widen(v1),
+            widen(v2),
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        );
+        Self::narrow::<11, 52>(
+            v,
+            RoundingMode::NearestTiesToEven,
+            OverflowMode::NonSaturating,
+        )
+        .0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ROUNDING_MODES;
+
+    // reference implementation; picks the neighbors of x among all finite
+    // E4M3 numbers and one of them according to the rounding mode, where the
+    // successor of the largest finite number is 480 (the next number if the
+    // exponent range were unbounded), which overflows; like rounding toward
+    // zero, rounding to odd never overflows, even though the largest finite
+    // number has an even significand
+    fn round_e4m3(x: f64, rounding_mode: RoundingMode, overflow_mode: OverflowMode) -> u8 {
+        if x.is_nan() {
+            return E4M3_NAN;
+        }
+
+        let sign_bit = if x.is_sign_negative() { 0x80 } else { 0x00 };
+        let magnitude = x.abs();
+        let value = |i: u8| {
+            if i == E4M3_NAN {
+                480.0
+            } else {
+                f32::from(SoftFloat8E4M3::from_bits(i)) as f64
+            }
+        };
+
+        let mut lo = 0;
+        while lo < E4M3_MAX && value(lo + 1) <= magnitude {
+            lo += 1;
+        }
+        let i = if value(lo) == magnitude {
+            lo
+        } else {
+            let hi = lo + 1;
+            let mid = (value(lo) + value(hi)) / 2.0;
+            let away = match rounding_mode {
+                RoundingMode::NearestTiesToEven => {
+                    magnitude > mid || (magnitude == mid && lo & 1 == 1)
+                }
+                RoundingMode::NearestTiesToAway => magnitude >= mid,
+                RoundingMode::TowardZero => false,
+                RoundingMode::TowardPositive => sign_bit == 0,
+                RoundingMode::TowardNegative => sign_bit != 0,
+                RoundingMode::ToOdd => lo & 1 == 0 && lo < E4M3_MAX,
+            };
+            if away {
+                hi
+            } else {
+                lo
+            }
+        };
+
+        if i == E4M3_NAN && overflow_mode == OverflowMode::Saturating {
+            sign_bit | E4M3_MAX
+        } else {
+            sign_bit | i
+        }
+    }
+
+    fn check_e4m3(y: SoftFloat8E4M3, expected: u8, args: (u8, u8)) {
+        if SoftFloat8E4M3::is_nan(y) || expected & 0x7f == E4M3_NAN {
+            assert!(SoftFloat8E4M3::is_nan(y), "{:?}", args);
+            assert_eq!(expected & 0x7f, E4M3_NAN, "{:?}", args);
+        } else {
+            assert_eq!(SoftFloat8E4M3::to_bits(y), expected, "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_e4m3_values() {
+        for (v, expected) in [
+            (0x00, 0.0),
+            (0x80, -0.0),
+            (0x01, 2.0_f32.powi(-9)),
+            (0x07, 7.0 * 2.0_f32.powi(-9)),
+            (0x08, 2.0_f32.powi(-6)),
+            (0x38, 1.0),
+            (0x78, 256.0),
+            (0x7e, 448.0),
+            (0xfe, -448.0),
+        ] {
+            let x = SoftFloat8E4M3::from_bits(v);
+            assert_eq!(f32::from(x).to_bits(), expected.to_bits(), "{:02x}", v);
+        }
+        assert!(f32::from(SoftFloat8E4M3::from_bits(0x7f)).is_nan());
+        assert!(f32::from(SoftFloat8E4M3::from_bits(0xff)).is_nan());
+    }
+
+    #[test]
+    fn test_e4m3_from_f32() {
+        let rm = RoundingMode::NearestTiesToEven;
+        for (value, expected, expected_saturating, expected_flags) in [
+            (1.0, 0x38, 0x38, ExceptionFlags::NONE),
+            (448.0, 0x7e, 0x7e, ExceptionFlags::NONE),
+            // ties to even, 480 would have an odd significand
+            (464.0, 0x7e, 0x7e, ExceptionFlags::INEXACT),
+            (
+                465.0,
+                0x7f,
+                0x7e,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                -1000.0,
+                0xff,
+                0xfe,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (255.0, 0x78, 0x78, ExceptionFlags::INEXACT),
+            (
+                2.0_f32.powi(-10),
+                0x00,
+                0x00,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (f32::INFINITY, 0x7f, 0x7e, ExceptionFlags::INVALID),
+            (f32::NEG_INFINITY, 0xff, 0xfe, ExceptionFlags::INVALID),
+            (f32::NAN, 0x7f, 0x7f, ExceptionFlags::NONE),
+        ] {
+            let (y, flags) =
+                SoftFloat8E4M3::from_f32_with_overflow(value, rm, OverflowMode::NonSaturating);
+            assert_eq!(SoftFloat8E4M3::to_bits(y), expected, "{}", value);
+            assert_eq!(flags, expected_flags, "{}", value);
+            let (y, _) =
+                SoftFloat8E4M3::from_f32_with_overflow(value, rm, OverflowMode::Saturating);
+            assert_eq!(SoftFloat8E4M3::to_bits(y), expected_saturating, "{}", value);
+        }
+
+        // rounding to odd overflows to the largest finite number, even
+        // without saturation
+        let (y, flags) = SoftFloat8E4M3::from_f32_with_overflow(
+            -460.0,
+            RoundingMode::ToOdd,
+            OverflowMode::NonSaturating,
+        );
+        assert_eq!(SoftFloat8E4M3::to_bits(y), 0xfe);
+        assert_eq!(flags, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_all_e4m3_from_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let value = f32::from(x) as f64;
+            for rounding_mode in ROUNDING_MODES {
+                for overflow_mode in [OverflowMode::NonSaturating, OverflowMode::Saturating] {
+                    let (y, _) = SoftFloat8E4M3::from_soft_float16_with_overflow(
+                        x,
+                        rounding_mode,
+                        overflow_mode,
+                    );
+                    let expected = if value.is_infinite() {
+                        // infinities are invalid or saturate regardless of
+                        // the rounding mode
+                        match overflow_mode {
+                            OverflowMode::NonSaturating => E4M3_NAN,
+                            OverflowMode::Saturating => {
+                                (SoftFloat16::sign(x) as u8) << 7 | E4M3_MAX
+                            }
+                        }
+                    } else {
+                        round_e4m3(value, rounding_mode, overflow_mode)
+                    };
+                    check_e4m3(y, expected, (i as u8, (i >> 8) as u8));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_e4m3_to_softfloat16() {
+        for i in 0..=u8::MAX {
+            let x = SoftFloat8E4M3::from_bits(i);
+            let y = SoftFloat16::from(x);
+            if SoftFloat8E4M3::is_nan(x) {
+                assert!(SoftFloat16::is_nan(y));
+            } else {
+                // round trip
+                assert_eq!(SoftFloat8E4M3::to_bits(SoftFloat8E4M3::from(y)), i);
+                assert_eq!(
+                    round_e4m3(
+                        f32::from(y) as f64,
+                        RoundingMode::NearestTiesToEven,
+                        OverflowMode::NonSaturating
+                    ),
+                    i
+                );
+            }
+        }
+    }
+
+    fn check_all_e4m3(
+        op: impl Fn(SoftFloat8E4M3, SoftFloat8E4M3) -> SoftFloat8E4M3,
+        op_f64: impl Fn(f64, f64) -> f64,
+    ) {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat8E4M3::from_bits(i);
+                let x1 = SoftFloat8E4M3::from_bits(j);
+                let y = op(x0, x1);
+                let expected = round_e4m3(
+                    op_f64(f32::from(x0) as f64, f32::from(x1) as f64),
+                    RoundingMode::NearestTiesToEven,
+                    OverflowMode::NonSaturating,
+                );
+                check_e4m3(y, expected, (i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_e4m3_add() {
+        check_all_e4m3(|x0, x1| x0 + x1, |x0, x1| x0 + x1);
+    }
+
+    #[test]
+    fn test_all_e4m3_sub() {
+        check_all_e4m3(|x0, x1| x0 - x1, |x0, x1| x0 - x1);
+    }
+
+    #[test]
+    fn test_all_e4m3_mul() {
+        check_all_e4m3(|x0, x1| x0 * x1, |x0, x1| x0 * x1);
+    }
+
+    #[test]
+    fn test_all_e4m3_div() {
+        check_all_e4m3(|x0, x1| x0 / x1, |x0, x1| x0 / x1);
+    }
+
+    #[test]
+    fn test_all_e4m3_rem() {
+        // the IEEE 754 remainder of f64 numbers holding E4M3 numbers is exact
+        check_all_e4m3(
+            |x0, x1| x0 % x1,
+            |x0, x1| {
+                let r = x0 % x1;
+                let n = ((x0 - r) / x1).abs();
+                if 2.0 * r.abs() > x1.abs() || (2.0 * r.abs() == x1.abs() && n % 2.0 == 1.0) {
+                    r - x1.abs().copysign(r)
+                } else {
+                    r
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_all_e4m3_sqrt() {
+        check_all_e4m3(|x0, _| SoftFloat8E4M3::sqrt(x0), |x0, _| x0.sqrt());
+    }
+
+    #[test]
+    fn test_all_e4m3_round() {
+        check_all_e4m3(
+            |x0, _| SoftFloat8E4M3::round_ties_even(x0),
+            |x0, _| x0.round_ties_even(),
+        );
+    }
+
+    #[test]
+    fn test_all_e4m3_trunc() {
+        check_all_e4m3(|x0, _| SoftFloat8E4M3::trunc(x0), |x0, _| x0.trunc());
+    }
+
+    #[test]
+    fn test_all_e4m3_cmp() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat8E4M3::from_bits(i);
+                let x1 = SoftFloat8E4M3::from_bits(j);
+                let y = x0.partial_cmp(&x1);
+                let y_f = f32::from(x0).partial_cmp(&f32::from(x1));
+                assert_eq!(y, y_f, "{:?}", (i, j));
+                assert_eq!(x0 == x1, f32::from(x0) == f32::from(x1), "{:?}", (i, j));
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_e4m3_mul_add() {
+        for k in 0..=u8::MAX {
+            let x2 = SoftFloat8E4M3::from_bits(k);
+            // the exact result fits into f64
+            check_all_e4m3(
+                |x0, x1| SoftFloat8E4M3::mul_add(x0, x1, x2),
+                |x0, x1| x0 * x1 + f32::from(x2) as f64,
+            );
+        }
+    }
+
+    #[test]
+    fn test_e5m2_saturation() {
+        let rm = RoundingMode::NearestTiesToEven;
+        for (value, expected, expected_saturating) in [
+            (57344.0, 0x7b, 0x7b),
+            (61439.0, 0x7b, 0x7b),
+            (61440.0, 0x7c, 0x7b),
+            (-1e6, 0xfc, 0xfb),
+            (f32::INFINITY, 0x7c, 0x7b),
+            (f32::NEG_INFINITY, 0xfc, 0xfb),
+        ] {
+            let (y, _) =
+                SoftFloat8E5M2::from_f32_with_overflow(value, rm, OverflowMode::NonSaturating);
+            assert_eq!(SoftFloat8E5M2::to_bits(y), expected, "{}", value);
+            let (y, _) =
+                SoftFloat8E5M2::from_f32_with_overflow(value, rm, OverflowMode::Saturating);
+            assert_eq!(SoftFloat8E5M2::to_bits(y), expected_saturating, "{}", value);
+        }
+        assert!(SoftFloat8E5M2::is_nan(
+            SoftFloat8E5M2::from_f32_with_overflow(f32::NAN, rm, OverflowMode::Saturating).0
+        ));
+    }
+
+    #[test]
+    fn test_all_e5m2_softfloat16() {
+        for i in 0..=u8::MAX {
+            let x = SoftFloat8E5M2::from_bits(i);
+            let y = SoftFloat16::from(x);
+            // E5M2 is the upper half of binary16
+            assert_eq!(
+                SoftFloat16::to_bits(y),
+                (i as u16) << 8
+                    | if SoftFloat8E5M2::is_signaling(x) {
+                        0x0200
+                    } else {
+                        0
+                    }
+            );
+        }
+
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftFloat8E5M2::from(x);
+            let y_f = SoftFloat8E5M2::from(f32::from(x));
+            if SoftFloat16::is_nan(x) {
+                assert!(SoftFloat8E5M2::is_nan(y), "{}", i);
+            } else {
+                assert_eq!(
+                    SoftFloat8E5M2::to_bits(y),
+                    SoftFloat8E5M2::to_bits(y_f),
+                    "{}",
+                    i
+                );
+            }
+        }
+    }
+
+    fn check_all_e5m2(
+        op: impl Fn(SoftFloat8E5M2, SoftFloat8E5M2) -> SoftFloat8E5M2,
+        op_f32: impl Fn(f32, f32) -> f32,
+    ) {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat8E5M2::from_bits(i);
+                let x1 = SoftFloat8E5M2::from_bits(j);
+                let y = op(x0, x1);
+                let y_f = SoftFloat8E5M2::from(op_f32(f32::from(x0), f32::from(x1)));
+                if SoftFloat8E5M2::is_nan(y) || SoftFloat8E5M2::is_nan(y_f) {
+                    assert!(SoftFloat8E5M2::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat8E5M2::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat8E5M2::to_bits(y),
+                        SoftFloat8E5M2::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_e5m2() {
+        check_all_e5m2(|x0, x1| x0 + x1, |x0, x1| x0 + x1);
+        check_all_e5m2(|x0, x1| x0 - x1, |x0, x1| x0 - x1);
+        check_all_e5m2(|x0, x1| x0 * x1, |x0, x1| x0 * x1);
+        check_all_e5m2(|x0, x1| x0 / x1, |x0, x1| x0 / x1);
+        check_all_e5m2(|x0, _| SoftFloat8E5M2::sqrt(x0), |x0, _| x0.sqrt());
+    }
+}
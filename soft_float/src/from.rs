@@ -1,204 +1,408 @@
+use crate::format::{shift_right_sticky, Format};
 use crate::{
-    soft_float16::{NAN, NEG_INFINITY, NEG_ZERO, POS_INFINITY, POS_ZERO},
-    RoundTiesEven, SoftFloat16,
+    ExceptionFlags, Integer, IntegerOverflow, RoundingMode, SoftFloat, Storage, TininessDetection,
+    TryFromSoftFloatError,
 };
 
-impl From<f32> for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> From<f32>
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn from(value: f32) -> Self {
-        let bits = value.to_bits();
-
-        // extract fields
-        let sign = ((bits & 0x80000000) >> 16) as u16;
-        let unbiased_exponent = ((bits & 0x7f800000) >> 23) as i32 - 127;
-        let original_significand = bits & 0x7fffff; // without implicit bit
-
-        if unbiased_exponent < -25 {
-            // smallest representable number in f16 is 2^{-24} and if unbiased
-            // exponent is < -25 we can be sure rounding bit is zero
-            if sign == 0 {
-                POS_ZERO
-            } else {
-                NEG_ZERO
-            }
-        } else if unbiased_exponent == 128 && original_significand != 0 {
-            // NAN
-            NAN
-        } else if unbiased_exponent > 15 {
-            // overflow
-            if sign == 0 {
-                POS_INFINITY
-            } else {
-                NEG_INFINITY
-            }
-        } else {
-            let (exponent, significand) = if unbiased_exponent < -14 {
-                // convert to denormal number
-                let original_significand = original_significand | 0x800000; // include implicit bit
-
-                // shift to match exponents of normal f32 and denormal f16
-                // shift additionally by 14 to fit 23+1bits into 10bits
-                // keep guard, round, sticky bits
-                let shift = -(unbiased_exponent + 15); // NOTE 1 2^unbiased_exponent = (1 >> shift) 2^{-15}
-                assert!(shift >= 0);
-                let sticky_bits = (1 << (shift + 14 - 3 + 1)) - 1;
-                let sticky = (original_significand & sticky_bits != 0) as u16;
-                let significand = (original_significand >> (shift + 14 - 3)) as u16 | sticky;
-                (0, significand)
-            } else {
-                // adjust exponent
-                let exponent = (unbiased_exponent + 15) as u16;
-
-                // adjust significand
-                // no need to include implicit bit since we convert a normal f32 to a normal f16 number
-                // shift by 13 to fit 23bits into 10bits
-                // keep guard, round, sticky bits
-                let sticky_bits = (1 << (13 - 3 + 1)) - 1;
-                let sticky = (original_significand & sticky_bits != 0) as u16;
-                let significand = (original_significand >> (13 - 3)) as u16 | sticky;
-                (exponent, significand)
-            };
+        Self::from_f32_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
 
-            // rounding (to even)
-            let grs = significand & 0x7;
-            let significand = significand >> 3;
-            let lsb = significand & 1;
-            if grs < 0x4 || (grs == 0x4 && lsb == 0) {
-                Self::from_bits(sign | exponent << 10 | significand)
-            } else {
-                // allow significand to overflow into exponent
-                Self::from_bits(sign | (exponent << 10 | significand) + 1)
-            }
-        }
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn from_f32_with_rounding(value: f32, rounding_mode: RoundingMode) -> Self {
+        Self::from_f32_with_flags(value, rounding_mode, TininessDetection::default()).0
+    }
+
+    pub fn from_f32_with_flags(
+        value: f32,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::convert::<8, 23>(
+            value.to_bits() as u128,
+            rounding_mode,
+            tininess_detection,
+        ))
     }
 }
 
-impl From<SoftFloat16> for f32 {
-    fn from(value: SoftFloat16) -> Self {
-        let sign = SoftFloat16::sign(value) as u32;
-        let exponent = SoftFloat16::exponent(value) as u32;
-        let significand = SoftFloat16::significand(value) as u32;
-
-        if exponent == 0x1F && significand != 0 {
-            return f32::NAN;
-        } else if exponent == 0x1F && significand == 0 {
-            return if sign == 0 {
-                f32::INFINITY
-            } else {
-                f32::NEG_INFINITY
-            };
-        } else if exponent == 0 && significand == 0 {
-            return if sign == 0 { 0.0_f32 } else { -0.0_f32 };
-        }
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> From<f64>
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn from(value: f64) -> Self {
+        Self::from_f64_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
 
-        // handle denormals and implicit bit
-        let (exponent, significand) = if exponent == 0 {
-            (1, significand)
-        } else {
-            (exponent, significand | 0x400)
-        };
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn from_f64_with_rounding(value: f64, rounding_mode: RoundingMode) -> Self {
+        Self::from_f64_with_flags(value, rounding_mode, TininessDetection::default()).0
+    }
+
+    pub fn from_f64_with_flags(
+        value: f64,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::convert::<11, 52>(
+            value.to_bits() as u128,
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+}
 
-        let mut exponent = exponent + 127 - 15;
-        let mut significand = significand << 13;
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn from_soft_float_with_rounding<
+        U: Storage,
+        const EXP_BITS1: u32,
+        const MANT_BITS1: u32,
+    >(
+        value: SoftFloat<U, EXP_BITS1, MANT_BITS1>,
+        rounding_mode: RoundingMode,
+    ) -> Self {
+        Self::from_soft_float_with_flags(value, rounding_mode, TininessDetection::default()).0
+    }
 
-        while significand & (1 << 23) == 0 && exponent > 1 {
-            // realign decimal point if necessary (only happens for denormal
-            // numbers)
-            significand <<= 1;
-            exponent -= 1;
-        }
+    // converts between arbitrary formats; narrowing conversions round and
+    // may overflow or underflow, widening conversions are exact
+    pub fn from_soft_float_with_flags<U: Storage, const EXP_BITS1: u32, const MANT_BITS1: u32>(
+        value: SoftFloat<U, EXP_BITS1, MANT_BITS1>,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::convert::<
+            EXP_BITS1,
+            MANT_BITS1,
+        >(
+            SoftFloat::raw(value), rounding_mode, tininess_detection
+        ))
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> From<SoftFloat<T, EXP_BITS, MANT_BITS>>
+    for f32
+{
+    fn from(value: SoftFloat<T, EXP_BITS, MANT_BITS>) -> Self {
+        SoftFloat::to_f32_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
 
-        f32::from_bits(sign << 31 | exponent << 23 | significand & 0x7fffff)
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // conversion is exact for formats that are not wider than f32, e.g.,
+    // SoftFloat16
+    pub fn to_f32_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (f32, ExceptionFlags) {
+        let (bits, flags) = Format::<8, 23>::convert::<EXP_BITS, MANT_BITS>(
+            Self::raw(v),
+            rounding_mode,
+            tininess_detection,
+        );
+        (f32::from_bits(bits as u32), flags)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> From<SoftFloat<T, EXP_BITS, MANT_BITS>>
+    for f64
+{
+    fn from(value: SoftFloat<T, EXP_BITS, MANT_BITS>) -> Self {
+        SoftFloat::to_f64_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
     }
 }
 
-impl From<i32> for SoftFloat16 {
-    fn from(value: i32) -> Self {
-        let bits = value as u32;
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // conversion is exact for formats that are not wider than f64, e.g.,
+    // SoftFloat16 and SoftFloat32
+    pub fn to_f64_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (f64, ExceptionFlags) {
+        let (bits, flags) = Format::<11, 52>::convert::<EXP_BITS, MANT_BITS>(
+            Self::raw(v),
+            rounding_mode,
+            tininess_detection,
+        );
+        (f64::from_bits(bits as u64), flags)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> From<$t>
+                for SoftFloat<T, EXP_BITS, MANT_BITS>
+            {
+                fn from(value: $t) -> Self {
+                    Self::from_int_with_rounding(value, RoundingMode::NearestTiesToEven)
+                }
+            }
+
+            // rounds to nearest (ties to even), fails for NANs and values
+            // that are out of range
+            impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>
+                TryFrom<SoftFloat<T, EXP_BITS, MANT_BITS>> for $t
+            {
+                type Error = TryFromSoftFloatError;
+
+                fn try_from(value: SoftFloat<T, EXP_BITS, MANT_BITS>) -> Result<Self, Self::Error> {
+                    SoftFloat::try_to_int_with_flags(value, RoundingMode::NearestTiesToEven, false)
+                        .map(|(v, _)| v)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, u8, u16, u32, u64, u128);
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn from_int_with_rounding<I: Integer>(value: I, rounding_mode: RoundingMode) -> Self {
+        Self::from_int_with_flags(value, rounding_mode).0
+    }
+
+    pub fn from_int_with_flags<I: Integer>(
+        value: I,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        let (sign, magnitude) = I::to_sign_magnitude(value);
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::from_integer(
+            sign,
+            magnitude,
+            rounding_mode,
+        ))
+    }
+
+    pub fn from_i32_with_rounding(value: i32, rounding_mode: RoundingMode) -> Self {
+        Self::from_int_with_rounding(value, rounding_mode)
+    }
+
+    pub fn from_i32_with_flags(value: i32, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        Self::from_int_with_flags(value, rounding_mode)
+    }
+}
 
-        let sign = (bits >> 31) as u16;
-        let significand = value.abs() as u32;
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // like convertToIntegerExact in IEEE 754, sec. 5.8, if exact, and like
+    // the other convertToInteger operations otherwise, which do not raise the
+    // inexact exception
+    pub fn try_to_int_with_flags<I: Integer>(
+        v: Self,
+        rounding_mode: RoundingMode,
+        exact: bool,
+    ) -> Result<(I, ExceptionFlags), TryFromSoftFloatError> {
+        let (sign, magnitude, flags) = Format::<EXP_BITS, MANT_BITS>::to_integer(
+            Self::raw(v),
+            rounding_mode,
+            exact,
+            [I::MAX_POSITIVE, I::MAX_NEGATIVE],
+        )?;
+        Ok((I::from_sign_magnitude(sign, magnitude), flags))
+    }
 
-        if significand == 0 {
-            return if sign == 0 { POS_ZERO } else { NEG_ZERO };
+    // NANs and values that are out of range signal invalid and return the
+    // value chosen by integer_overflow
+    pub fn to_int_with_flags<I: Integer>(
+        v: Self,
+        rounding_mode: RoundingMode,
+        exact: bool,
+        integer_overflow: IntegerOverflow,
+    ) -> (I, ExceptionFlags) {
+        match Self::try_to_int_with_flags(v, rounding_mode, exact) {
+            Ok(result) => result,
+            Err(error) => (error.value(integer_overflow), ExceptionFlags::INVALID),
         }
+    }
+
+    // rounds to nearest (ties to even); note that inexact results do not
+    // raise the inexact exception
+    pub fn to_i32_with_flags(v: Self) -> (i32, ExceptionFlags) {
+        Self::to_int_with_flags(
+            v,
+            RoundingMode::NearestTiesToEven,
+            false,
+            IntegerOverflow::Sentinel,
+        )
+    }
+}
 
-        if significand >= (1 << 16) {
-            // overflow
-            return if sign == 0 {
-                POS_INFINITY
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // converts from the format with EXP_BITS1 exponent and MANT_BITS1
+    // significand bits to this format
+    pub(crate) const fn convert<const EXP_BITS1: u32, const MANT_BITS1: u32>(
+        v: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (sign, exponent, significand) = Format::<EXP_BITS1, MANT_BITS1>::unpack(v);
+
+        if exponent == Format::<EXP_BITS1, MANT_BITS1>::EXPONENT_MASK && significand != 0 {
+            // NAN; keep sign and the most significant bits of the payload,
+            // quiet signaling NANs
+            let flags = if Format::<EXP_BITS1, MANT_BITS1>::is_signaling(v) {
+                ExceptionFlags::INVALID
+            } else {
+                ExceptionFlags::NONE
+            };
+            let significand = if MANT_BITS >= MANT_BITS1 {
+                significand << (MANT_BITS - MANT_BITS1)
             } else {
-                NEG_INFINITY
+                significand >> (MANT_BITS1 - MANT_BITS)
             };
+            return (
+                Self::pack(sign, Self::EXPONENT_MASK, significand | Self::QUIET_BIT),
+                flags,
+            );
+        } else if exponent == Format::<EXP_BITS1, MANT_BITS1>::EXPONENT_MASK {
+            // oo
+            return (Self::infinity(sign), ExceptionFlags::NONE);
+        } else if exponent == 0 && significand == 0 {
+            return (Self::zero(sign), ExceptionFlags::NONE);
         }
 
-        let significand = significand as u16;
+        // handle denormals and implicit bit
+        let (exponent, significand) =
+            Format::<EXP_BITS1, MANT_BITS1>::normalize(exponent, significand);
+
+        // adjust exponent; denormal and very small numbers end up with
+        // exponents below one, large numbers with exponents at or above
+        // EXPONENT_MASK
+        let exponent = exponent - Format::<EXP_BITS1, MANT_BITS1>::BIAS + Self::BIAS;
+
+        // adjust significand to look like 1.x{MANT_BITS}, keep guard, round,
+        // sticky bits
+        let significand = if MANT_BITS + 3 >= MANT_BITS1 {
+            significand << (MANT_BITS + 3 - MANT_BITS1)
+        } else {
+            shift_right_sticky(significand, (MANT_BITS1 - MANT_BITS - 3) as i32)
+        };
 
-        // normalize
-        let mut exponent = 30;
-        let mut significand = significand;
-        while significand & (1 << 15) == 0 {
-            significand <<= 1;
-            exponent -= 1;
-        }
+        Self::round_and_pack(
+            sign,
+            exponent,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        )
+    }
 
-        // fit into 11 (significand) + 3 (grs) bits
-        let sticky = if significand & 0x7 == 0 { 0 } else { 1 };
-        let significand = significand >> (16 - 14) | sticky;
+    // converts an integer given by sign and magnitude
+    pub(crate) const fn from_integer(
+        sign: u32,
+        magnitude: u128,
+        rounding_mode: RoundingMode,
+    ) -> (u128, ExceptionFlags) {
+        if magnitude == 0 {
+            return (Self::zero(0), ExceptionFlags::NONE);
+        }
 
-        // rounding (to even)
-        let grs = significand & 0x7;
-        let significand = significand >> 3;
-        let lsb = significand & 1;
-        let rnd = if grs < 0x4 || (grs == 0x4 && lsb == 0) {
-            // round down
-            0
+        // normalize
+        let width = 128 - magnitude.leading_zeros();
+        let exponent = Self::BIAS + width as i32 - 1;
+
+        // fit into MANT_BITS + 1 (significand) + 3 (grs) bits; large numbers
+        // end up with exponents at or above EXPONENT_MASK and overflow;
+        // integers are never tiny
+        let significand = if width <= MANT_BITS + 1 + 3 {
+            magnitude << (MANT_BITS + 1 + 3 - width)
         } else {
-            // round up
-            1
+            shift_right_sticky(magnitude, (width - (MANT_BITS + 1 + 3)) as i32)
         };
 
-        // cut off implicit bit and allow overflow into exponent
-        Self::from_bits(sign << 15 | (exponent << 10 | significand & 0x3FF) + rnd)
+        Self::round_and_pack(
+            sign,
+            exponent,
+            significand,
+            rounding_mode,
+            TininessDetection::AfterRounding,
+        )
     }
-}
 
-impl From<SoftFloat16> for i32 {
-    fn from(value: SoftFloat16) -> Self {
-        let value = SoftFloat16::round_ties_even(value);
-
-        let (sign, exponent, significand) = (
-            SoftFloat16::sign(value),
-            SoftFloat16::exponent(value),
-            SoftFloat16::significand(value),
-        );
+    // rounds to an integer and returns its sign and magnitude, which must
+    // not exceed max_magnitude[sign]; inexact results raise the inexact
+    // exception only if exact
+    pub(crate) fn to_integer(
+        v: u128,
+        rounding_mode: RoundingMode,
+        exact: bool,
+        max_magnitude: [u128; 2],
+    ) -> Result<(u32, u128, ExceptionFlags), TryFromSoftFloatError> {
+        let (sign, exponent, significand) = Self::unpack(v);
+        let overflow = if sign == 0 {
+            TryFromSoftFloatError::PositiveOverflow
+        } else {
+            TryFromSoftFloatError::NegativeOverflow
+        };
 
-        if exponent == 0x1F {
-            // Infinity and NAN are returned as largest-magnitude negative integer
-            // http://www.jhauser.us/arithmetic/TestFloat-3/doc/TestFloat-general.html, sec 6.1
-            return 1 << 31;
+        if exponent == Self::EXPONENT_MASK && significand != 0 {
+            return Err(TryFromSoftFloatError::Nan);
+        } else if exponent == Self::EXPONENT_MASK {
+            return Err(overflow);
         }
 
-        let unbiased_exponent = (exponent as i16) - 15;
+        let (rounded, _) = Self::round_to_integral(v, rounding_mode);
+        let (_, exponent, significand) = Self::unpack(rounded);
 
-        if unbiased_exponent < 0 {
-            // underflow
-            return 0;
-        }
+        // nonzero integral values are normal numbers
+        let magnitude = if exponent == 0 {
+            0
+        } else {
+            let unbiased_exponent = exponent - Self::BIAS;
+            if unbiased_exponent >= 128 {
+                // too large for any integer
+                return Err(overflow);
+            }
 
-        let significand = (significand | 0x400) as i32; // include implicit bit
+            let significand = significand | Self::IMPLICIT_BIT; // include implicit bit
 
-        // shift to make sure lsb has exponent 0
-        let shift = unbiased_exponent - 10;
-        let significand = if shift < 0 {
-            significand >> -shift
-        } else {
-            significand << shift
+            // shift to make sure lsb has exponent 0
+            let shift = unbiased_exponent - MANT_BITS as i32;
+            if shift < 0 {
+                significand >> -shift
+            } else {
+                significand << shift
+            }
         };
 
-        if sign == 0 {
-            significand
+        if magnitude > max_magnitude[sign as usize] {
+            return Err(overflow);
+        }
+
+        let flags = if exact && rounded != v {
+            ExceptionFlags::INEXACT
         } else {
-            -significand
+            ExceptionFlags::NONE
+        };
+        Ok((sign, magnitude, flags))
+    }
+
+    // rounds to nearest (ties to even), see SoftFloat::to_i32_with_flags
+    pub(crate) fn to_i32(v: u128) -> (i32, ExceptionFlags) {
+        match Self::to_integer(
+            v,
+            RoundingMode::NearestTiesToEven,
+            false,
+            [i32::MAX_POSITIVE, i32::MAX_NEGATIVE],
+        ) {
+            Ok((sign, magnitude, flags)) => (i32::from_sign_magnitude(sign, magnitude), flags),
+            Err(_) => (i32::SENTINEL, ExceptionFlags::INVALID),
         }
     }
 }
@@ -206,6 +410,8 @@ impl From<SoftFloat16> for i32 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{check_rounding, XorShift, ROUNDING_MODES};
+    use crate::{SoftFloat128, SoftFloat16};
 
     #[test]
     fn test_softfloat16_from_i32() {
@@ -233,15 +439,213 @@ mod tests {
             (0xf09d, -9448),
         ] {
             let x = SoftFloat16::from_bits(v);
-            let y = i32::from(x);
+            let y = i32::try_from(x).unwrap();
             assert_eq!(y, expected)
         }
     }
 
+    #[test]
+    fn test_softfloat16_from_integers() {
+        for (y, expected) in [
+            (SoftFloat16::from(i8::MIN), 0xd800),
+            (SoftFloat16::from(i32::MIN), 0xfc00),
+            (SoftFloat16::from(i64::from(-2049)), 0xe800),
+            (SoftFloat16::from(i128::MIN), 0xfc00),
+            (SoftFloat16::from(u8::MAX), 0x5bf8),
+            (SoftFloat16::from(u16::MAX), 0x7c00),
+            (SoftFloat16::from(u64::from(65504u32)), 0x7bff),
+            (SoftFloat16::from(u128::MAX), 0x7c00),
+            (SoftFloat16::from(0u128), 0x0000),
+        ] {
+            assert_eq!(SoftFloat16::to_bits(y), expected);
+        }
+
+        for (y, expected) in [
+            (SoftFloat128::from(i128::MIN), 0xc07e << 112),
+            (SoftFloat128::from(u128::MAX), 0x407f << 112),
+            (
+                SoftFloat128::from(u64::MAX),
+                (0x403e << 112) | (i64::MAX as u128) << 49,
+            ),
+        ] {
+            assert_eq!(SoftFloat128::to_bits(y), expected);
+        }
+
+        let (y, flags) = SoftFloat16::from_int_with_flags(2049u64, RoundingMode::TowardPositive);
+        assert_eq!(SoftFloat16::to_bits(y), 0x6801);
+        assert_eq!(flags, ExceptionFlags::INEXACT);
+    }
+
+    #[test]
+    fn test_softfloat16_to_int_rounding() {
+        let x = SoftFloat16::from(2.5f32);
+        for (rounding_mode, expected) in [
+            (RoundingMode::NearestTiesToEven, (2, -2)),
+            (RoundingMode::NearestTiesToAway, (3, -3)),
+            (RoundingMode::TowardZero, (2, -2)),
+            (RoundingMode::TowardPositive, (3, -2)),
+            (RoundingMode::TowardNegative, (2, -3)),
+            (RoundingMode::ToOdd, (3, -3)),
+        ] {
+            for (x, expected) in [(x, expected.0), (-x, expected.1)] {
+                let (y, flags) = SoftFloat16::to_int_with_flags::<i64>(
+                    x,
+                    rounding_mode,
+                    false,
+                    IntegerOverflow::Sentinel,
+                );
+                assert_eq!(
+                    (y, flags),
+                    (expected, ExceptionFlags::NONE),
+                    "{:?}",
+                    rounding_mode
+                );
+                let (y, flags) = SoftFloat16::to_int_with_flags::<i64>(
+                    x,
+                    rounding_mode,
+                    true,
+                    IntegerOverflow::Sentinel,
+                );
+                assert_eq!(
+                    (y, flags),
+                    (expected, ExceptionFlags::INEXACT),
+                    "{:?}",
+                    rounding_mode
+                );
+            }
+        }
+
+        // exact results do not raise inexact
+        let (y, flags) = SoftFloat16::to_int_with_flags::<u8>(
+            SoftFloat16::from(255.0f32),
+            RoundingMode::NearestTiesToEven,
+            true,
+            IntegerOverflow::Sentinel,
+        );
+        assert_eq!((y, flags), (255, ExceptionFlags::NONE));
+
+        // negative values that round to zero are in range of unsigned integers
+        let (y, flags) = SoftFloat16::to_int_with_flags::<u32>(
+            SoftFloat16::from(-0.75f32),
+            RoundingMode::TowardZero,
+            true,
+            IntegerOverflow::Sentinel,
+        );
+        assert_eq!((y, flags), (0, ExceptionFlags::INEXACT));
+    }
+
+    #[test]
+    fn test_softfloat16_to_int_overflow() {
+        let nan = SoftFloat16::from_bits(0x7e00);
+        let inf = SoftFloat16::from_bits(0x7c00);
+        let rm = RoundingMode::NearestTiesToEven;
+        let (sentinel, saturating) = (IntegerOverflow::Sentinel, IntegerOverflow::Saturating);
+
+        assert_eq!(i16::try_from(nan), Err(TryFromSoftFloatError::Nan));
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i16>(nan, rm, false, sentinel),
+            (i16::MIN, ExceptionFlags::INVALID)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i16>(nan, rm, false, saturating),
+            (0, ExceptionFlags::INVALID)
+        );
+
+        assert_eq!(
+            u64::try_from(-inf),
+            Err(TryFromSoftFloatError::NegativeOverflow)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<u64>(-inf, rm, false, sentinel),
+            (u64::MAX, ExceptionFlags::INVALID)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<u64>(-inf, rm, false, saturating),
+            (0, ExceptionFlags::INVALID)
+        );
+
+        // rounding decides whether the value is in range
+        let x = SoftFloat16::from(127.5f32);
+        assert_eq!(
+            i8::try_from(x),
+            Err(TryFromSoftFloatError::PositiveOverflow)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i8>(x, rm, true, sentinel),
+            (i8::MIN, ExceptionFlags::INVALID)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i8>(x, rm, true, saturating),
+            (i8::MAX, ExceptionFlags::INVALID)
+        );
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i8>(x, RoundingMode::TowardZero, true, sentinel),
+            (127, ExceptionFlags::INEXACT)
+        );
+        let x = SoftFloat16::from(-128.5f32);
+        assert_eq!(i8::try_from(x), Ok(-128));
+        assert_eq!(
+            SoftFloat16::to_int_with_flags::<i8>(
+                x,
+                RoundingMode::TowardNegative,
+                false,
+                saturating
+            ),
+            (i8::MIN, ExceptionFlags::INVALID)
+        );
+        let x = SoftFloat16::from(-0.75f32);
+        assert_eq!(
+            u8::try_from(x),
+            Err(TryFromSoftFloatError::NegativeOverflow)
+        );
+
+        // 2^127 and above
+        let x = SoftFloat128::from(i128::MIN);
+        assert_eq!(i128::try_from(x), Ok(i128::MIN));
+        assert_eq!(
+            i128::try_from(-x),
+            Err(TryFromSoftFloatError::PositiveOverflow)
+        );
+        assert_eq!(u128::try_from(-x), Ok(1 << 127));
+        let x = SoftFloat128::from(u128::MAX);
+        assert_eq!(
+            u128::try_from(x),
+            Err(TryFromSoftFloatError::PositiveOverflow)
+        );
+    }
+
+    // compare to Rust's saturating `as`, which rounds toward zero
+    fn check_all_to_int_saturating<I: Integer + PartialEq>(as_int: fn(f32) -> I) {
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let (y, _) = SoftFloat16::to_int_with_flags::<I>(
+                x,
+                RoundingMode::TowardZero,
+                false,
+                IntegerOverflow::Saturating,
+            );
+            assert_eq!(y, as_int(f32::from(x)), "{:#x}", i);
+        }
+    }
+
+    #[test]
+    fn test_all_to_int_saturating() {
+        check_all_to_int_saturating(|v| v as i8);
+        check_all_to_int_saturating(|v| v as u8);
+        check_all_to_int_saturating(|v| v as i16);
+        check_all_to_int_saturating(|v| v as u16);
+        check_all_to_int_saturating(|v| v as i32);
+        check_all_to_int_saturating(|v| v as u32);
+        check_all_to_int_saturating(|v| v as i64);
+        check_all_to_int_saturating(|v| v as u64);
+        check_all_to_int_saturating(|v| v as i128);
+        check_all_to_int_saturating(|v| v as u128);
+    }
+
     #[test]
     fn test_softfloat16_from_f32() {
         for (v, expected) in [
-            (0x7FFF0007, 0x7e00),
+            (0x7FFF0007, 0x7ff8),
             (0x337fc010, 0x1),
             (0x331ffffc, 0x1),
             (0x337FFFC4, 0x1),
@@ -264,4 +668,211 @@ mod tests {
             assert_eq!(y.to_bits(), expected)
         }
     }
+
+    #[test]
+    fn test_softfloat16_from_f64() {
+        for (v, expected) in [
+            (0x7FF8000000000000, 0x7e00),
+            (0x7FF0000000000001, 0x7e00),
+            (0x40EFFC0000000000, 0x7bff),
+            (0x40EFFDFFFFFFFFFF, 0x7bff),
+            (0x40EFFE0000000000, 0x7c00),
+            (0x3F10000000000000, 0x0400),
+            (0x3E70000000000000, 0x0001),
+            (0x3E60000000000000, 0x0000),
+            (0xBFF0000000000000, 0xbc00),
+        ] {
+            let x = f64::from_bits(v);
+            let y = SoftFloat16::from(x);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:#x}", v)
+        }
+    }
+
+    #[test]
+    fn test_softfloat16_from_f64_no_double_rounding() {
+        // values just off halfway between two SoftFloat16 numbers that f32
+        // rounds onto the halfway point; converting via f32 then breaks the
+        // tie towards even and gets these wrong
+        for (value, expected) in [
+            // 1 + 2^-11 + 2^-40, above the midpoint of 0x3c00 and 0x3c01
+            (1.0 + 2f64.powi(-11) + 2f64.powi(-40), 0x3c01),
+            // 1 + 2^-10 + 2^-11 - 2^-40, below the midpoint of 0x3c01 and 0x3c02
+            (
+                1.0 + 2f64.powi(-10) + 2f64.powi(-11) - 2f64.powi(-40),
+                0x3c01,
+            ),
+            // 2^-25 + 2^-60, above the midpoint of zero and the smallest
+            // subnormal number
+            (2f64.powi(-25) + 2f64.powi(-60), 0x0001),
+            // 65520 - 2^-20, below the overflow threshold
+            (65520.0 - 2f64.powi(-20), 0x7bff),
+        ] {
+            let via_f32 = SoftFloat16::from(value as f32);
+            assert_ne!(SoftFloat16::to_bits(via_f32), expected, "{:?}", value);
+            let y = SoftFloat16::from(value);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:?}", value);
+            let y = SoftFloat16::from(-value);
+            assert_eq!(SoftFloat16::to_bits(y), expected | 0x8000, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_softfloat16_from_f64_with_flags() {
+        for (value, rounding_mode, expected, expected_flags) in [
+            (
+                2f64.powi(-25) + 2f64.powi(-60),
+                RoundingMode::NearestTiesToEven,
+                0x0001,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                65520.0 - 2f64.powi(-20),
+                RoundingMode::NearestTiesToEven,
+                0x7bff,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                65520.0,
+                RoundingMode::NearestTiesToEven,
+                0x7c00,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                65536.0,
+                RoundingMode::TowardZero,
+                0x7bff,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                1.0 + 2f64.powi(-52),
+                RoundingMode::TowardPositive,
+                0x3c01,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                1.5,
+                RoundingMode::NearestTiesToEven,
+                0x3e00,
+                ExceptionFlags::NONE,
+            ),
+            (
+                f64::from_bits(0x7FF0000000000001),
+                RoundingMode::NearestTiesToEven,
+                0x7e00,
+                ExceptionFlags::INVALID,
+            ),
+        ] {
+            let (y, flags) = SoftFloat16::from_f64_with_flags(
+                value,
+                rounding_mode,
+                TininessDetection::default(),
+            );
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:?}", value);
+            assert_eq!(flags, expected_flags, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn test_f64_from_softfloat16() {
+        for (v, expected) in [
+            (0x97db, 0xbf5f6c0000000000_u64),
+            (0xe850, 0xc0a1400000000000_u64),
+            (0x1, 0x3e70000000000000),
+            (0x3ff, 0x3f0ff80000000000),
+            (0x7bff, 0x40effc0000000000),
+            (0x7c00, 0x7ff0000000000000),
+            (0xfc00, 0xfff0000000000000),
+            (0x7e01, 0x7ff8040000000000),
+            (0x8000, 0x8000000000000000),
+        ] {
+            let x = SoftFloat16::from_bits(v);
+            let y = f64::from(x);
+            assert_eq!(y.to_bits(), expected, "{:#x}", v)
+        }
+    }
+
+    #[test]
+    fn test_all_f64_from_softfloat16_exact() {
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let (y, flags) = SoftFloat16::to_f64_with_flags(
+                x,
+                RoundingMode::NearestTiesToEven,
+                TininessDetection::default(),
+            );
+            if SoftFloat16::is_nan(x) {
+                assert!(y.is_nan(), "{:#x}", i);
+                continue;
+            }
+            assert_eq!(flags, ExceptionFlags::NONE, "{:#x}", i);
+            assert_eq!(f64::from(f32::from(x)).to_bits(), y.to_bits(), "{:#x}", i);
+            assert_eq!(SoftFloat16::to_bits(SoftFloat16::from(y)), i, "{:#x}", i);
+        }
+    }
+
+    fn check_all_from_f32<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        let next_up = |v: f32| f32::from_bits(v.to_bits() + 1);
+        let next_down = |v: f32| f32::from_bits(v.to_bits() - 1);
+
+        for i in 0..=u8::MAX {
+            let x = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+            let y = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x));
+            if SoftFloat::is_nan(x) {
+                assert!(SoftFloat::is_nan(y), "{}", i);
+                continue;
+            }
+            assert_eq!(SoftFloat::to_bits(y), i);
+
+            // conversion of numbers between x and its successor; the
+            // successor of the largest finite number is infinity, which is
+            // where rounding to nearest overflows
+            if i >= 0x7f >> MANT_BITS << MANT_BITS {
+                continue;
+            }
+            let lo = f32::from(x);
+            let hi = if i == (0x7f >> MANT_BITS << MANT_BITS) - 1 {
+                2.0 * lo - f32::from(SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i - 1))
+            } else {
+                f32::from(SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i + 1))
+            };
+            let mid = (lo + hi) / 2.0;
+            let even = if i & 1 == 0 { i } else { i + 1 };
+            for (value, expected) in [(next_down(mid), i), (mid, even), (next_up(mid), i + 1)] {
+                let y = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(value);
+                assert_eq!(SoftFloat::to_bits(y), expected, "{:?}", (i, value));
+                let y = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(-value);
+                assert_eq!(SoftFloat::to_bits(y), expected | 0x80, "{:?}", (i, value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_from_f32_binary8() {
+        check_all_from_f32::<2, 5>();
+        check_all_from_f32::<3, 4>();
+        check_all_from_f32::<4, 3>();
+        check_all_from_f32::<5, 2>();
+    }
+
+    #[test]
+    fn test_from_rounding() {
+        let mut rng = XorShift::new();
+        for _ in 0..100_000 {
+            let state = rng.next_u32();
+            for rounding_mode in ROUNDING_MODES {
+                // f32 values in and slightly beyond the range of SoftFloat16
+                let value = f32::from_bits(state & 0x87ffffff | 0x30000000);
+                let y = SoftFloat16::from_f32_with_rounding(value, rounding_mode);
+                let nearest = SoftFloat16::from(value);
+                let compare = |c: f64| (value as f64).total_cmp(&c);
+                check_rounding(y, nearest, compare, rounding_mode, &[]);
+
+                let value = state as i32 >> (state % 32);
+                let y = SoftFloat16::from_i32_with_rounding(value, rounding_mode);
+                let nearest = SoftFloat16::from(value);
+                let compare = |c: f64| (value as f64).total_cmp(&c);
+                check_rounding(y, nearest, compare, rounding_mode, &[]);
+            }
+        }
+    }
 }
@@ -0,0 +1,120 @@
+use std::fmt::{self, Debug, Display};
+
+use crate::IntegerOverflow;
+
+// primitive integer a SoftFloat can be converted from and to
+pub trait Integer: Copy + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    // largest magnitudes of positive and negative values
+    const MAX_POSITIVE: u128;
+    const MAX_NEGATIVE: u128;
+
+    // result of invalid conversions according to TestFloat
+    const SENTINEL: Self;
+
+    fn to_sign_magnitude(v: Self) -> (u32, u128);
+
+    // wraps around if the magnitude is out of range
+    fn from_sign_magnitude(sign: u32, magnitude: u128) -> Self;
+}
+
+macro_rules! impl_integer {
+    (signed: $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+                const MAX_POSITIVE: u128 = <$t>::MAX as u128;
+                const MAX_NEGATIVE: u128 = <$t>::MIN.unsigned_abs() as u128;
+                const SENTINEL: Self = <$t>::MIN;
+
+                fn to_sign_magnitude(v: Self) -> (u32, u128) {
+                    ((v < 0) as u32, v.unsigned_abs() as u128)
+                }
+
+                fn from_sign_magnitude(sign: u32, magnitude: u128) -> Self {
+                    if sign == 0 {
+                        magnitude as $t
+                    } else {
+                        magnitude.wrapping_neg() as $t
+                    }
+                }
+            }
+        )*
+    };
+    (unsigned: $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+                const MAX_POSITIVE: u128 = <$t>::MAX as u128;
+                const MAX_NEGATIVE: u128 = 0;
+                const SENTINEL: Self = <$t>::MAX;
+
+                fn to_sign_magnitude(v: Self) -> (u32, u128) {
+                    (0, v as u128)
+                }
+
+                fn from_sign_magnitude(sign: u32, magnitude: u128) -> Self {
+                    if sign == 0 {
+                        magnitude as $t
+                    } else {
+                        magnitude.wrapping_neg() as $t
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(signed: i8, i16, i32, i64, i128);
+impl_integer!(unsigned: u8, u16, u32, u64, u128);
+
+// reason a SoftFloat could not be converted to an integer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryFromSoftFloatError {
+    Nan,
+    PositiveOverflow,
+    NegativeOverflow,
+}
+
+impl TryFromSoftFloatError {
+    // replacement value for the failed conversion
+    pub(crate) fn value<I: Integer>(self, integer_overflow: IntegerOverflow) -> I {
+        match (integer_overflow, self) {
+            (IntegerOverflow::Sentinel, _) => I::SENTINEL,
+            (IntegerOverflow::Saturating, Self::Nan) => I::from_sign_magnitude(0, 0),
+            (IntegerOverflow::Saturating, Self::PositiveOverflow) => I::MAX,
+            (IntegerOverflow::Saturating, Self::NegativeOverflow) => I::MIN,
+        }
+    }
+}
+
+impl Display for TryFromSoftFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nan => write!(f, "cannot convert NaN to integer"),
+            Self::PositiveOverflow => write!(f, "value too large for integer type"),
+            Self::NegativeOverflow => write!(f, "value too small for integer type"),
+        }
+    }
+}
+
+impl std::error::Error for TryFromSoftFloatError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_magnitude() {
+        assert_eq!(i8::to_sign_magnitude(-128), (1, 128));
+        assert_eq!(i8::from_sign_magnitude(1, 128), -128);
+        assert_eq!(i128::to_sign_magnitude(i128::MIN), (1, 1 << 127));
+        assert_eq!(i128::from_sign_magnitude(1, 1 << 127), i128::MIN);
+        assert_eq!(u128::to_sign_magnitude(u128::MAX), (0, u128::MAX));
+        assert_eq!(u8::from_sign_magnitude(1, 0), 0);
+    }
+}
@@ -0,0 +1,12 @@
+// handling of NANs and values that are out of range when converting to
+// integers; TestFloat (as x86 SSE) returns a single sentinel per type, the
+// most negative value for signed and the largest value for unsigned
+// integers, saturating conversions behave like Rust's `as` instead and return
+// zero for NANs and the closest representable integer otherwise; both signal
+// invalid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IntegerOverflow {
+    #[default]
+    Sentinel,
+    Saturating,
+}
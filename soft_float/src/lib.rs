@@ -1,18 +1,62 @@
 mod add;
+mod bfloat16;
+mod big_uint;
+mod display;
 mod div;
 mod eq;
+mod exception_flags;
+mod ext_float80;
+mod format;
+mod fp8;
 mod from;
+mod integer;
+mod integer_overflow;
+mod literal;
 mod mul;
 mod mul_add;
+mod nan;
 mod neg;
 mod ord;
+mod overflow_mode;
+mod parse;
+mod posit16;
 mod rem;
 mod round;
+mod rounding_mode;
+mod rounding_precision;
+mod soft_float;
+mod soft_float128;
 mod soft_float16;
+mod soft_float32;
+mod soft_float64;
+mod sqrt;
 mod sub;
+#[cfg(test)]
+mod testing;
+mod tininess_detection;
+mod total_order;
 mod trunc;
+mod u256;
 
+pub use bfloat16::SoftBFloat16;
+pub use exception_flags::ExceptionFlags;
+pub use ext_float80::ExtFloat80;
+pub use fp8::{SoftFloat8E4M3, SoftFloat8E5M2};
+pub use integer::{Integer, TryFromSoftFloatError};
+pub use integer_overflow::IntegerOverflow;
 pub use mul_add::MulAdd;
-pub use round::RoundTiesEven;
-pub use soft_float16::SoftFloat16;
+pub use overflow_mode::OverflowMode;
+pub use parse::ParseSoftFloatError;
+pub use posit16::{Posit16, Posit16Es, Quire16, Quire16Es};
+pub use round::{Ceil, Floor, Round, RoundTiesEven};
+pub use rounding_mode::RoundingMode;
+pub use rounding_precision::RoundingPrecision;
+pub use soft_float::{SoftFloat, SoftFloat16, Storage};
+pub use soft_float128::SoftFloat128;
+pub use soft_float16::consts;
+pub use soft_float32::SoftFloat32;
+pub use soft_float64::SoftFloat64;
+pub use sqrt::Sqrt;
+pub use tininess_detection::TininessDetection;
+pub use total_order::TotalOrder;
 pub use trunc::Trunc;
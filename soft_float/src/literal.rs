@@ -0,0 +1,161 @@
+use crate::format::Format;
+use crate::{ExceptionFlags, ParseSoftFloatError, RoundingMode, SoftFloat16, TininessDetection};
+
+// SoftFloat16 literal, rounded to nearest, ties to even, at compile time,
+// e.g., `f16!(1.5)`, `f16!(-0.1)`, `f16!(6.1e-5)`, `f16!(inf)`, `f16!(-inf)`,
+// `f16!(nan)`, or string literals with the syntax of FromStr like
+// `f16!("0x1.ffcp+15")`; invalid literals do not compile, and neither do
+// literals that overflow to infinity when the strict form `f16!(strict 1e5)`
+// is used; integer tokens like `0x3c00` are rejected rather than read as
+// hexadecimal numbers (15360), which is rarely what is meant
+#[macro_export]
+macro_rules! f16 {
+    (strict $($literal:tt)+) => {
+        $crate::f16!(@literal true, $($literal)+)
+    };
+    (@literal $strict:literal, - $literal:tt) => {{
+        const V: $crate::SoftFloat16 = $crate::SoftFloat16::from_literal_const(
+            concat!("-", stringify!($literal)),
+            $strict,
+        );
+        V
+    }};
+    (@literal $strict:literal, $literal:tt) => {{
+        const V: $crate::SoftFloat16 =
+            $crate::SoftFloat16::from_literal_const(stringify!($literal), $strict);
+        V
+    }};
+    ($($literal:tt)+) => {
+        $crate::f16!(@literal false, $($literal)+)
+    };
+}
+
+impl SoftFloat16 {
+    // const version of from_str, sharing its implementation
+    pub const fn from_str_const(s: &str) -> Result<Self, ParseSoftFloatError> {
+        match Format::<5, 10>::parse(
+            s.as_bytes(),
+            false,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ) {
+            Ok((v, _)) => Ok(Self::from_bits_const(v as u16)),
+            Err(error) => Err(error),
+        }
+    }
+
+    // used by f16!, where the panics turn into compile errors
+    #[doc(hidden)]
+    pub const fn from_literal_const(literal: &str, strict: bool) -> Self {
+        // string literals are quoted, numeric ones may contain underscores
+        let (s, underscores) = match literal.as_bytes() {
+            [b'"', s @ .., b'"'] => (s, false),
+            s => (s, true),
+        };
+        if let (true, [b'0', b'x' | b'X', ..] | [b'-', b'0', b'x' | b'X', ..]) = (underscores, s) {
+            panic!("hexadecimal SoftFloat16 literals must be strings, e.g., f16!(\"0x1p-14\")");
+        }
+        match Format::<5, 10>::parse(
+            s,
+            underscores,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ) {
+            Ok((v, flags)) => {
+                if strict && flags.contains(ExceptionFlags::OVERFLOW) {
+                    panic!("literal out of range for SoftFloat16");
+                }
+                Self::from_bits_const(v as u16)
+            }
+            Err(_) => panic!("invalid SoftFloat16 literal"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseSoftFloatError, SoftFloat16};
+
+    // evaluated at compile time
+    const TABLE: [SoftFloat16; 4] = [f16!(0.5), f16!(-0.25), f16!("1e-2"), f16!(strict 65504)];
+
+    fn bits(v: SoftFloat16) -> u16 {
+        SoftFloat16::to_bits(v)
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(bits(f16!(1.5)), 0x3e00);
+        assert_eq!(bits(f16!(-1.5)), 0xbe00);
+        assert_eq!(bits(f16!(0.1)), 0x2e66);
+        assert_eq!(bits(f16!("0.1")), 0x2e66);
+        assert_eq!(bits(f16!("-0.1")), 0xae66);
+        assert_eq!(bits(f16!(1)), 0x3c00);
+        assert_eq!(bits(f16!(1.)), 0x3c00);
+        assert_eq!(bits(f16!(1_000)), 0x63d0);
+        assert_eq!(bits(f16!(1E-3)), 0x1419);
+        assert_eq!(bits(f16!(1e-3)), 0x1419);
+        assert_eq!(bits(f16!(6.1e-5)), 0x03ff);
+        assert_eq!(bits(f16!(6e-8)), 0x0001);
+        assert_eq!(bits(f16!(1e-9)), 0x0000);
+        assert_eq!(bits(f16!(-0.0)), 0x8000);
+        assert_eq!(bits(f16!("0x1.ffcp+15")), 0x7bff);
+        assert_eq!(bits(f16!(65519.99)), 0x7bff);
+        assert_eq!(bits(f16!(strict 65519.99)), 0x7bff);
+        assert_eq!(bits(f16!(65520)), 0x7c00);
+        assert_eq!(bits(f16!(1e99)), 0x7c00);
+        assert_eq!(bits(f16!(inf)), 0x7c00);
+        assert_eq!(bits(f16!(-inf)), 0xfc00);
+        assert_eq!(bits(f16!("-Infinity")), 0xfc00);
+        assert!(SoftFloat16::is_nan(f16!(nan)));
+        assert_eq!(bits(f16!(nan)), bits("NaN".parse().unwrap()));
+
+        // correctly rounded, unlike converting via f32 (which rounds
+        // 1.00048828125000001 down to 1.00048828125, halfway between 1 and
+        // the next SoftFloat16)
+        assert_eq!(bits(f16!(1.00048828125000001)), 0x3c01);
+        assert_eq!(
+            bits(SoftFloat16::from(
+                "1.00048828125000001".parse::<f32>().unwrap()
+            )),
+            0x3c00
+        );
+
+        assert_eq!(TABLE.map(bits), [0x3800, 0xb400, 0x211f, 0x7bff]);
+    }
+
+    #[test]
+    fn test_from_str_const() {
+        // evaluated at compile time
+        const PARSED: [Result<SoftFloat16, ParseSoftFloatError>; 5] = [
+            SoftFloat16::from_str_const("1e-2"),
+            SoftFloat16::from_str_const("-0x1.ffcp+15"),
+            SoftFloat16::from_str_const("1.00048828125000000000000000000001"),
+            SoftFloat16::from_str_const(""),
+            SoftFloat16::from_str_const("1_0"),
+        ];
+        assert_eq!(
+            PARSED.map(|v| v.map(bits)),
+            [
+                Ok(0x211f),
+                Ok(0xfbff),
+                Ok(0x3c01),
+                Err(ParseSoftFloatError::Empty),
+                Err(ParseSoftFloatError::Invalid),
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "must be strings")]
+    fn test_hex_token() {
+        // f16!(0x3c00) does not compile
+        SoftFloat16::from_literal_const("0x3c00", false);
+    }
+
+    #[test]
+    #[should_panic(expected = "must be strings")]
+    fn test_negative_hex_token() {
+        SoftFloat16::from_literal_const("-0X1p-14", false);
+    }
+}
@@ -1,152 +1,112 @@
 use std::ops::Mul;
 
-use crate::soft_float16::{NAN, NEG_INFINITY, NEG_ZERO, POS_INFINITY, POS_ZERO};
-use crate::SoftFloat16;
+use crate::format::{shift_right_sticky, Format};
+use crate::u256::U256;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
 
-impl Mul for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Mul
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        let (sign0, exponent0, significand0) = (
-            Self::sign(self),
-            Self::exponent(self),
-            Self::significand(self),
-        );
-        let (sign1, exponent1, significand1) = (
-            Self::sign(other),
-            Self::exponent(other),
-            Self::significand(other),
-        );
-
-        if (exponent0 == 0x1F && significand0 != 0) || (exponent1 == 0x1F && significand1 != 0) {
+        Self::mul_with_rounding(self, other, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn mul_with_rounding(v0: Self, v1: Self, rounding_mode: RoundingMode) -> Self {
+        Self::mul_with_flags(v0, v1, rounding_mode, TininessDetection::default()).0
+    }
+
+    pub fn mul_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::mul(
+            Self::raw(v0),
+            Self::raw(v1),
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn mul(
+        v0: u128,
+        v1: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+
+        if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
             // NAN * _ or _ * NAN
-            return NAN;
-        } else if ((exponent0 == 0x1F && exponent1 == 0) || (exponent0 == 0 && exponent1 == 0x1F))
+            return Self::propagate_nan(&[v0, v1]);
+        } else if ((exponent0 == Self::EXPONENT_MASK && exponent1 == 0)
+            || (exponent0 == 0 && exponent1 == Self::EXPONENT_MASK))
             && significand0 == 0
             && significand1 == 0
         {
             // oo * 0 or 0 * oo
-            return NAN;
-        } else if (exponent0 == 0x1F && significand0 == 0)
-            || (exponent1 == 0x1F && significand1 == 0)
-        {
+            return (Self::NAN, ExceptionFlags::INVALID);
+        } else if exponent0 == Self::EXPONENT_MASK || exponent1 == Self::EXPONENT_MASK {
             // oo * _ or _ * oo
-            return if sign0 ^ sign1 == 0 {
-                POS_INFINITY
-            } else {
-                NEG_INFINITY
-            };
+            return (Self::infinity(sign0 ^ sign1), ExceptionFlags::NONE);
         } else if (exponent0 == 0 && significand0 == 0) || exponent1 == 0 && significand1 == 0 {
             // 0 * _ or _ * 0
-            return if sign0 ^ sign1 == 0 {
-                POS_ZERO
-            } else {
-                NEG_ZERO
-            };
+            return (Self::zero(sign0 ^ sign1), ExceptionFlags::NONE);
         }
 
         let sign = sign0 ^ sign1;
 
         // handle denormals and implicit bit
-        let (exponent0, significand0) = if exponent0 == 0 {
-            // normalize
-            let mut exponent = 1_i16;
-            let mut significand = significand0;
-            assert!(significand != 0);
-            while significand & (1 << 10) == 0 {
-                significand <<= 1;
-                exponent -= 1;
-            }
-            (exponent, significand)
-        } else {
-            (exponent0 as i16, significand0 | 0x400)
-        };
-
-        let (exponent1, significand1) = if exponent1 == 0 {
-            // normalize
-            let mut exponent = 1_i16;
-            let mut significand = significand1;
-            assert!(significand != 0);
-            while significand & (1 << 10) == 0 {
-                significand <<= 1;
-                exponent -= 1;
-            }
-            (exponent, significand)
-        } else {
-            (exponent1 as i16, significand1 | 0x400)
-        };
+        let (exponent0, significand0) = Self::normalize(exponent0, significand0);
+        let (exponent1, significand1) = Self::normalize(exponent1, significand1);
 
-        let exponent = (exponent0 + exponent1) as i16 - 15;
+        let exponent = exponent0 + exponent1 - Self::BIAS;
 
-        let significand = (significand0 as u32) * (significand1 as u32);
-        assert!(significand < (1 << (12 + 10)));
-        assert!((significand & (1 << (11 + 10))) != 0 | (significand & (1 << (10 + 10))));
+        // result looks like 1x.x{2 * MANT_BITS} or 1.x{2 * MANT_BITS}
+        let significand = U256::widening_mul(significand0, significand1);
+        let implicit_bit = U256::from_u128(Self::IMPLICIT_BIT);
+        assert!(U256::compare(significand, U256::shift_left(implicit_bit, 2 + MANT_BITS)).is_lt());
+        assert!(U256::compare(significand, U256::shift_left(implicit_bit, MANT_BITS)).is_ge());
 
-        // keep lowest three bits for guard, round, sticky bits
-        let sticky_bits = (1 << (10 - 3)) - 1;
-        let sticky = (significand & sticky_bits != 0) as u16;
-        let significand = ((significand >> (10 - 3)) as u16) | sticky;
+        // insert guard, round, sticky bits and drop the lower half of the
+        // significand
+        let significand = U256::to_u128(U256::shift_right_sticky(
+            U256::shift_left(significand, 3),
+            MANT_BITS as i32,
+        ));
 
-        let (exponent, significand) = if significand & (1 << (11 + 3)) != 0 {
+        let (exponent, significand) = if significand & (Self::IMPLICIT_BIT << (1 + 3)) != 0 {
             // realign decimal point
-            let sticky = (significand & 1) | sticky;
-            let significand = (significand >> 1) | sticky;
-            (exponent + 1, significand)
+            (exponent + 1, shift_right_sticky(significand, 1))
         } else {
-            assert!((significand & (1 << (10 + 3))) != 0);
             (exponent, significand)
         };
 
-        let (exponent, significand) = if exponent <= -11 {
-            // underflow, since shift would be >= 12, i.e., all bits and guard
-            // bit would be zero; will always be rounded to zero
-            return if sign == 0 { POS_ZERO } else { NEG_ZERO };
-        } else if exponent <= 0 {
-            // must convert to denormal number; make exponent representable by
-            // shifting significand
-            let shift = 1 - exponent;
-            let sticky_bits = (1 << shift) - 1;
-            let sticky = (significand & sticky_bits != 0) as u16;
-            let significand = (significand >> shift) | sticky;
-            (1, significand)
-        } else if exponent >= 0x1F {
-            // overflow
-            return if sign == 0 {
-                POS_INFINITY
-            } else {
-                NEG_INFINITY
-            };
-        } else {
-            (exponent as u16, significand)
-        };
-
-        // rounding (to even)
-        let grs = significand & 0x7;
-        let significand = significand >> 3;
-        let lsb = significand & 1;
-        let rnd = if grs < 0x4 || (grs == 0x4 && lsb == 0) {
-            // round down
-            0
-        } else {
-            // round up
-            1
-        };
-
-        // denormals have exponent 0
-        let exponent = if exponent == 1 && significand < 0x400 {
-            0
-        } else {
-            exponent
-        };
-
-        // cut off implicit bit and allow overflow into exponent
-        Self::from_bits(sign << 15 | (exponent << 10 | significand & 0x3FF) + rnd)
+        Self::round_and_pack(
+            sign,
+            exponent,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        )
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{check_exact, check_rounding, XorShift, ROUNDING_MODES};
+    use crate::SoftFloat16;
 
     #[test]
     fn test_mul() {
@@ -166,7 +126,12 @@ mod tests {
             let x1 = SoftFloat16::from_bits(v1);
             let y = x0 * x1;
             let y_f = SoftFloat16::from(f32::from(x0) * f32::from(x1));
-            assert_eq!(SoftFloat16::to_bits(y), SoftFloat16::to_bits(y_f));
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{:?}", (v0, v1));
+                assert!(SoftFloat16::is_nan(y_f), "{:?}", (v0, v1));
+            } else {
+                assert_eq!(SoftFloat16::to_bits(y), SoftFloat16::to_bits(y_f));
+            }
         }
     }
 
@@ -179,9 +144,9 @@ mod tests {
                 let x1_sf = SoftFloat16::from_bits(j);
                 let y_sf = x0_sf * x1_sf;
                 let y_f = SoftFloat16::from(f32::from(x0_sf) * f32::from(x1_sf));
-                if y_sf == NAN || y_f == NAN {
-                    assert!(y_sf == NAN, "{:?}", (i, j));
-                    assert!(y_f == NAN, "{:?}", (i, j));
+                if SoftFloat16::is_nan(y_sf) || SoftFloat16::is_nan(y_f) {
+                    assert!(SoftFloat16::is_nan(y_sf), "{:?}", (i, j));
+                    assert!(SoftFloat16::is_nan(y_f), "{:?}", (i, j));
                 } else {
                     assert_eq!(
                         SoftFloat16::to_bits(y_sf),
@@ -193,4 +158,80 @@ mod tests {
             }
         }
     }
+
+    fn check_all_mul<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0 * x1;
+                let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0) * f32::from(x1));
+                if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                    assert!(SoftFloat::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat::to_bits(y),
+                        SoftFloat::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_mul_binary8() {
+        check_all_mul::<2, 5>();
+        check_all_mul::<3, 4>();
+        check_all_mul::<4, 3>();
+        check_all_mul::<5, 2>();
+    }
+
+    fn check_mul_rounding(i: u16, j: u16, rounding_mode: RoundingMode) {
+        let x0 = SoftFloat16::from_bits(i);
+        let x1 = SoftFloat16::from_bits(j);
+        let y = SoftFloat16::mul_with_rounding(x0, x1, rounding_mode);
+        let nearest = x0 * x1;
+        let exact = f32::from(x0) as f64 * f32::from(x1) as f64;
+        if !exact.is_finite() || exact == 0.0 {
+            check_exact(y, nearest, rounding_mode, &[i, j]);
+        } else {
+            check_rounding(y, nearest, |c| exact.total_cmp(&c), rounding_mode, &[i, j]);
+        }
+    }
+
+    #[test]
+    fn test_mul_rounding() {
+        for rounding_mode in ROUNDING_MODES {
+            // underflow and overflow
+            check_mul_rounding(0x0001, 0x3800, rounding_mode);
+            check_mul_rounding(0x8001, 0x3400, rounding_mode);
+            check_mul_rounding(0x7bff, 0x3c01, rounding_mode);
+        }
+    }
+
+    #[test]
+    fn test_random_mul_rounding() {
+        let mut rng = XorShift::new();
+        for _ in 0..100_000 {
+            let (i, j) = (rng.next_u16(), rng.next_u16());
+            for rounding_mode in ROUNDING_MODES {
+                check_mul_rounding(i, j, rounding_mode);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_mul_rounding() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                for rounding_mode in ROUNDING_MODES {
+                    check_mul_rounding(i, j, rounding_mode);
+                }
+            }
+        }
+    }
 }
@@ -1,246 +1,515 @@
-use crate::soft_float16::NAN;
-use crate::SoftFloat16;
+use crate::format::Format;
+use crate::u256::U256;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
 
 pub trait MulAdd {
     fn mul_add(v0: Self, v1: Self, v2: Self) -> Self;
 }
 
-impl MulAdd for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> MulAdd
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn mul_add(v0: Self, v1: Self, v2: Self) -> Self {
-        todo!();
-        println!("----------------");
-
-        // TODO handle special numbers
-        let (sign, exponent, significand) = mul(v0, v1);
+        Self::mul_add_with_rounding(v0, v1, v2, RoundingMode::NearestTiesToEven)
+    }
+}
 
-        add(sign, exponent, significand, v2)
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // computes v0 * v1 + v2 with a single rounding
+    pub fn mul_add_with_rounding(
+        v0: Self,
+        v1: Self,
+        v2: Self,
+        rounding_mode: RoundingMode,
+    ) -> Self {
+        Self::mul_add_with_flags(v0, v1, v2, rounding_mode, TininessDetection::default()).0
     }
 
+    pub fn mul_add_with_flags(
+        v0: Self,
+        v1: Self,
+        v2: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::mul_add(
+            Self::raw(v0),
+            Self::raw(v1),
+            Self::raw(v2),
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
 }
 
-fn mul(v0: SoftFloat16, v1: SoftFloat16) -> (u16, i16, u32) {
-    let (sign0, exponent0, significand0) = (
-        SoftFloat16::sign(v0),
-        SoftFloat16::exponent(v0),
-        SoftFloat16::significand(v0),
-    );
-
-    let (sign1, exponent1, significand1) = (
-        SoftFloat16::sign(v1),
-        SoftFloat16::exponent(v1),
-        SoftFloat16::significand(v1),
-    );
-
-    let sign = sign0 ^ sign1;
-
-    // handle denormals and implicit bit
-    let (exponent0, significand0) = if exponent0 == 0 {
-        // TODO introduce function for `normalize`
-        // normalize
-        let mut exponent = 1_i16;
-        let mut significand = significand0;
-        assert!(significand != 0);
-        while significand & (1 << 10) == 0 {
-            significand <<= 1;
-            exponent -= 1;
-        }
-        (exponent, significand)
-    } else {
-        (exponent0 as i16, significand0 | 0x400)
-    };
-
-    let (exponent1, significand1) = if exponent1 == 0 {
-        // normalize
-        let mut exponent = 1_i16;
-        let mut significand = significand1;
-        assert!(significand != 0);
-        while significand & (1 << 10) == 0 {
-            significand <<= 1;
-            exponent -= 1;
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn mul_add(
+        v0: u128,
+        v1: u128,
+        v2: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+        let (sign2, exponent2, significand2) = Self::unpack(v2);
+
+        let is_zero0 = exponent0 == 0 && significand0 == 0;
+        let is_zero1 = exponent1 == 0 && significand1 == 0;
+        let is_infinity0 = exponent0 == Self::EXPONENT_MASK && significand0 == 0;
+        let is_infinity1 = exponent1 == Self::EXPONENT_MASK && significand1 == 0;
+        let sign = sign0 ^ sign1;
+
+        if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
+            // NAN * _ + _ or _ * NAN + _
+            return Self::propagate_nan(&[v0, v1, v2]);
+        } else if (is_infinity0 && is_zero1) || (is_zero0 && is_infinity1) {
+            // oo * 0 + _ or 0 * oo + _; invalid even if the addend is a
+            // quiet NAN, see IEEE 754, sec. 7.2
+            return (Self::NAN, ExceptionFlags::INVALID);
+        } else if exponent2 == Self::EXPONENT_MASK && significand2 != 0 {
+            // _ * _ + NAN
+            return Self::propagate_nan(&[v2]);
+        } else if is_infinity0 || is_infinity1 {
+            // oo * _ + _ or _ * oo + _
+            return if exponent2 == Self::EXPONENT_MASK && sign != sign2 {
+                // oo - oo
+                (Self::NAN, ExceptionFlags::INVALID)
+            } else {
+                (Self::infinity(sign), ExceptionFlags::NONE)
+            };
+        } else if exponent2 == Self::EXPONENT_MASK {
+            // _ * _ + oo
+            return (v2, ExceptionFlags::NONE);
+        } else if is_zero0 || is_zero1 {
+            // 0 * _ + _ or _ * 0 + _
+            return if exponent2 == 0 && significand2 == 0 && sign != sign2 {
+                // 0 - 0
+                (
+                    Self::zero(rounding_mode.exact_zero_sign()),
+                    ExceptionFlags::NONE,
+                )
+            } else {
+                (v2, ExceptionFlags::NONE)
+            };
         }
-        (exponent, significand)
-    } else {
-        (exponent1 as i16, significand1 | 0x400)
-    };
 
-    let exponent = exponent0 + exponent1 - 15; // biased exponent of result
-    let significand = (significand0 as u32) * (significand1 as u32);
-    assert!(significand < (1 << (2 * 11))); // result can not have more than 2 * 11 bits
-    assert!((significand & (1 << (1 + 10 + 10))) != 0 | (significand & (1 << (10 + 10)))); // result looks like 1x.x{10}x{10} or 1.x{10}x{10}
+        let (sign, exponent, significand) = Self::mul_exact(v0, v1);
 
-    (sign, exponent, significand)
-}
+        Self::add_unrounded(
+            sign,
+            exponent,
+            significand,
+            v2,
+            rounding_mode,
+            tininess_detection,
+        )
+    }
 
-fn add(sign0: u16, exponent0: i16, significand0: u32, v1: SoftFloat16) -> SoftFloat16 {
-    let (sign1, exponent1, significand1) = (
-        SoftFloat16::sign(v1),
-        SoftFloat16::exponent(v1),
-        SoftFloat16::significand(v1),
-    );
-
-    let significand0 = significand0 as u64;
-
-    // handle denormals and implicit bit
-    let (exponent1, significand1) = if exponent1 == 0 {
-        (1_i16, significand1 as u64)
-    } else {
-        (exponent1 as i16, (significand1 | 0x400) as u64)
-    };
-
-    // align second significand (which looks like x.x{10}) to result of
-    // multiplication (which looks like xx.x{10}x{10})
-    let significand1 = significand1 << 10;
-
-    // align decimal point of second number
-    let shift = exponent1 - exponent0;
-
-    // if shift is too large, not even grs bits are influenced by first summand
-    // and we can return early
-    if shift >= 11 + 3 + 1 {
-        return v1;
-    }
-
-    let significand1 = if shift < 0 {
-        significand1 >> -shift
-    } else {
-        significand1 << shift
-    };
-
-    let (sign, exponent, significand) = if sign0 == sign1 {
-        let (sign, exponent, significand) = (sign0, exponent0, significand0 + significand1);
-        // result is at least 1.0{10}0{10}
-        assert!(significand >= (1 << (10 + 10)));
-        // result is at most 11{10}0011.1{10}1{10}
-        assert!(significand < (1 << (1 + 10 + 2 + 2 + 10 + 10)));
-
-        println!("{:036b} +", significand0);
-        println!("{:036b} =", significand1);
-        println!("{:036b} <- sig", significand);
-        println!("{} <- exp", exponent);
-
-        // normalize
-        // first we align to look like 1.x{10}x{10}
-        let mut sticky = 0;
-        let mut exponent = exponent;
-        let mut significand = significand;
-        while significand >= (1 << (1 + 10 + 10)) {
-            sticky |= significand & 1;
-            significand >>= 1;
-            exponent += 1;
+    // computes the exact product of two finite, nonzero numbers; the
+    // resulting significand looks like 1.x{2 * MANT_BITS + 1}, i.e., the
+    // value of the product is
+    // significand * 2^(exponent - BIAS - 2 * MANT_BITS - 1)
+    const fn mul_exact(v0: u128, v1: u128) -> (u32, i32, U256) {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+
+        let sign = sign0 ^ sign1;
+
+        // handle denormals and implicit bit
+        let (exponent0, significand0) = Self::normalize(exponent0, significand0);
+        let (exponent1, significand1) = Self::normalize(exponent1, significand1);
+
+        let exponent = exponent0 + exponent1 - Self::BIAS; // biased exponent of result
+        let significand = U256::widening_mul(significand0, significand1);
+        let implicit_bit = U256::from_u128(Self::IMPLICIT_BIT);
+        assert!(U256::compare(significand, U256::shift_left(implicit_bit, 2 + MANT_BITS)).is_lt()); // result can not have more than 2 * (MANT_BITS + 1) bits
+        assert!(U256::compare(significand, U256::shift_left(implicit_bit, MANT_BITS)).is_ge()); // result looks like 1x.x{2 * MANT_BITS} or 1.x{2 * MANT_BITS}
+
+        // make result look like 1.x{2 * MANT_BITS + 1}
+        if !U256::is_zero(U256::and(
+            significand,
+            U256::shift_left(implicit_bit, 1 + MANT_BITS),
+        )) {
+            (sign, exponent + 1, significand)
+        } else {
+            (sign, exponent, U256::shift_left(significand, 1))
         }
-        // second we align to look like 1.x{10}x{3}, keeping grs bits
-        let sticky_bits = (1 << 7) - 1;
-        let sticky = (significand & sticky_bits != 0) as u64 | sticky;
-        let significand = ((significand >> 7) | sticky) as u16;
-
-        println!("{:036b} <- sig (after)", significand);
-        println!("{} <- exp (after)", exponent);
-
-        (sign, exponent, significand)
-    } else {
-        todo!();
-    };
-
-    let (exponent, significand) = if exponent <= 0 {
-        // must convert to denormal number; make exponent representable by
-        // shifting significand
-        let shift = 1 - exponent;
-        let sticky_bits = (1 << shift) - 1;
-        let sticky = (significand & sticky_bits != 0) as u16;
-        let significand = (significand >> shift) | sticky;
-        (1, significand)
-    } else {
-        (exponent as u16, significand)
-    };
-
-    // rounding (to even)
-    let grs = significand & 0x7;
-    let significand = significand >> 3;
-    let lsb = significand & 1;
-    let rnd = if grs < 0x4 || (grs == 0x4 && lsb == 0) {
-        // round down
-        0
-    } else {
-        // round up
-        1
-    };
-
-    // denormals have exponent 0
-    let exponent = if exponent == 1 && significand < 0x400 {
-        0
-    } else {
-        exponent
-    };
-
-    // cut off implicit bit and allow into exponent
-    SoftFloat16::from_bits(sign << 15 | (exponent << 10 | significand & 0x3FF) + rnd)
+    }
+
+    // adds a finite, nonzero number looking like 1.x{2 * MANT_BITS + 1} to a
+    // finite number without intermediate rounding
+    const fn add_unrounded(
+        sign0: u32,
+        exponent0: i32,
+        significand0: U256,
+        v1: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+
+        // position of the implicit bit of the first significand
+        let implicit_bit = U256::shift_left(U256::from_u128(Self::IMPLICIT_BIT), MANT_BITS + 1);
+
+        let (sign, exponent, significand) = if exponent1 == 0 && significand1 == 0 {
+            // _ + 0; insert guard, round, sticky bits
+            (sign0, exponent0, U256::shift_left(significand0, 3))
+        } else {
+            // handle denormals and implicit bit and align second significand
+            // (which looks like 1.x{MANT_BITS}) to result of multiplication
+            // (which looks like 1.x{2 * MANT_BITS + 1})
+            let (exponent1, significand1) = Self::normalize(exponent1, significand1);
+            let significand1 = U256::shift_left(U256::from_u128(significand1), MANT_BITS + 1);
+
+            // make sure that first number has larger or equal exponent to
+            // make subsequent logic easier
+            let (sign0, exponent0, significand0, sign1, exponent1, significand1) =
+                if exponent0 >= exponent1 {
+                    (
+                        sign0,
+                        exponent0,
+                        significand0,
+                        sign1,
+                        exponent1,
+                        significand1,
+                    )
+                } else {
+                    (
+                        sign1,
+                        exponent1,
+                        significand1,
+                        sign0,
+                        exponent0,
+                        significand0,
+                    )
+                };
+
+            // insert guard, round, sticky bits and align decimal point of
+            // second number
+            let significand0 = U256::shift_left(significand0, 3);
+            let significand1 =
+                U256::shift_right_sticky(U256::shift_left(significand1, 3), exponent0 - exponent1);
+
+            // if signs are equal add significands, otherwise subtract
+            if sign0 == sign1 {
+                let significand = U256::sum(significand0, significand1);
+                assert!(U256::compare(significand, U256::shift_left(implicit_bit, 2 + 3)).is_lt());
+
+                if !U256::is_zero(U256::and(
+                    significand,
+                    U256::shift_left(implicit_bit, 1 + 3),
+                )) {
+                    // realign decimal point
+                    (
+                        sign0,
+                        exponent0 + 1,
+                        U256::shift_right_sticky(significand, 1),
+                    )
+                } else {
+                    (sign0, exponent0, significand)
+                }
+            } else {
+                // always subtract smaller from larger significand and pick
+                // corresponding sign
+                let (sign, mut exponent, mut significand) =
+                    if U256::compare(significand0, significand1).is_ge() {
+                        (
+                            sign0,
+                            exponent0,
+                            U256::difference(significand0, significand1),
+                        )
+                    } else {
+                        (
+                            sign1,
+                            exponent0,
+                            U256::difference(significand1, significand0),
+                        )
+                    };
+
+                if U256::is_zero(significand) {
+                    // exact cancellation
+                    return (
+                        Self::zero(rounding_mode.exact_zero_sign()),
+                        ExceptionFlags::NONE,
+                    );
+                }
+
+                // (try to) realign decimal point; if several leading digits
+                // are canceled the shift between the two numbers was at most
+                // one, so no bits have been pushed into the sticky bit and
+                // the significand is exact
+                while U256::is_zero(U256::and(significand, U256::shift_left(implicit_bit, 3)))
+                    && exponent > 1
+                {
+                    significand = U256::shift_left(significand, 1);
+                    exponent -= 1;
+                }
+
+                (sign, exponent, significand)
+            }
+        };
+
+        // drop the lower half of the significand, keeping guard, round,
+        // sticky bits; the result looks like 1.x{MANT_BITS} with three grs
+        // bits
+        let significand =
+            U256::to_u128(U256::shift_right_sticky(significand, MANT_BITS as i32 + 1));
+
+        Self::round_and_pack(
+            sign,
+            exponent,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        )
+    }
 }
 
+// fused, like SoftFloat's
 impl MulAdd for f32 {
-    fn mul_add(_v0: Self, _v1: Self, _v2: Self) -> Self {
-        unimplemented!();
+    fn mul_add(v0: Self, v1: Self, v2: Self) -> Self {
+        v0.mul_add(v1, v2)
     }
 }
 
-impl MulAdd for i32 {
-    fn mul_add(_v0: Self, _v1: Self, _v2: Self) -> Self {
-        unimplemented!();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_exact, check_rounding, XorShift, ROUNDING_MODES};
+    use crate::{SoftFloat16, SoftFloat32};
+
+    // reference implementation; the exact result is computed as an integer
+    // multiple of 2^-48 and rounded to odd when converted to f32, which
+    // avoids double rounding when the result is subsequently converted to
+    // SoftFloat16
+    fn mul_add_f32(x0: f32, x1: f32, x2: f32) -> f32 {
+        if !x0.is_finite() || !x1.is_finite() || !x2.is_finite() {
+            return x0.mul_add(x1, x2);
+        }
+
+        let scale = (1_i64 << 48) as f32;
+        let product = (x0 * (1 << 24) as f32) as i128 * (x1 * (1 << 24) as f32) as i128;
+        let exact = product + (x2 * scale) as i128;
+        if exact == 0 {
+            // sign of exact zero is handled correctly by f32
+            return x0.mul_add(x1, x2);
+        }
+
+        let y = exact as f32;
+        let bits = y.to_bits();
+        let y = if y as i128 != exact && bits & 1 == 0 {
+            if (exact > y as i128) == (y > 0.0) {
+                f32::from_bits(bits + 1)
+            } else {
+                f32::from_bits(bits - 1)
+            }
+        } else {
+            y
+        };
+        y / scale
+    }
+
+    fn check_mul_add(v0: u16, v1: u16, v2: u16) {
+        let x0 = SoftFloat16::from_bits(v0);
+        let x1 = SoftFloat16::from_bits(v1);
+        let x2 = SoftFloat16::from_bits(v2);
+        let y = SoftFloat16::mul_add(x0, x1, x2);
+        let y_f = SoftFloat16::from(mul_add_f32(f32::from(x0), f32::from(x1), f32::from(x2)));
+        if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+            assert!(SoftFloat16::is_nan(y), "{:?}", (v0, v1, v2));
+            assert!(SoftFloat16::is_nan(y_f), "{:?}", (v0, v1, v2));
+        } else {
+            assert_eq!(
+                SoftFloat16::to_bits(y),
+                SoftFloat16::to_bits(y_f),
+                "\n{:?}\n  {:016b}\n  {:016b}",
+                (v0, v1, v2),
+                SoftFloat16::to_bits(y),
+                SoftFloat16::to_bits(y_f),
+            );
+        }
+    }
+
+    #[test]
+    fn test_mul_add() {
+        for (v0, v1, v2) in [
+            (0x3e00, 0x3e00, 0x3e00),
+            (0x3e00, 0x3e00, 0x3a00),
+            (0x3e00, 0x3e00, 0x4200),
+            (0x1e00, 0x1e00, 0x3200),
+            (0x1e00, 0x1e00, 0x0200),
+            (0x1a00, 0x1a00, 0x0100),
+            (0x1, 0x1, 0x7bff),
+            (0x1, 0x1, 0x8001),
+            (0x5900, 0x5900, 0x1),
+            (0x8444, 0xbb7e, 0xb430),
+            (0x3c01, 0x3c01, 0xbc02),
+            (0x3c01, 0x3bff, 0xbc00),
+            (0x3c00, 0x3c00, 0xbc00),
+            (0x8000, 0x3c00, 0x8000),
+            (0x0000, 0x3c00, 0x8000),
+            (0x7bff, 0x4000, 0xfbff),
+            (0x7bff, 0x3c01, 0x0000),
+            (0x7c00, 0x0000, 0x3c00),
+            (0x7c00, 0x3c00, 0xfc00),
+            (0x3c00, 0x3c00, 0x7c00),
+            (0x7e00, 0x3c00, 0x3c00),
+        ] {
+            check_mul_add(v0, v1, v2);
+        }
     }
-}
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-
-//     #[test]
-//     fn test_mul_add() {
-//         for (v0, v1, v2) in [
-//             (0x3e00, 0x3e00, 0x3e00),
-//             (0x3e00, 0x3e00, 0x3a00),
-//             (0x3e00, 0x3e00, 0x4200),
-//             (0x1e00, 0x1e00, 0x3200),
-//             (0x1e00, 0x1e00, 0x0200),
-//             (0x1a00, 0x1a00, 0x0100),
-//             (0x1, 0x1, 0x7bff),
-//             (0x5900, 0x5900, 0x1),
-//             // 8444 BB7E B430 B430
-//             // (0x8444, 0xBB7E, 0xB430),
-//         ] {
-//             let x0 = SoftFloat16::from_bits(v0);
-//             let x1 = SoftFloat16::from_bits(v1);
-//             let x2 = SoftFloat16::from_bits(v2);
-//             let y = SoftFloat16::mul_add(x0, x1, x2);
-//             let y_f = SoftFloat16::from(f32::from(x0).mul_add(f32::from(x1), f32::from(x2)));
-//             println!("\n{:016b} {} <- y", SoftFloat16::to_bits(y), y);
-//             println!("{:016b} {} <- y_f", SoftFloat16::to_bits(y_f), y_f);
-//             assert_eq!(SoftFloat16::to_bits(y), SoftFloat16::to_bits(y_f));
-//         }
-//     }
-
-//     #[test]
-//     #[ignore]
-//     fn test_all_mul_add() {
-//         for i in 0..u16::MAX {
-//             for j in 0..u16::MAX {
-//                 for k in 0..u16::MAX {
-//                     let x0 = SoftFloat16::from_bits(i);
-//                     let x1 = SoftFloat16::from_bits(j);
-//                     let x2 = SoftFloat16::from_bits(k);
-//                     let y = SoftFloat16::mul_add(x0, x1, x2);
-//                     let y_f = SoftFloat16::from(f32::from(x0).mul_add(f32::from(x1), f32::from(x2)));
-//                     if y == NAN || y_f == NAN {
-//                         assert!(y == NAN, "{:?}", (i, j, k));
-//                         assert!(y_f == NAN, "{:?}", (i, j, k));
-//                     } else {
-//                         assert_eq!(
-//                             SoftFloat16::to_bits(y),
-//                             SoftFloat16::to_bits(y_f),
-//                             "{:?}",
-//                             (i, j, k)
-//                         );
-//                     }
-//                 }
-//             }
-//         }
-//     }
-// }
+    #[test]
+    fn test_random_mul_add() {
+        let mut rng = XorShift::new();
+        for _ in 0..1_000_000 {
+            let (v0, v1, v2) = (rng.next_u16(), rng.next_u16(), rng.next_u16());
+            check_mul_add(v0, v1, v2);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_mul_add() {
+        // testing all triples is not feasible, so we fix a couple of addends
+        for k in (0..u16::MAX).step_by(0x0fff) {
+            for i in 0..u16::MAX {
+                for j in 0..u16::MAX {
+                    let x0 = SoftFloat16::from_bits(i);
+                    let x1 = SoftFloat16::from_bits(j);
+                    let x2 = SoftFloat16::from_bits(k);
+                    let y = SoftFloat16::mul_add(x0, x1, x2);
+                    let y_f =
+                        SoftFloat16::from(mul_add_f32(f32::from(x0), f32::from(x1), f32::from(x2)));
+                    if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                        assert!(SoftFloat16::is_nan(y), "{:?}", (i, j, k));
+                        assert!(SoftFloat16::is_nan(y_f), "{:?}", (i, j, k));
+                    } else {
+                        assert_eq!(
+                            SoftFloat16::to_bits(y),
+                            SoftFloat16::to_bits(y_f),
+                            "{:?}",
+                            (i, j, k)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_all_mul_add<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        // testing all triples takes too long without optimizations, so we
+        // fix a couple of addends
+        for k in (0..=u8::MAX).step_by(5) {
+            for i in 0..=u8::MAX {
+                for j in 0..=u8::MAX {
+                    let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                    let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                    let x2 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(k);
+                    let y = SoftFloat::mul_add(x0, x1, x2);
+                    let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(mul_add_f32(
+                        f32::from(x0),
+                        f32::from(x1),
+                        f32::from(x2),
+                    ));
+                    if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                        assert!(SoftFloat::is_nan(y), "{:?}", (i, j, k));
+                        assert!(SoftFloat::is_nan(y_f), "{:?}", (i, j, k));
+                    } else {
+                        assert_eq!(
+                            SoftFloat::to_bits(y),
+                            SoftFloat::to_bits(y_f),
+                            "{:?}",
+                            (i, j, k)
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_mul_add_binary8() {
+        check_all_mul_add::<2, 5>();
+        check_all_mul_add::<3, 4>();
+        check_all_mul_add::<4, 3>();
+        check_all_mul_add::<5, 2>();
+    }
+
+    // unlike v0 * v1 + v2, rounded once
+    #[test]
+    fn test_mul_add_f32() {
+        let (v0, v1, v2) = (1.0 + f32::EPSILON, 1.0 - f32::EPSILON, -1.0);
+        let y = <f32 as MulAdd>::mul_add(v0, v1, v2);
+        assert_eq!(y, -f32::EPSILON * f32::EPSILON);
+        let y_f = MulAdd::mul_add(
+            SoftFloat32::from(v0),
+            SoftFloat32::from(v1),
+            SoftFloat32::from(v2),
+        );
+        assert_eq!(y.to_bits(), f32::from(y_f).to_bits());
+    }
+
+    fn check_mul_add_rounding(i: u16, j: u16, k: u16, rounding_mode: RoundingMode) {
+        let x0 = SoftFloat16::from_bits(i);
+        let x1 = SoftFloat16::from_bits(j);
+        let x2 = SoftFloat16::from_bits(k);
+        let y = SoftFloat16::mul_add_with_rounding(x0, x1, x2, rounding_mode);
+        let nearest = SoftFloat16::mul_add(x0, x1, x2);
+        let (v0, v1, v2) = (f32::from(x0), f32::from(x1), f32::from(x2));
+        if !v0.is_finite() || !v1.is_finite() || !v2.is_finite() {
+            // special cases are exact; the product is formed in f64, where it
+            // can't overflow unlike x0 * x1, to keep it finite before the
+            // addition of an infinite addend
+            let nearest = SoftFloat16::from(v0 as f64 * v1 as f64 + v2 as f64);
+            check_exact(y, nearest, rounding_mode, &[i, j, k]);
+            return;
+        }
+        if v0 * v1 == 0.0 {
+            // zero products are exact, the sign of a zero sum depends on the
+            // rounding mode
+            let nearest = SoftFloat16::add_with_rounding(x0 * x1, x2, rounding_mode);
+            check_exact(y, nearest, rounding_mode, &[i, j, k]);
+            return;
+        }
+
+        // exact result is an integer multiple of 2^-48
+        let scale = (1_i64 << 48) as f64;
+        let exact = (v0 as f64 * (1 << 24) as f64) as i128 * (v1 as f64 * (1 << 24) as f64) as i128
+            + (v2 as f64 * scale) as i128;
+        if exact == 0 {
+            let expected = (rounding_mode.exact_zero_sign() << 15) as u16;
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:?}", (i, j, k));
+        } else {
+            let compare = |c: f64| exact.cmp(&((c * scale) as i128));
+            check_rounding(y, nearest, compare, rounding_mode, &[i, j, k]);
+        }
+    }
+
+    #[test]
+    fn test_mul_add_rounding() {
+        for rounding_mode in ROUNDING_MODES {
+            // exact zeros, tiny products and overflow
+            check_mul_add_rounding(0x3c00, 0x3c00, 0xbc00, rounding_mode);
+            check_mul_add_rounding(0x0001, 0x0001, 0x3c00, rounding_mode);
+            check_mul_add_rounding(0x0001, 0x0001, 0x0000, rounding_mode);
+            check_mul_add_rounding(0x0001, 0x8001, 0x0000, rounding_mode);
+            check_mul_add_rounding(0x7bff, 0x4000, 0xfbff, rounding_mode);
+            check_mul_add_rounding(0xc49a, 0xf6a1, 0xfc00, rounding_mode);
+        }
+    }
+
+    #[test]
+    fn test_random_mul_add_rounding() {
+        let mut rng = XorShift::new();
+        for _ in 0..100_000 {
+            let (i, j, k) = (rng.next_u16(), rng.next_u16(), rng.next_u16());
+            for rounding_mode in ROUNDING_MODES {
+                check_mul_add_rounding(i, j, k, rounding_mode);
+            }
+        }
+    }
+}
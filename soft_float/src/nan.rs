@@ -0,0 +1,309 @@
+use crate::format::Format;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage};
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn is_nan(v: Self) -> bool {
+        Format::<EXP_BITS, MANT_BITS>::is_nan(Self::raw(v))
+    }
+
+    pub fn is_signaling(v: Self) -> bool {
+        Format::<EXP_BITS, MANT_BITS>::is_signaling(Self::raw(v))
+    }
+
+    pub fn is_quiet(v: Self) -> bool {
+        Self::is_nan(v) && !Self::is_signaling(v)
+    }
+
+    // returns the payload of a NAN as an integral number, or -1 if v is not
+    // a NAN, see IEEE 754, sec. 9.7
+    pub fn get_payload(v: Self) -> Self {
+        Self::from_raw(Format::<EXP_BITS, MANT_BITS>::get_payload(Self::raw(v)))
+    }
+
+    // returns a quiet NAN with payload pl, or +0 if pl is not a valid payload
+    pub fn set_payload(pl: Self) -> Self {
+        Self::from_raw(Format::<EXP_BITS, MANT_BITS>::set_payload(Self::raw(pl)))
+    }
+
+    // returns a signaling NAN with payload pl, or +0 if pl is not a valid
+    // payload; zero is not a valid payload since the resulting encoding
+    // would be infinity
+    pub fn set_payload_signaling(pl: Self) -> Self {
+        Self::from_raw(Format::<EXP_BITS, MANT_BITS>::set_payload_signaling(
+            Self::raw(pl),
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // the bits of the significand of a NAN below the quiet bit hold the
+    // payload
+    const PAYLOAD_BITS: u128 = Self::QUIET_BIT - 1;
+
+    pub(crate) const fn is_nan(v: u128) -> bool {
+        let (_, exponent, significand) = Self::unpack(v);
+        exponent == Self::EXPONENT_MASK && significand != 0
+    }
+
+    pub(crate) const fn is_signaling(v: u128) -> bool {
+        Self::is_nan(v) && v & Self::QUIET_BIT == 0
+    }
+
+    pub(crate) fn get_payload(v: u128) -> u128 {
+        if Self::is_nan(v) {
+            Self::from_integer(0, v & Self::PAYLOAD_BITS, RoundingMode::NearestTiesToEven).0
+        } else {
+            Self::from_integer(1, 1, RoundingMode::NearestTiesToEven).0
+        }
+    }
+
+    pub(crate) fn set_payload(pl: u128) -> u128 {
+        match Self::payload(pl) {
+            Some(payload) => Self::pack(0, Self::EXPONENT_MASK, Self::QUIET_BIT | payload),
+            None => Self::zero(0),
+        }
+    }
+
+    pub(crate) fn set_payload_signaling(pl: u128) -> u128 {
+        match Self::payload(pl) {
+            Some(payload) if payload != 0 => Self::pack(0, Self::EXPONENT_MASK, payload),
+            _ => Self::zero(0),
+        }
+    }
+
+    // NAN results of operations with NAN operands are the first operand that
+    // is a NAN, quieted, i.e., sign and payload are preserved; this is the
+    // rule of x86 SSE and of Berkeley SoftFloat's 8086-SSE specialization; a
+    // signaling NAN among the operands raises the invalid exception
+    pub(crate) const fn propagate_nan(operands: &[u128]) -> (u128, ExceptionFlags) {
+        let mut flags = ExceptionFlags::NONE;
+        let mut i = 0;
+        while i < operands.len() {
+            if Self::is_signaling(operands[i]) {
+                flags = ExceptionFlags::INVALID;
+            }
+            i += 1;
+        }
+
+        let mut i = 0;
+        while !Self::is_nan(operands[i]) {
+            i += 1;
+            assert!(i < operands.len(), "should have at least one NAN operand");
+        }
+
+        (operands[i] | Self::QUIET_BIT, flags)
+    }
+
+    // converts an integral number in the range of payloads to an integer
+    fn payload(pl: u128) -> Option<u128> {
+        let (sign, exponent, significand) = Self::unpack(pl);
+
+        if exponent == 0 && significand == 0 {
+            // +0 or -0
+            return Some(0);
+        } else if sign == 1 || !(Self::BIAS..=Self::BIAS + MANT_BITS as i32 - 2).contains(&exponent)
+        {
+            // negative, too small or too large; note that
+            // 2^(MANT_BITS - 1) - 1 is the largest payload
+            return None;
+        }
+
+        // shift to make sure lsb has exponent 0; any fraction means pl is not
+        // integral
+        let shift = MANT_BITS as i32 - (exponent - Self::BIAS);
+        let significand = significand | Self::IMPLICIT_BIT;
+        if significand & ((1 << shift) - 1) != 0 {
+            None
+        } else {
+            Some(significand >> shift)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SoftFloat16, TininessDetection};
+
+    #[test]
+    fn test_classify() {
+        for (v, is_nan, is_signaling) in [
+            (0x7e00, true, false),
+            (0xfe01, true, false),
+            (0x7fff, true, false),
+            (0x7c01, true, true),
+            (0xfdff, true, true),
+            (0x7c00, false, false),
+            (0x3c00, false, false),
+            (0x0000, false, false),
+        ] {
+            let x = SoftFloat16::from_bits(v);
+            assert_eq!(SoftFloat16::to_bits(x), v);
+            assert_eq!(SoftFloat16::is_nan(x), is_nan, "{:04x}", v);
+            assert_eq!(SoftFloat16::is_signaling(x), is_signaling, "{:04x}", v);
+            assert_eq!(
+                SoftFloat16::is_quiet(x),
+                is_nan && !is_signaling,
+                "{:04x}",
+                v
+            );
+        }
+    }
+
+    #[test]
+    fn test_payload() {
+        for (v, expected) in [
+            (0x7e00, 0x0000),
+            (0x7e01, 0x3c00),
+            (0xfe2a, 0x5140),
+            (0x7fff, 0x5ffc),
+            (0x7c05, 0x4500),
+            (0x3c00, 0xbc00),
+            (0x7c00, 0xbc00),
+        ] {
+            let x = SoftFloat16::from_bits(v);
+            let y = SoftFloat16::get_payload(x);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:04x}", v);
+        }
+
+        for (pl, expected, expected_signaling) in [
+            (0x0000, 0x7e00, 0x0000),
+            (0x8000, 0x7e00, 0x0000),
+            (0x3c00, 0x7e01, 0x7c01),
+            (0x5140, 0x7e2a, 0x7c2a),
+            (0x5ffc, 0x7fff, 0x7dff),
+            (0x6000, 0x0000, 0x0000),
+            (0x3e00, 0x0000, 0x0000),
+            (0xbc00, 0x0000, 0x0000),
+            (0x7c00, 0x0000, 0x0000),
+            (0x7e00, 0x0000, 0x0000),
+        ] {
+            let x = SoftFloat16::from_bits(pl);
+            let y = SoftFloat16::set_payload(x);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:04x}", pl);
+            let y = SoftFloat16::set_payload_signaling(x);
+            assert_eq!(SoftFloat16::to_bits(y), expected_signaling, "{:04x}", pl);
+        }
+    }
+
+    #[test]
+    fn test_all_payload() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            if SoftFloat16::is_quiet(x) && SoftFloat16::sign(x) == 0 {
+                let y = SoftFloat16::set_payload(SoftFloat16::get_payload(x));
+                assert_eq!(SoftFloat16::to_bits(y), i);
+            } else if SoftFloat16::is_signaling(x) && SoftFloat16::sign(x) == 0 {
+                let y = SoftFloat16::set_payload_signaling(SoftFloat16::get_payload(x));
+                assert_eq!(SoftFloat16::to_bits(y), i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_propagate_nan() {
+        let rm = RoundingMode::NearestTiesToEven;
+        let td = TininessDetection::AfterRounding;
+        let (one, two) = (SoftFloat16::from(1), SoftFloat16::from(2));
+        let (qnan, snan) = (
+            SoftFloat16::from_bits(0xfe2a),
+            SoftFloat16::from_bits(0x7c15),
+        );
+
+        for ((y, flags), expected, expected_flags) in [
+            (
+                SoftFloat16::add_with_flags(one, qnan, rm, td),
+                0xfe2a,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::add_with_flags(snan, one, rm, td),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::add_with_flags(qnan, snan, rm, td),
+                0xfe2a,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::add_with_flags(snan, qnan, rm, td),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::sub_with_flags(one, qnan, rm, td),
+                0xfe2a,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::mul_with_flags(snan, one, rm, td),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::div_with_flags(qnan, snan, rm, td),
+                0xfe2a,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::sqrt_with_flags(snan, rm),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::rem_with_flags(two, snan),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::mul_add_with_flags(one, two, snan, rm, td),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::mul_add_with_flags(one, qnan, snan, rm, td),
+                0xfe2a,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::round_ties_even_with_flags(snan),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+            (
+                SoftFloat16::from_f32_with_flags(f32::from_bits(0xffc54000), rm, td),
+                0xfe2a,
+                ExceptionFlags::NONE,
+            ),
+            (
+                SoftFloat16::from_f32_with_flags(f32::from_bits(0x7f82a000), rm, td),
+                0x7e15,
+                ExceptionFlags::INVALID,
+            ),
+        ] {
+            assert_eq!(SoftFloat16::to_bits(y), expected);
+            assert_eq!(flags, expected_flags);
+        }
+
+        for (x, expected, expected_flags) in [
+            (qnan, 0xffc54000, ExceptionFlags::NONE),
+            (snan, 0x7fc2a000, ExceptionFlags::INVALID),
+        ] {
+            let (y, flags) = SoftFloat16::to_f32_with_flags(x, rm, td);
+            assert_eq!(y.to_bits(), expected);
+            assert_eq!(flags, expected_flags);
+        }
+
+        // comparisons and negation don't return NANs
+        assert_eq!(
+            SoftFloat16::eq_with_flags(snan, one),
+            (false, ExceptionFlags::INVALID)
+        );
+        assert_eq!(
+            SoftFloat16::eq_with_flags(qnan, one),
+            (false, ExceptionFlags::NONE)
+        );
+        assert_eq!(SoftFloat16::to_bits(-snan), 0xfc15);
+    }
+}
@@ -1,16 +1,16 @@
 use std::ops::Neg;
 
-use crate::soft_float16::NAN;
-use crate::SoftFloat16;
+use crate::format::Format;
+use crate::{SoftFloat, Storage};
 
-impl Neg for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Neg
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     type Output = Self;
 
+    // only flips the sign bit, i.e., NANs keep their payload and signaling
+    // NANs are not quieted, see IEEE 754, sec. 5.5.1
     fn neg(self) -> Self {
-        if self == NAN {
-            NAN
-        } else {
-            Self::from_bits(Self::to_bits(self) ^ (1 << 15))
-        }
+        Self::from_raw(Self::raw(self) ^ Format::<EXP_BITS, MANT_BITS>::SIGN_BIT)
     }
 }
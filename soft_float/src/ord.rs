@@ -1,21 +1,54 @@
 use std::cmp::Ordering;
 
-use crate::SoftFloat16;
+use crate::format::Format;
+use crate::{ExceptionFlags, SoftFloat, Storage};
 
-impl PartialOrd for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> PartialOrd
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let (sign0, exponent0, significand0) = (
-            Self::sign(*self),
-            Self::exponent(*self),
-            Self::significand(*self),
-        );
-        let (sign1, exponent1, significand1) = (
-            Self::sign(*other),
-            Self::exponent(*other),
-            Self::significand(*other),
-        );
+        Format::<EXP_BITS, MANT_BITS>::partial_cmp(Self::raw(*self), Self::raw(*other))
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // signaling comparison, i.e., comparing with NAN raises the invalid
+    // exception, see IEEE 754, sec. 5.11
+    pub fn le_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        match v0.partial_cmp(&v1) {
+            None => (false, ExceptionFlags::INVALID),
+            Some(ordering) => (ordering != Ordering::Greater, ExceptionFlags::NONE),
+        }
+    }
+
+    pub fn lt_with_flags(v0: Self, v1: Self) -> (bool, ExceptionFlags) {
+        match v0.partial_cmp(&v1) {
+            None => (false, ExceptionFlags::INVALID),
+            Some(ordering) => (ordering == Ordering::Less, ExceptionFlags::NONE),
+        }
+    }
+
+    // totalOrder, see IEEE 754, sec. 5.10: -NAN < -inf < ... < -0 < +0 < ... <
+    // +inf < +NAN, where NANs of the same sign are ordered by their encoding,
+    // i.e., signaling before quiet ones, then by payload; compare f32::total_cmp
+    pub fn total_cmp(v0: Self, v1: Self) -> Ordering {
+        Format::<EXP_BITS, MANT_BITS>::total_cmp(Self::raw(v0), Self::raw(v1))
+    }
+
+    // totalOrderMag, i.e., totalOrder of the absolute values
+    pub fn total_cmp_mag(v0: Self, v1: Self) -> Ordering {
+        Format::<EXP_BITS, MANT_BITS>::total_cmp_mag(Self::raw(v0), Self::raw(v1))
+    }
+}
 
-        if (exponent0 == 0x1F && significand0 != 0) || (exponent1 == 0x1F && significand1 != 0) {
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn partial_cmp(v0: u128, v1: u128) -> Option<Ordering> {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (sign1, exponent1, significand1) = Self::unpack(v1);
+
+        if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
             // NAN == NAN
             None
         } else if (exponent0 == 0 && significand0 == 0) && (exponent1 == 0 && significand1 == 0) {
@@ -27,20 +60,57 @@ impl PartialOrd for SoftFloat16 {
             Some(Ordering::Less)
         } else {
             assert!(sign0 == sign1);
-            let self_bits_abs = SoftFloat16::to_bits(*self) & 0x7fff;
-            let other_bits_abs = SoftFloat16::to_bits(*other) & 0x7fff;
-            if sign0 == 0 {
-                self_bits_abs.partial_cmp(&other_bits_abs)
+            let bits_abs0 = v0 & !Self::SIGN_BIT;
+            let bits_abs1 = v1 & !Self::SIGN_BIT;
+            let (smaller, larger) = if sign0 == 0 {
+                (bits_abs0, bits_abs1)
+            } else {
+                (bits_abs1, bits_abs0)
+            };
+            if smaller < larger {
+                Some(Ordering::Less)
+            } else if smaller == larger {
+                Some(Ordering::Equal)
             } else {
-                other_bits_abs.partial_cmp(&self_bits_abs)
+                Some(Ordering::Greater)
             }
         }
     }
+
+    pub(crate) const fn total_cmp(v0: u128, v1: u128) -> Ordering {
+        compare(Self::total_key(v0), Self::total_key(v1))
+    }
+
+    pub(crate) const fn total_cmp_mag(v0: u128, v1: u128) -> Ordering {
+        compare(v0 & !Self::SIGN_BIT, v1 & !Self::SIGN_BIT)
+    }
+
+    // maps encodings to integers in totalOrder: negative numbers are mirrored
+    // below SIGN_BIT, positive ones are moved above it
+    const fn total_key(v: u128) -> u128 {
+        if v & Self::SIGN_BIT != 0 {
+            Self::SIGN_BIT - 1 - (v & !Self::SIGN_BIT)
+        } else {
+            v | Self::SIGN_BIT
+        }
+    }
+}
+
+// same as Ord::cmp, which is not available in const contexts
+const fn compare(v0: u128, v1: u128) -> Ordering {
+    if v0 < v1 {
+        Ordering::Less
+    } else if v0 == v1 {
+        Ordering::Equal
+    } else {
+        Ordering::Greater
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SoftFloat16;
 
     #[test]
     #[ignore]
@@ -69,4 +139,82 @@ mod tests {
             }
         }
     }
+
+    fn check_all_cmp<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0.partial_cmp(&x1);
+                let y_f = f32::from(x0).partial_cmp(&f32::from(x1));
+                assert_eq!(y, y_f, "{:?}", (i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_cmp_binary8() {
+        check_all_cmp::<2, 5>();
+        check_all_cmp::<3, 4>();
+        check_all_cmp::<4, 3>();
+        check_all_cmp::<5, 2>();
+    }
+
+    // independent of the encoding: NANs by sign and payload (including the
+    // quiet bit), all other numbers by f32::total_cmp, which distinguishes
+    // the zeros
+    fn check_all_total_cmp<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        let key = |x: SoftFloat<u8, EXP_BITS, MANT_BITS>| {
+            let sign = 1 - 2 * SoftFloat::sign(x) as i32;
+            if SoftFloat::is_nan(x) {
+                (sign, sign * SoftFloat::significand(x) as i32, 0.0)
+            } else {
+                (0, 0, f32::from(x))
+            }
+        };
+        let sign_bit = 1 << (EXP_BITS + MANT_BITS);
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let (k0, k1) = (key(x0), key(x1));
+                let y = SoftFloat::total_cmp(x0, x1);
+                let y_f = (k0.0, k0.1).cmp(&(k1.0, k1.1)).then(k0.2.total_cmp(&k1.2));
+                assert_eq!(y, y_f, "{:?}", (i, j));
+
+                let abs0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i & !sign_bit);
+                let abs1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j & !sign_bit);
+                let y = SoftFloat::total_cmp_mag(x0, x1);
+                assert_eq!(y, SoftFloat::total_cmp(abs0, abs1), "{:?}", (i, j));
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_total_cmp_binary8() {
+        check_all_total_cmp::<2, 5>();
+        check_all_total_cmp::<3, 4>();
+        check_all_total_cmp::<4, 3>();
+        check_all_total_cmp::<5, 2>();
+    }
+
+    #[test]
+    fn test_total_cmp() {
+        let mut values = [
+            0x7e00, 0x0000, 0xfc00, 0x8000, 0x7c01, 0x3c00, 0xfe00, 0x7c00, 0x0001, 0xbc00,
+        ]
+        .map(SoftFloat16::from_bits);
+        values.sort_by(|v0, v1| SoftFloat16::total_cmp(*v0, *v1));
+        assert_eq!(
+            values.map(SoftFloat16::to_bits),
+            [0xfe00, 0xfc00, 0xbc00, 0x8000, 0x0000, 0x0001, 0x3c00, 0x7c00, 0x7c01, 0x7e00]
+        );
+        assert_eq!(
+            SoftFloat16::total_cmp_mag(
+                SoftFloat16::from_bits(0xbc00),
+                SoftFloat16::from_bits(0x3c00)
+            ),
+            Ordering::Equal
+        );
+    }
 }
@@ -0,0 +1,10 @@
+// handling of finite results that are too large to be represented when
+// narrowing to 8-bit formats, see the OCP 8-bit Floating Point Specification
+// (OFP8), sec. 5.2; saturating conversions return the largest finite number
+// of the same sign instead, even for infinite inputs, and keep NANs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowMode {
+    #[default]
+    NonSaturating,
+    Saturating,
+}
@@ -0,0 +1,614 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::big_uint::BigUint;
+use crate::format::Format;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
+
+// reason a string could not be parsed, compare std::num::ParseFloatError
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseSoftFloatError {
+    Empty,
+    Invalid,
+}
+
+impl Display for ParseSoftFloatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "cannot parse float from empty string"),
+            Self::Invalid => write!(f, "invalid float literal"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSoftFloatError {}
+
+// accepts the same syntax as f32::from_str: an optional sign followed by
+// "inf", "infinity", "nan" (ignoring case), or a decimal number with optional
+// fraction and exponent, e.g., "12", "-1.5e-3", ".5", or "1."; additionally,
+// hexadecimal numbers like C99's "%a" with an optional binary exponent, e.g.,
+// "0x1.ffcp+15", "-0X.8P-3", or "0x10"
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> FromStr
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    type Err = ParseSoftFloatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_rounding(s, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn from_str_with_rounding(
+        s: &str,
+        rounding_mode: RoundingMode,
+    ) -> Result<Self, ParseSoftFloatError> {
+        Self::from_str_with_flags(s, rounding_mode, TininessDetection::default()).map(|(v, _)| v)
+    }
+
+    // the decimal or hexadecimal number is rounded once, directly to this
+    // format, no matter how many digits it has
+    pub fn from_str_with_flags(
+        s: &str,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> Result<(Self, ExceptionFlags), ParseSoftFloatError> {
+        Format::<EXP_BITS, MANT_BITS>::parse(s.as_bytes(), false, rounding_mode, tininess_detection)
+            .map(Self::from_raw_with_flags)
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // significant decimal digits that suffice for rounding correctly in any
+    // mode: the numbers with the most digits that rounding has to tell apart
+    // are those halfway between two neighbors, odd multiples m / 2^k of
+    // 2^-(BIAS + MANT_BITS) with m < 2^(MANT_BITS + 2), and m / 2^k =
+    // m * 5^k / 10^k has fewer than (MANT_BITS + 2) * log10(2) + k * log10(5)
+    // + 1 significant digits
+    const MAX_DIGITS: u32 =
+        ((Self::BIAS as u32 + MANT_BITS) * 699 + (MANT_BITS + 2) * 302) / 1000 + 2;
+
+    // the syntax of from_str, on bytes, as most str methods are not const;
+    // underscores between digits are skipped if requested (for f16!); digits
+    // beyond the significant ones that are needed are replaced by a single 1
+    // if any of them is nonzero, which keeps the number strictly between the
+    // same two numbers with fewer digits, so it rounds the same way
+    pub(crate) const fn parse(
+        s: &[u8],
+        underscores: bool,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> Result<(u128, ExceptionFlags), ParseSoftFloatError> {
+        let (sign, s) = match s {
+            [] => return Err(ParseSoftFloatError::Empty),
+            [b'-', s @ ..] => (1, s),
+            [b'+', s @ ..] => (0, s),
+            s => (0, s),
+        };
+
+        if s.eq_ignore_ascii_case(b"nan") {
+            return Ok((
+                Self::NAN | (sign as u128) << (EXP_BITS + MANT_BITS),
+                ExceptionFlags::NONE,
+            ));
+        } else if s.eq_ignore_ascii_case(b"inf") || s.eq_ignore_ascii_case(b"infinity") {
+            return Ok((Self::infinity(sign), ExceptionFlags::NONE));
+        }
+
+        // hexadecimal significands need at most MANT_BITS + 2 bits plus up to
+        // three leading zeros of the first digit, which fit into 32 digits
+        let (radix, max_digits, s) = match s {
+            [b'0', b'x' | b'X', s @ ..] => (16, 32, s),
+            s => (10, Self::MAX_DIGITS, s),
+        };
+
+        // significant digits, i.e., without leading zeros, and the exponent
+        // (in digits) of the last one kept
+        let mut digits = BigUint::zero();
+        let mut count = 0;
+        let mut exponent = 0;
+        let mut sticky = false;
+        let mut any_digits = false;
+        let mut point = false;
+        let mut i = 0;
+        while i < s.len() {
+            let c = s[i];
+            let digit = match c {
+                b'0'..=b'9' => (c - b'0') as u32,
+                b'a'..=b'f' => (c - b'a' + 10) as u32,
+                b'A'..=b'F' => (c - b'A' + 10) as u32,
+                _ => radix,
+            };
+            if digit < radix {
+                any_digits = true;
+                if count < max_digits {
+                    digits.mul_add_small(radix, digit);
+                    count += !digits.is_zero() as u32;
+                    exponent -= point as i64;
+                } else {
+                    sticky |= digit != 0;
+                    exponent += !point as i64;
+                }
+            } else if c == b'.' && !point {
+                point = true;
+            } else if !(c == b'_' && underscores) {
+                break;
+            }
+            i += 1;
+        }
+        if !any_digits {
+            return Err(ParseSoftFloatError::Invalid);
+        }
+        if sticky {
+            digits.mul_add_small(radix, 1);
+            exponent -= 1;
+        }
+
+        let exponent_offset = match s.split_at(i).1 {
+            [] => 0,
+            [b'e' | b'E', s @ ..] if radix == 10 => match parse_exponent(s, underscores) {
+                Ok(exponent) => exponent,
+                Err(error) => return Err(error),
+            },
+            [b'p' | b'P', s @ ..] if radix == 16 => match parse_exponent(s, underscores) {
+                Ok(exponent) => exponent,
+                Err(error) => return Err(error),
+            },
+            _ => return Err(ParseSoftFloatError::Invalid),
+        };
+
+        Ok(if radix == 10 {
+            Self::from_decimal(
+                sign,
+                digits,
+                exponent + exponent_offset,
+                rounding_mode,
+                tininess_detection,
+            )
+        } else {
+            Self::from_binary(
+                sign,
+                &digits,
+                4 * exponent + exponent_offset,
+                rounding_mode,
+                tininess_detection,
+            )
+        })
+    }
+}
+
+// saturates, as all exponents beyond the range of i32 lead to overflow or
+// underflow anyway
+const fn parse_exponent(s: &[u8], underscores: bool) -> Result<i64, ParseSoftFloatError> {
+    let (negative, s) = match s {
+        [b'-', s @ ..] => (true, s),
+        [b'+', s @ ..] => (false, s),
+        s => (false, s),
+    };
+    let mut magnitude = 0;
+    let mut any_digits = false;
+    let mut i = 0;
+    while i < s.len() {
+        match s[i] {
+            c @ b'0'..=b'9' => {
+                magnitude = 10 * magnitude + (c - b'0') as i64;
+                if magnitude > i32::MAX as i64 {
+                    magnitude = i32::MAX as i64;
+                }
+                any_digits = true;
+            }
+            b'_' if underscores => {}
+            _ => return Err(ParseSoftFloatError::Invalid),
+        }
+        i += 1;
+    }
+    if !any_digits {
+        return Err(ParseSoftFloatError::Invalid);
+    }
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // rounds digits * 10^exponent; the exact value is computed with as many
+    // bits as necessary, so there is no double rounding
+    pub(crate) const fn from_decimal(
+        sign: u32,
+        digits: BigUint,
+        exponent: i64,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        if digits.is_zero() {
+            return (Self::zero(sign), ExceptionFlags::NONE);
+        }
+
+        // numbers far outside the range of the format only need to be
+        // rounded correctly, so they are replaced by numbers that are out of
+        // range as well, which avoids huge powers of ten;
+        // 2^(bits - 1) <= digits < 2^bits and 2^(3 * exponent) <= 10^exponent
+        // < 2^(4 * exponent) for positive exponents (and the other way round
+        // for negative ones) bound the binary exponent
+        let bits = digits.bits() as i64;
+        let (lower, upper) = if exponent >= 0 {
+            (bits - 1 + 3 * exponent, bits + 4 * exponent)
+        } else {
+            (bits - 1 + 4 * exponent, bits + 3 * exponent)
+        };
+        let implicit_bit = 1 << (MANT_BITS + 3);
+        if lower > Self::BIAS as i64 + 1 {
+            return Self::round_and_pack(
+                sign,
+                Self::EXPONENT_MASK,
+                implicit_bit,
+                rounding_mode,
+                tininess_detection,
+            );
+        } else if upper < -(Self::BIAS as i64) - MANT_BITS as i64 - 2 {
+            // an eighth of the smallest denormal number
+            return Self::round_and_pack(
+                sign,
+                -(MANT_BITS as i32) - 2,
+                implicit_bit,
+                rounding_mode,
+                tininess_detection,
+            );
+        }
+
+        // significand with MANT_BITS + 1 bits and guard, round, sticky bits,
+        // and the unbiased exponent of its most significant bit
+        let (significand, unbiased_exponent) = if exponent >= 0 {
+            let mut v = digits;
+            v.mul_pow10(exponent as u32);
+            (v.to_u128_sticky(MANT_BITS + 4), v.bits() as i32 - 1)
+        } else {
+            // scale numerator or denominator by 2^shift, so the quotient has
+            // MANT_BITS + 5 or MANT_BITS + 6 bits; the remainder only
+            // contributes to the sticky bit
+            let denominator = BigUint::pow10(-exponent as u32);
+            let shift = (denominator.bits() + MANT_BITS + 5) as i32 - digits.bits() as i32;
+            let (quotient, remainder) = if shift >= 0 {
+                digits.shl(shift as u32).div_rem(&denominator)
+            } else {
+                digits.div_rem(&denominator.shl(-shift as u32))
+            };
+            let quotient_bits = 128 - quotient.leading_zeros();
+            let significand = BigUint::from_u128(quotient).to_u128_sticky(MANT_BITS + 4)
+                | !remainder.is_zero() as u128;
+            (significand, quotient_bits as i32 - 1 - shift)
+        };
+
+        Self::round_and_pack(
+            sign,
+            unbiased_exponent + Self::BIAS,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // rounds digits * 2^exponent
+    pub(crate) const fn from_binary(
+        sign: u32,
+        digits: &BigUint,
+        exponent: i64,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        if digits.is_zero() {
+            return (Self::zero(sign), ExceptionFlags::NONE);
+        }
+
+        // as in from_decimal, exponents far out of range are clamped
+        let unbiased_exponent = digits.bits() as i64 - 1 + exponent;
+        let (min, max) = (
+            -(Self::BIAS as i64) - MANT_BITS as i64 - 2,
+            Self::BIAS as i64 + 2,
+        );
+        let unbiased_exponent = if unbiased_exponent < min {
+            min
+        } else if unbiased_exponent > max {
+            max
+        } else {
+            unbiased_exponent
+        };
+        Self::round_and_pack(
+            sign,
+            unbiased_exponent as i32 + Self::BIAS,
+            digits.to_u128_sticky(MANT_BITS + 4),
+            rounding_mode,
+            tininess_detection,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SoftFloat128, SoftFloat16, SoftFloat64};
+
+    fn parse_bits(s: &str) -> u16 {
+        SoftFloat16::to_bits(s.parse::<SoftFloat16>().unwrap())
+    }
+
+    #[test]
+    fn test_from_str() {
+        for (s, expected) in [
+            ("1", 0x3c00),
+            ("+1.5", 0x3e00),
+            ("-2", 0xc000),
+            (".5", 0x3800),
+            ("1.", 0x3c00),
+            ("0", 0x0000),
+            ("-0.0", 0x8000),
+            ("0e999999999999999999999", 0x0000),
+            ("1e3", 0x63d0),
+            ("1E-3", 0x1419),
+            ("6.5504e4", 0x7bff),
+            ("65519.99", 0x7bff),
+            ("65520", 0x7c00),
+            ("1e999999999999999999999", 0x7c00),
+            ("-1e-999999999999999999999", 0x8000),
+            ("6.103515625e-5", 0x0400),
+            ("5.960464477539063e-8", 0x0001),
+            ("inf", 0x7c00),
+            ("-Infinity", 0xfc00),
+            ("NaN", 0x7e00),
+            ("-nan", 0xfe00),
+        ] {
+            assert_eq!(parse_bits(s), expected, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid() {
+        assert_eq!("".parse::<SoftFloat16>(), Err(ParseSoftFloatError::Empty));
+        for s in [
+            "-", ".", "e5", "1e", "1e+", "1.2.3", "--1", " 1", "1 ", "abc", "0x", "0x.", "0xp1",
+            "0x1p", "0x1g", "0x1.2.3", "0x1p1.5", "0x-1", "x1", "in", "nan1", "1_0", "1e1_0",
+            "1p5", "1.5f32",
+        ] {
+            assert_eq!(
+                s.parse::<SoftFloat16>(),
+                Err(ParseSoftFloatError::Invalid),
+                "{}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_str_halfway() {
+        // half of the smallest denormal number is rounded to even, anything
+        // above it up, no matter how far down the difference is
+        assert_eq!(parse_bits("2.98023223876953125e-8"), 0x0000);
+        assert_eq!(
+            parse_bits("2.98023223876953125000000000000000001e-8"),
+            0x0001
+        );
+        assert_eq!(
+            parse_bits("2.98023223876953124999999999999999999e-8"),
+            0x0000
+        );
+
+        // halfway between 1 and its successor
+        let ones = "0".repeat(1000) + "1";
+        assert_eq!(parse_bits("1.00048828125"), 0x3c00);
+        assert_eq!(parse_bits(&format!("1.00048828125{}", ones)), 0x3c01);
+        assert_eq!(parse_bits("1.00146484375"), 0x3c02);
+        assert_eq!(parse_bits(&format!("1.0014648437499{}", ones)), 0x3c01);
+
+        // very long digit strings
+        let zeros = "0".repeat(10000);
+        assert_eq!(parse_bits(&format!("1{}e-10000", zeros)), 0x3c00);
+        assert_eq!(parse_bits(&format!("0.{}1e10001", zeros)), 0x3c00);
+        assert_eq!(parse_bits(&format!("1{}", zeros)), 0x7c00);
+        assert_eq!(parse_bits(&format!("0.{}1", zeros)), 0x0000);
+    }
+
+    #[test]
+    fn test_from_str_hex() {
+        for (s, expected) in [
+            ("0x1p+0", 0x3c00),
+            ("0X1P0", 0x3c00),
+            ("0x1.ffcp+15", 0x7bff),
+            ("-0x1.8p1", 0xc200),
+            ("0x.8", 0x3800),
+            ("0x10", 0x4c00),
+            ("0x1e", 0x4f80),
+            ("0x3ff.p-24", 0x03ff),
+            ("0x1p-14", 0x0400),
+            ("0x0.004p-14", 0x0001),
+            ("0x1p-24", 0x0001),
+            ("-0x0p+0", 0x8000),
+            ("0x0.0000p99999999999", 0x0000),
+            // halfway cases are rounded to even
+            ("0x1.ffep+15", 0x7c00),
+            ("0x1.ffdfffffffffffffffffffffffp+15", 0x7bff),
+            ("0x1.002p0", 0x3c00),
+            ("0x1.00200000000000000000000000000000001p0", 0x3c01),
+            ("0x1.006p0", 0x3c02),
+            ("0x1p-25", 0x0000),
+            ("0x1.000000000000000000000000000000000001p-25", 0x0001),
+            ("0x1p16", 0x7c00),
+            ("0x1p99999999999", 0x7c00),
+            ("-0x1p-99999999999", 0x8000),
+        ] {
+            assert_eq!(parse_bits(s), expected, "{}", s);
+        }
+
+        let (y, flags) = SoftFloat16::from_str_with_flags(
+            "0x1.00000000000000000000000000000000001p0",
+            RoundingMode::TowardPositive,
+            TininessDetection::default(),
+        )
+        .unwrap();
+        assert_eq!(SoftFloat16::to_bits(y), 0x3c01);
+        assert_eq!(flags, ExceptionFlags::INEXACT);
+
+        let y = "0x1.ffffffffffffffffffffffffffffp16383"
+            .parse::<SoftFloat128>()
+            .unwrap();
+        assert_eq!(SoftFloat128::to_bits(y), 0x7ffeffffffffffffffffffffffffffff);
+    }
+
+    #[test]
+    fn test_from_str_with_flags() {
+        let td = TininessDetection::default();
+        for (s, rounding_mode, expected, expected_flags) in [
+            (
+                "1.5",
+                RoundingMode::TowardZero,
+                0x3e00,
+                ExceptionFlags::NONE,
+            ),
+            (
+                "0.1",
+                RoundingMode::NearestTiesToEven,
+                0x2e66,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                "0.1",
+                RoundingMode::TowardPositive,
+                0x2e67,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                "-0.1",
+                RoundingMode::TowardPositive,
+                0xae66,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                "1e-30",
+                RoundingMode::TowardPositive,
+                0x0001,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                "-1e-999999",
+                RoundingMode::TowardNegative,
+                0x8001,
+                ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                "1e5",
+                RoundingMode::TowardZero,
+                0x7bff,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                "1e999999",
+                RoundingMode::NearestTiesToEven,
+                0x7c00,
+                ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT,
+            ),
+            (
+                "inf",
+                RoundingMode::TowardZero,
+                0x7c00,
+                ExceptionFlags::NONE,
+            ),
+        ] {
+            let (y, flags) = SoftFloat16::from_str_with_flags(s, rounding_mode, td).unwrap();
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{}", s);
+            assert_eq!(flags, expected_flags, "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_from_str_binary128() {
+        for (s, expected) in [
+            ("1", 0x3fff << 112),
+            ("0.1", 0x3ffb999999999999999999999999999a),
+            (
+                "1.18973149535723176508575932662800702e4932",
+                0x7ffeffffffffffffffffffffffffffff,
+            ),
+            ("1.2e4932", 0x7fff << 112),
+            ("6.475175119438025110924438958227646552e-4966", 0x1),
+            ("-3.23e-4966", 0x8000 << 112),
+        ] {
+            let y = s.parse::<SoftFloat128>().unwrap();
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{}", s);
+        }
+    }
+
+    // the exact decimal expansions of the f64 just below and above the
+    // midpoints between neighboring SoftFloat16 have far more significant
+    // digits than are kept, yet round like the f64 themselves
+    fn check_truncated_midpoints(step: usize) {
+        for i in (0..0x7bffu16).step_by(step) {
+            let v0 = f64::from(f32::from(SoftFloat16::from_bits(i)));
+            let v1 = f64::from(f32::from(SoftFloat16::from_bits(i + 1)));
+            let midpoint = (v0 + v1) / 2.0;
+            for v in [midpoint.next_down(), midpoint.next_up()] {
+                let s = SoftFloat64::to_exact_decimal(SoftFloat64::from(v));
+                let expected = SoftFloat16::to_bits(SoftFloat16::from(v));
+                assert_eq!(parse_bits(&s), expected, "{}", s);
+                assert_eq!(parse_bits(&format!("-{}", s)), expected | 0x8000, "{}", s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_str_truncated() {
+        check_truncated_midpoints(97);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_from_str_truncated() {
+        check_truncated_midpoints(1);
+    }
+
+    // parses the shortest representation of f32, which lies well within the
+    // rounding interval of each number, and the exact midpoints between
+    // neighboring numbers, which are rounded to even unless followed by
+    // further nonzero digits
+    fn check_all_from_str<const EXP_BITS: u32, const MANT_BITS: u32>(n: u32) {
+        let from_bits = |i: u32| SoftFloat::<u32, EXP_BITS, MANT_BITS>::from_bits(i);
+        let sign_bit = 1 << (EXP_BITS + MANT_BITS);
+        let max = (((1 << EXP_BITS) - 1) << MANT_BITS) - 1;
+        for i in 0..n {
+            let x = from_bits(i);
+            let y = format!("{}", f32::from(x))
+                .parse::<SoftFloat<u32, EXP_BITS, MANT_BITS>>()
+                .unwrap();
+            if SoftFloat::is_nan(x) {
+                assert!(SoftFloat::is_nan(y), "{}", i);
+                continue;
+            }
+            assert_eq!(SoftFloat::to_bits(y), i, "{}", i);
+
+            if i & !sign_bit >= max {
+                continue;
+            }
+            let mid = (f64::from(x) + f64::from(from_bits(i + 1))) / 2.0;
+            let mid = format!("{:.60e}", mid);
+            let even = if i & 1 == 0 { i } else { i + 1 };
+            let (mantissa, exponent) = mid.split_once('e').unwrap();
+            let above = format!("{}1e{}", mantissa, exponent);
+            for (s, expected) in [(mid, even), (above, i + 1)] {
+                let y = s.parse::<SoftFloat<u32, EXP_BITS, MANT_BITS>>().unwrap();
+                assert_eq!(SoftFloat::to_bits(y), expected, "{}", s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_from_str_binary8() {
+        check_all_from_str::<2, 5>(1 << 8);
+        check_all_from_str::<3, 4>(1 << 8);
+        check_all_from_str::<4, 3>(1 << 8);
+        check_all_from_str::<5, 2>(1 << 8);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_from_str() {
+        check_all_from_str::<5, 10>(1 << 16);
+    }
+}
@@ -0,0 +1,900 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::format::{shift_right_sticky, Format};
+use crate::u256::U256;
+use crate::{
+    ExceptionFlags, RoundingMode, SoftFloat, SoftFloat16, SoftFloat32, Sqrt, Storage,
+    TininessDetection,
+};
+
+// 16-bit posit with ES exponent bits, see the Posit Standard (2022); after
+// the sign, a run of identical bits (the regime) terminated by the opposite
+// bit gives k, followed by up to ES exponent bits e and the fraction, and the
+// value is (1.fraction) * 2^(k * 2^ES + e); negative numbers are the two's
+// complement of their magnitude, so posits are ordered like i16
+//
+// there is a single zero and, instead of infinities and NANs, a single NaR
+// (not a real, 0x8000); all operations round to nearest, ties to even on
+// the encoding, i.e., the midpoint between two neighbors is the posit with
+// one more bit in between, which differs from the arithmetic mean where
+// exponent bits are cut off; results never round to zero or NaR, but
+// saturate at the smallest and largest positive number
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Posit16Es<const ES: u32>(u16);
+
+// posit<16, 1>, the common choice before the standard fixed ES to 2
+pub type Posit16 = Posit16Es<1>;
+
+const NAR: u16 = 0x8000;
+const MAXPOS: u16 = 0x7FFF;
+const MINPOS: u16 = 0x0001;
+
+impl<const ES: u32> Posit16Es<ES> {
+    // more exponent bits do not fit into the quire
+    const IS_VALID: () = assert!(ES <= 2, "unsupported number of exponent bits");
+
+    pub fn from_bits(v: u16) -> Self {
+        let () = Self::IS_VALID;
+        Self(v)
+    }
+
+    pub fn to_bits(v: Self) -> u16 {
+        v.0
+    }
+
+    pub fn is_nar(v: Self) -> bool {
+        v.0 == NAR
+    }
+
+    pub fn is_zero(v: Self) -> bool {
+        v.0 == 0
+    }
+
+    // splits a posit other than zero and NaR into sign, scale and
+    // significand, which looks like 1.x{16}, i.e., the value is
+    // significand * 2^(scale - 16)
+    fn decode(v: Self) -> (u32, i32, u32) {
+        let sign = (v.0 >> 15) as u32;
+        let magnitude = if sign == 1 { v.0.wrapping_neg() } else { v.0 };
+
+        // regime, starting right after the sign bit
+        let body = magnitude << 1;
+        let (run, k) = if body >> 15 == 1 {
+            let run = body.leading_ones().min(15);
+            (run, run as i32 - 1)
+        } else {
+            let run = body.leading_zeros().min(15);
+            (run, -(run as i32))
+        };
+
+        // exponent and fraction follow the terminating bit; cut off exponent
+        // bits are zero
+        let rest = ((body as u32) << (run + 1)) & 0xFFFF;
+        let exponent = rest >> (16 - ES);
+        let fraction = (rest << ES) & 0xFFFF;
+
+        (sign, (k << ES) + exponent as i32, 1 << 16 | fraction)
+    }
+
+    // rounds the nonzero number (-1)^sign * significand * 2^exponent
+    fn round(sign: u32, exponent: i32, significand: u128) -> Self {
+        assert!(significand != 0);
+        let shift = significand.leading_zeros();
+        Self::encode(sign, exponent + 127 - shift as i32, significand << shift)
+    }
+
+    // encodes (-1)^sign * 1.x{127} * 2^scale for a significand with its
+    // leading one at bit 127, rounding to nearest, ties to even on the
+    // encoding
+    fn encode(sign: u32, scale: i32, significand: u128) -> Self {
+        let k = scale >> ES;
+        let exponent = (scale & ((1 << ES) - 1)) as u128;
+
+        // the regime of the largest (smallest) positive number takes up all
+        // 15 bits after the sign, anything larger (smaller) saturates
+        let magnitude = if k >= 14 {
+            MAXPOS
+        } else if k < -14 {
+            MINPOS
+        } else {
+            let (regime, regime_length) = if k >= 0 {
+                (((1 << (k + 1)) - 1) << 1, k as u32 + 2)
+            } else {
+                (1, (1 - k) as u32)
+            };
+
+            // cut off the leading one, keep 64 fraction bits including a
+            // sticky bit
+            let fraction = shift_right_sticky(significand << 1, 64);
+            let bits = regime << (ES + 64) | exponent << 64 | fraction;
+
+            // keep the 15 bits after the sign bit; a regime of at most 15
+            // bits means that the result is neither zero nor NaR, even when
+            // rounding up
+            let shift = regime_length + ES + 64 - 15;
+            let kept = bits >> shift;
+            let guard = bits >> (shift - 1) & 1 != 0;
+            let sticky = bits & ((1 << (shift - 1)) - 1) != 0;
+            let round_up = guard && (sticky || kept & 1 == 1);
+            (kept + round_up as u128) as u16
+        };
+
+        if sign == 1 {
+            Self::from_bits(magnitude.wrapping_neg())
+        } else {
+            Self::from_bits(magnitude)
+        }
+    }
+
+    // infinities and NANs become NaR; the conversion is correctly rounded,
+    // which includes saturation
+    pub fn from_soft_float<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        value: SoftFloat<T, EXP_BITS, MANT_BITS>,
+    ) -> Self {
+        let (sign, exponent, significand) =
+            Format::<EXP_BITS, MANT_BITS>::unpack(SoftFloat::raw(value));
+
+        if exponent == Format::<EXP_BITS, MANT_BITS>::EXPONENT_MASK {
+            return Self::from_bits(NAR);
+        } else if exponent == 0 && significand == 0 {
+            return Self::from_bits(0);
+        }
+
+        let (exponent, significand) =
+            Format::<EXP_BITS, MANT_BITS>::normalize(exponent, significand);
+        Self::round(
+            sign,
+            exponent - Format::<EXP_BITS, MANT_BITS>::BIAS - MANT_BITS as i32,
+            significand,
+        )
+    }
+
+    // NaR becomes the default NAN, without raising the invalid exception
+    pub fn to_soft_float_with_flags<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32>(
+        v: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (SoftFloat<T, EXP_BITS, MANT_BITS>, ExceptionFlags) {
+        if Self::is_nar(v) {
+            return (
+                SoftFloat::from_raw(Format::<EXP_BITS, MANT_BITS>::NAN),
+                ExceptionFlags::NONE,
+            );
+        } else if Self::is_zero(v) {
+            return (
+                SoftFloat::from_raw(Format::<EXP_BITS, MANT_BITS>::zero(0)),
+                ExceptionFlags::NONE,
+            );
+        }
+
+        // adjust significand to look like 1.x{MANT_BITS} with guard, round,
+        // sticky bits
+        let (sign, scale, significand) = Self::decode(v);
+        let significand = if MANT_BITS + 3 >= 16 {
+            (significand as u128) << (MANT_BITS + 3 - 16)
+        } else {
+            shift_right_sticky(significand as u128, (16 - MANT_BITS - 3) as i32)
+        };
+
+        SoftFloat::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::round_and_pack(
+            sign,
+            scale + Format::<EXP_BITS, MANT_BITS>::BIAS,
+            significand,
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+}
+
+impl<const ES: u32> Add for Posit16Es<ES> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        if Self::is_nar(self) || Self::is_nar(other) {
+            return Self::from_bits(NAR);
+        } else if Self::is_zero(self) {
+            return other;
+        } else if Self::is_zero(other) {
+            return self;
+        }
+
+        // order operands by scale, align significands (keeping a sticky bit)
+        let (sign0, scale0, significand0) = Self::decode(self);
+        let (sign1, scale1, significand1) = Self::decode(other);
+        let ((sign0, scale0, significand0), (sign1, scale1, significand1)) = if scale0 >= scale1 {
+            ((sign0, scale0, significand0), (sign1, scale1, significand1))
+        } else {
+            ((sign1, scale1, significand1), (sign0, scale0, significand0))
+        };
+        let significand0 = (significand0 as u128) << 96;
+        let significand1 = shift_right_sticky((significand1 as u128) << 96, scale0 - scale1);
+
+        let (sign, significand) = if sign0 == sign1 {
+            (sign0, significand0 + significand1)
+        } else if significand0 >= significand1 {
+            (sign0, significand0 - significand1)
+        } else {
+            (sign1, significand1 - significand0)
+        };
+        if significand == 0 {
+            return Self::from_bits(0);
+        }
+        Self::round(sign, scale0 - 16 - 96, significand)
+    }
+}
+
+impl<const ES: u32> Sub for Posit16Es<ES> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl<const ES: u32> Mul for Posit16Es<ES> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        if Self::is_nar(self) || Self::is_nar(other) {
+            return Self::from_bits(NAR);
+        } else if Self::is_zero(self) || Self::is_zero(other) {
+            return Self::from_bits(0);
+        }
+
+        // the product is exact
+        let (sign0, scale0, significand0) = Self::decode(self);
+        let (sign1, scale1, significand1) = Self::decode(other);
+        Self::round(
+            sign0 ^ sign1,
+            scale0 + scale1 - 32,
+            significand0 as u128 * significand1 as u128,
+        )
+    }
+}
+
+impl<const ES: u32> Div for Posit16Es<ES> {
+    type Output = Self;
+
+    // division by zero is NaR
+    fn div(self, other: Self) -> Self {
+        if Self::is_nar(self) || Self::is_nar(other) || Self::is_zero(other) {
+            return Self::from_bits(NAR);
+        } else if Self::is_zero(self) {
+            return Self::from_bits(0);
+        }
+
+        // the quotient has at least 64 bits, the remainder goes into a
+        // sticky bit
+        let (sign0, scale0, significand0) = Self::decode(self);
+        let (sign1, scale1, significand1) = Self::decode(other);
+        let dividend = (significand0 as u128) << 80;
+        let quotient = dividend / significand1 as u128;
+        let sticky = !dividend.is_multiple_of(significand1 as u128) as u128;
+        Self::round(
+            sign0 ^ sign1,
+            scale0 - scale1 - 80 - 1,
+            quotient << 1 | sticky,
+        )
+    }
+}
+
+impl<const ES: u32> Neg for Posit16Es<ES> {
+    type Output = Self;
+
+    // two's complement; zero and NaR are their own negation
+    fn neg(self) -> Self {
+        Self::from_bits(self.0.wrapping_neg())
+    }
+}
+
+// unlike IEEE 754 comparisons, NaR equals itself and is less than any real
+// number
+impl<const ES: u32> PartialOrd for Posit16Es<ES> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const ES: u32> Ord for Posit16Es<ES> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0 as i16).cmp(&(other.0 as i16))
+    }
+}
+
+impl<const ES: u32> Sqrt for Posit16Es<ES> {
+    // the square root of a negative number is NaR
+    fn sqrt(v: Self) -> Self {
+        if Self::is_nar(v) || v.0 >> 15 == 1 {
+            return Self::from_bits(NAR);
+        } else if Self::is_zero(v) {
+            return v;
+        }
+
+        // make the exponent even and the significand as wide as possible,
+        // the remainder goes into a sticky bit
+        let (_, scale, significand) = Self::decode(v);
+        let (exponent, significand) = if (scale - 16) % 2 != 0 {
+            (scale - 16 - 1, (significand as u128) << 1)
+        } else {
+            (scale - 16, significand as u128)
+        };
+        let radicand = significand << 108;
+        let root = radicand.isqrt();
+        let sticky = (root * root != radicand) as u128;
+        Self::round(0, (exponent - 108) / 2 - 1, root << 1 | sticky)
+    }
+}
+
+impl<const ES: u32> From<SoftFloat16> for Posit16Es<ES> {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float(value)
+    }
+}
+
+impl<const ES: u32> From<Posit16Es<ES>> for SoftFloat16 {
+    fn from(value: Posit16Es<ES>) -> Self {
+        Posit16Es::to_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        )
+        .0
+    }
+}
+
+impl<const ES: u32> From<f32> for Posit16Es<ES> {
+    fn from(value: f32) -> Self {
+        Self::from_soft_float(SoftFloat32::from_bits(value.to_bits()))
+    }
+}
+
+// conversion is always exact
+impl<const ES: u32> From<Posit16Es<ES>> for f32 {
+    fn from(value: Posit16Es<ES>) -> Self {
+        let (v, _): (SoftFloat32, _) = Posit16Es::to_soft_float_with_flags(
+            value,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::default(),
+        );
+        f32::from_bits(SoftFloat32::to_bits(v))
+    }
+}
+
+// quire of the Posit Standard, a fixed-point accumulator wide enough to hold
+// sums of products of posits exactly, so dot products are rounded only once;
+// it spans the squares of the smallest and largest positive posit, 28 * 2^ES
+// bits below and above the binary point each, and leaves more than 30 carry
+// bits of its 256 bits for products of the largest positive posit
+//
+// the value is kept as sign and magnitude, a NaR operand turns the whole
+// quire into NaR
+#[derive(Clone, Copy, Debug)]
+pub struct Quire16Es<const ES: u32> {
+    nar: bool,
+    negative: bool,
+    magnitude: U256,
+}
+
+pub type Quire16 = Quire16Es<1>;
+
+impl<const ES: u32> Default for Quire16Es<ES> {
+    fn default() -> Self {
+        Self {
+            nar: false,
+            negative: false,
+            magnitude: U256::ZERO,
+        }
+    }
+}
+
+impl<const ES: u32> Quire16Es<ES> {
+    const FRACTION_BITS: i32 = 28 << ES;
+
+    pub fn is_nar(q: Self) -> bool {
+        q.nar
+    }
+
+    pub fn add_posit(q: Self, v: Posit16Es<ES>) -> Self {
+        if Posit16Es::is_nar(v) {
+            return Self::nar();
+        } else if Posit16Es::is_zero(v) {
+            return q;
+        }
+        let (sign, scale, significand) = Posit16Es::decode(v);
+        Self::accumulate(q, sign == 1, significand as u128, scale - 16)
+    }
+
+    // adds v0 * v1 without rounding
+    pub fn add_product(q: Self, v0: Posit16Es<ES>, v1: Posit16Es<ES>) -> Self {
+        Self::add_product_with_sign(q, v0, v1, false)
+    }
+
+    // subtracts v0 * v1 without rounding
+    pub fn sub_product(q: Self, v0: Posit16Es<ES>, v1: Posit16Es<ES>) -> Self {
+        Self::add_product_with_sign(q, v0, v1, true)
+    }
+
+    fn add_product_with_sign(q: Self, v0: Posit16Es<ES>, v1: Posit16Es<ES>, negate: bool) -> Self {
+        if Posit16Es::is_nar(v0) || Posit16Es::is_nar(v1) {
+            return Self::nar();
+        } else if Posit16Es::is_zero(v0) || Posit16Es::is_zero(v1) {
+            return q;
+        }
+        let (sign0, scale0, significand0) = Posit16Es::decode(v0);
+        let (sign1, scale1, significand1) = Posit16Es::decode(v1);
+        Self::accumulate(
+            q,
+            (sign0 ^ sign1 == 1) ^ negate,
+            significand0 as u128 * significand1 as u128,
+            scale0 + scale1 - 32,
+        )
+    }
+
+    // rounds the accumulated value once
+    pub fn to_posit(q: Self) -> Posit16Es<ES> {
+        if q.nar {
+            return Posit16Es::from_bits(NAR);
+        } else if U256::is_zero(q.magnitude) {
+            return Posit16Es::from_bits(0);
+        }
+
+        // reduce to 128 bits, keeping a sticky bit
+        let width = 256 - U256::leading_zeros(q.magnitude);
+        let shift = width.saturating_sub(128);
+        let significand = U256::to_u128(U256::shift_right_sticky(q.magnitude, shift as i32));
+        Posit16Es::round(
+            q.negative as u32,
+            shift as i32 - Self::FRACTION_BITS,
+            significand,
+        )
+    }
+
+    fn nar() -> Self {
+        Self {
+            nar: true,
+            ..Self::default()
+        }
+    }
+
+    // adds (-1)^negative * significand * 2^exponent; bits below the least
+    // significant bit of the quire are zero for products of posits, since
+    // their precision decreases towards the smallest positive posit
+    fn accumulate(q: Self, negative: bool, significand: u128, exponent: i32) -> Self {
+        if q.nar {
+            return q;
+        }
+
+        let shift = exponent + Self::FRACTION_BITS;
+        let addend = if shift >= 0 {
+            U256::shift_left(U256::from_u128(significand), shift as u32)
+        } else {
+            assert!(significand.trailing_zeros() >= shift.unsigned_abs());
+            U256::from_u128(significand >> -shift)
+        };
+
+        let (negative, magnitude) = if q.negative == negative {
+            (negative, U256::sum(q.magnitude, addend))
+        } else if U256::compare(q.magnitude, addend).is_ge() {
+            (q.negative, U256::difference(q.magnitude, addend))
+        } else {
+            (negative, U256::difference(addend, q.magnitude))
+        };
+        Self {
+            nar: false,
+            negative: negative && !U256::is_zero(magnitude),
+            magnitude,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::XorShift;
+
+    // values of all posits with 17 bits and ES exponent bits, in units of
+    // 2^-(15 * 2^ES), their smallest positive number; the posits with 16
+    // bits are those with even index, the odd ones are the midpoints in
+    // between; decodes bit by bit, independently of Posit16Es::decode
+    fn reference_values<const ES: u32>() -> Vec<i128> {
+        (0..1 << 17)
+            .map(|v: u32| {
+                let sign = v >> 16;
+                let x = if sign == 1 {
+                    v.wrapping_neg() & 0x1FFFF
+                } else {
+                    v
+                };
+                if x == 0 || x == 0x10000 {
+                    // zero, NaR
+                    return 0;
+                }
+
+                let bit = |i: i32| if i >= 0 { (x >> i & 1) as i32 } else { 0 };
+                let mut i = 15;
+                let first = bit(i);
+                let mut run = 0;
+                while i >= 0 && bit(i) == first {
+                    run += 1;
+                    i -= 1;
+                }
+                i -= 1;
+                let k = if first == 1 { run - 1 } else { -run };
+
+                let mut exponent = 0;
+                for _ in 0..ES {
+                    exponent = exponent << 1 | bit(i);
+                    i -= 1;
+                }
+
+                let mut significand = 1_i128;
+                let mut fraction_bits = 0;
+                while i >= 0 {
+                    significand = significand << 1 | bit(i) as i128;
+                    fraction_bits += 1;
+                    i -= 1;
+                }
+
+                let shift = (k << ES) + exponent - fraction_bits + (15 << ES);
+                assert!(shift >= 0);
+                if sign == 1 {
+                    -(significand << shift)
+                } else {
+                    significand << shift
+                }
+            })
+            .collect()
+    }
+
+    // checks that y is the result of rounding some exact z to nearest, ties
+    // to even on the encoding, where cmp(m) compares z with m (in units of
+    // 2^-(15 * 2^ES)) and values are the reference values
+    fn check_rounding<const ES: u32>(
+        y: Posit16Es<ES>,
+        cmp: impl Fn(i128) -> Ordering,
+        values: &[i128],
+        args: (u32, u32),
+    ) {
+        let p = Posit16Es::to_bits(y) as i16 as i32;
+        assert!(!Posit16Es::is_nar(y), "{:04x?}", args);
+        if p == 0 {
+            assert_eq!(cmp(0), Ordering::Equal, "{:04x?}", args);
+            return;
+        }
+
+        let midpoint = |q: i32| values[(q & 0x1FFFF) as usize];
+        let is_even = p & 1 == 0;
+        let above_lower = match p {
+            1 => cmp(0) == Ordering::Greater,
+            -0x7FFF => true,
+            _ => match cmp(midpoint(2 * p - 1)) {
+                Ordering::Greater => true,
+                Ordering::Equal => is_even,
+                Ordering::Less => false,
+            },
+        };
+        let below_upper = match p {
+            -1 => cmp(0) == Ordering::Less,
+            0x7FFF => true,
+            _ => match cmp(midpoint(2 * p + 1)) {
+                Ordering::Less => true,
+                Ordering::Equal => is_even,
+                Ordering::Greater => false,
+            },
+        };
+        assert!(above_lower && below_upper, "{:04x?}", args);
+    }
+
+    // checks op for all pairs of every step-th posit; nar tells whether the
+    // result is NaR for real operands, and cmp(x0, x1, m) compares the exact
+    // result for the operands x0 and x1 with m, all in units of
+    // 2^-(15 * 2^ES)
+    fn check_all_binary<const ES: u32>(
+        op: impl Fn(Posit16Es<ES>, Posit16Es<ES>) -> Posit16Es<ES>,
+        nar: impl Fn(i128, i128) -> bool,
+        cmp: impl Fn(i128, i128, i128) -> Ordering,
+        step: usize,
+    ) {
+        let values = reference_values::<ES>();
+        for i in (0..=u16::MAX).step_by(step) {
+            for j in (0..=u16::MAX).step_by(step) {
+                let (x0, x1) = (Posit16Es::from_bits(i), Posit16Es::from_bits(j));
+                let y = op(x0, x1);
+                let (v0, v1) = (values[2 * i as usize], values[2 * j as usize]);
+                if Posit16Es::is_nar(x0) || Posit16Es::is_nar(x1) || nar(v0, v1) {
+                    assert!(Posit16Es::is_nar(y), "{:04x?}", (i, j));
+                } else {
+                    check_rounding(y, |m| cmp(v0, v1, m), &values, (i as u32, j as u32));
+                }
+            }
+        }
+    }
+
+    fn check_all_add<const ES: u32>(step: usize) {
+        check_all_binary::<ES>(
+            |x0, x1| x0 + x1,
+            |_, _| false,
+            |v0, v1, m| (v0 + v1).cmp(&m),
+            step,
+        );
+    }
+
+    fn check_all_sub<const ES: u32>(step: usize) {
+        check_all_binary::<ES>(
+            |x0, x1| x0 - x1,
+            |_, _| false,
+            |v0, v1, m| (v0 - v1).cmp(&m),
+            step,
+        );
+    }
+
+    // products are in units of 2^-(30 * 2^ES), which overflows i128 for
+    // ES = 2
+    fn check_all_mul<const ES: u32>(step: usize) {
+        check_all_binary::<ES>(
+            |x0, x1| x0 * x1,
+            |_, _| false,
+            |v0, v1, m| (v0 * v1).cmp(&(m << (15 << ES))),
+            step,
+        );
+    }
+
+    // v0 / v1 compared with m is v0 compared with m * v1 (reversed if v1 is
+    // negative)
+    fn check_all_div<const ES: u32>(step: usize) {
+        check_all_binary::<ES>(
+            |x0, x1| x0 / x1,
+            |_, v1| v1 == 0,
+            |v0, v1, m| {
+                let ordering = (v0 << (15 << ES)).cmp(&(m * v1));
+                if v1 < 0 {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            },
+            step,
+        );
+    }
+
+    fn check_all_sqrt<const ES: u32>() {
+        let values = reference_values::<ES>();
+        for i in 0..=u16::MAX {
+            let x = Posit16Es::<ES>::from_bits(i);
+            let y = Posit16Es::sqrt(x);
+            let v = values[2 * i as usize];
+            if Posit16Es::is_nar(x) || v < 0 {
+                assert!(Posit16Es::is_nar(y), "{:04x}", i);
+            } else {
+                let cmp = |m: i128| {
+                    if m < 0 {
+                        Ordering::Greater
+                    } else {
+                        (v << (15 << ES)).cmp(&(m * m))
+                    }
+                };
+                check_rounding(y, cmp, &values, (i as u32, 0));
+            }
+        }
+    }
+
+    // conversions to binary16 and f32 compare with the exact conversion to
+    // f32, conversions from binary16 and f32 with z in units of
+    // 2^-(15 * 2^ES)
+    fn check_all_conversions<const ES: u32>(step_f32: usize) {
+        let values = reference_values::<ES>();
+        let scale = 2.0_f64.powi(-(15 << ES));
+
+        for i in 0..=u16::MAX {
+            let x = Posit16Es::<ES>::from_bits(i);
+            let y = f32::from(x);
+            if Posit16Es::is_nar(x) {
+                assert!(y.is_nan());
+            } else {
+                assert_eq!(y as f64, values[2 * i as usize] as f64 * scale, "{:04x}", i);
+            }
+
+            for rounding_mode in [
+                RoundingMode::NearestTiesToEven,
+                RoundingMode::TowardZero,
+                RoundingMode::TowardNegative,
+            ] {
+                let (y16, flags) = Posit16Es::to_soft_float_with_flags(
+                    x,
+                    rounding_mode,
+                    TininessDetection::BeforeRounding,
+                );
+                let (y16_f, flags_f) = SoftFloat16::from_f32_with_flags(
+                    y,
+                    rounding_mode,
+                    TininessDetection::BeforeRounding,
+                );
+                assert_eq!(
+                    SoftFloat16::to_bits(y16),
+                    SoftFloat16::to_bits(y16_f),
+                    "{:04x}",
+                    i
+                );
+                if !Posit16Es::is_nar(x) {
+                    assert_eq!(flags, flags_f, "{:04x}", i);
+                }
+            }
+        }
+
+        let check_from = |y: Posit16Es<ES>, z: f32, i: u32| {
+            if z.is_nan() || z.is_infinite() {
+                assert!(Posit16Es::is_nar(y), "{:08x}", i);
+            } else {
+                let cmp = |m: i128| (z as f64).partial_cmp(&(m as f64 * scale)).unwrap();
+                check_rounding(y, cmp, &values, (i, 0));
+            }
+        };
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            check_from(Posit16Es::from(x), f32::from(x), i as u32);
+        }
+        for i in (0..=u32::MAX).step_by(step_f32) {
+            let z = f32::from_bits(i);
+            check_from(Posit16Es::from(z), z, i);
+        }
+    }
+
+    #[test]
+    fn test_posit16() {
+        let one = Posit16::from_bits(0x4000);
+        let nar = Posit16::from_bits(0x8000);
+        assert_eq!(f32::from(one), 1.0);
+        assert_eq!(f32::from(-one), -1.0);
+        assert_eq!(Posit16::to_bits(one + one), 0x5000);
+        assert_eq!(Posit16::to_bits(Posit16::from(3.0)), 0x5800);
+        assert_eq!(f32::from(Posit16::from_bits(0x7FFF)), 2.0_f32.powi(28));
+        assert_eq!(f32::from(Posit16::from_bits(0x0001)), 2.0_f32.powi(-28));
+        assert_eq!(
+            f32::from(Posit16Es::<2>::from_bits(0x7FFF)),
+            2.0_f32.powi(56)
+        );
+        assert_eq!(
+            f32::from(Posit16Es::<0>::from_bits(0x7FFF)),
+            2.0_f32.powi(14)
+        );
+
+        // saturation instead of overflow and underflow
+        assert_eq!(Posit16::to_bits(Posit16::from(f32::MAX)), 0x7FFF);
+        assert_eq!(Posit16::to_bits(Posit16::from(-f32::MAX)), 0x8001);
+        assert_eq!(Posit16::to_bits(Posit16::from(1e-30)), 0x0001);
+
+        // the midpoint between 2^26 and 2^28 is 2^27 (the encoding has no
+        // room for exponent bits there), so 2^27 ties to the even 2^26
+        assert_eq!(Posit16::to_bits(Posit16::from(2.0_f32.powi(27))), 0x7FFE);
+        assert_eq!(
+            Posit16::to_bits(Posit16::from(1.5 * 2.0_f32.powi(27))),
+            0x7FFF
+        );
+
+        // NaR
+        for x in [
+            nar + one,
+            one / Posit16::from_bits(0),
+            Posit16::sqrt(-one),
+            Posit16::from(f32::NAN),
+            Posit16::from(f32::INFINITY),
+        ] {
+            assert!(Posit16::is_nar(x));
+        }
+        assert!(f32::from(nar).is_nan());
+        assert!(-nar == nar);
+        assert!(nar < -one && -one < one);
+        assert!(Posit16::is_zero(one - one));
+    }
+
+    #[test]
+    fn test_quire() {
+        let maxpos = Posit16::from_bits(0x7FFF);
+        let minpos = Posit16::from_bits(0x0001);
+
+        // maxpos^2 + minpos^2 - maxpos^2 cancels exactly in the quire,
+        // leaving minpos^2, which rounds to minpos
+        let q = Quire16::add_product(Quire16::default(), maxpos, maxpos);
+        let q = Quire16::add_product(q, minpos, minpos);
+        let q = Quire16::sub_product(q, maxpos, maxpos);
+        assert_eq!(Posit16::to_bits(Quire16::to_posit(q)), 0x0001);
+        assert!(Posit16::is_zero(
+            maxpos * maxpos + minpos * minpos - maxpos * maxpos
+        ));
+        let q = Quire16::sub_product(q, minpos, minpos);
+        assert!(Posit16::is_zero(Quire16::to_posit(q)));
+
+        let q = Quire16::add_posit(q, Posit16::from(-3.0));
+        assert_eq!(f32::from(Quire16::to_posit(q)), -3.0);
+        let q = Quire16::add_product(q, Posit16::from_bits(0x8000), minpos);
+        assert!(Quire16::is_nar(q));
+        assert!(Posit16::is_nar(Quire16::to_posit(Quire16::add_posit(
+            q, minpos
+        ))));
+    }
+
+    // dot products of pseudo-random posits are rounded once; the exact
+    // result of up to 64 products fits into i128 for ES < 2
+    fn check_dot_products<const ES: u32>() {
+        let values = reference_values::<ES>();
+        let mut rng = XorShift::new();
+        let mut next = move || rng.next_u16();
+        for n in 0..2000 {
+            let mut q = Quire16Es::<ES>::default();
+            let mut z = 0_i128;
+            for _ in 0..n % 64 {
+                let (i, j) = (next() | 1, next() | 1);
+                let (x0, x1) = (Posit16Es::from_bits(i), Posit16Es::from_bits(j));
+                if Posit16Es::is_nar(x0) || Posit16Es::is_nar(x1) {
+                    continue;
+                }
+                q = Quire16Es::add_product(q, x0, x1);
+                z += values[2 * i as usize] * values[2 * j as usize];
+            }
+            let cmp = |m: i128| z.cmp(&(m << (15 << ES)));
+            check_rounding(Quire16Es::to_posit(q), cmp, &values, (n, 0));
+        }
+    }
+
+    #[test]
+    fn test_dot_products() {
+        check_dot_products::<0>();
+        check_dot_products::<1>();
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        check_all_add::<0>(149);
+        check_all_add::<1>(149);
+        check_all_add::<2>(149);
+        check_all_sub::<1>(149);
+        check_all_mul::<0>(149);
+        check_all_mul::<1>(149);
+        check_all_div::<0>(149);
+        check_all_div::<1>(149);
+    }
+
+    #[test]
+    fn test_all_sqrt() {
+        check_all_sqrt::<0>();
+        check_all_sqrt::<1>();
+    }
+
+    #[test]
+    fn test_all_conversions() {
+        check_all_conversions::<0>(65521);
+        check_all_conversions::<1>(65521);
+        check_all_conversions::<2>(65521);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_add() {
+        check_all_add::<1>(1);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_sub() {
+        check_all_sub::<1>(1);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_mul() {
+        check_all_mul::<1>(1);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_div() {
+        check_all_div::<1>(1);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_from_f32() {
+        check_all_conversions::<1>(1);
+    }
+}
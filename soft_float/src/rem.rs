@@ -0,0 +1,243 @@
+use std::ops::Rem;
+
+use crate::format::Format;
+use crate::{ExceptionFlags, SoftFloat, Storage};
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Rem
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    type Output = Self;
+
+    // IEEE 754 remainder, i.e., x - y * n where n is x / y rounded to the
+    // nearest integer (ties to even); note that this differs from the
+    // truncating `%` for primitive floats
+    fn rem(self, other: Self) -> Self {
+        Self::rem_with_flags(self, other).0
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // the remainder is always exact, so only the invalid exception can be
+    // raised
+    pub fn rem_with_flags(v0: Self, v1: Self) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::rem(
+            Self::raw(v0),
+            Self::raw(v1),
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) fn rem(v0: u128, v1: u128) -> (u128, ExceptionFlags) {
+        let (sign0, exponent0, significand0) = Self::unpack(v0);
+        let (_, exponent1, significand1) = Self::unpack(v1);
+
+        if (exponent0 == Self::EXPONENT_MASK && significand0 != 0)
+            || (exponent1 == Self::EXPONENT_MASK && significand1 != 0)
+        {
+            // NAN % _ or _ % NAN
+            return Self::propagate_nan(&[v0, v1]);
+        } else if exponent0 == Self::EXPONENT_MASK {
+            // oo % _
+            return (Self::NAN, ExceptionFlags::INVALID);
+        } else if exponent1 == 0 && significand1 == 0 {
+            // _ % 0
+            return (Self::NAN, ExceptionFlags::INVALID);
+        } else if exponent1 == Self::EXPONENT_MASK {
+            // _ % oo
+            return (v0, ExceptionFlags::NONE);
+        } else if exponent0 == 0 && significand0 == 0 {
+            // 0 % _
+            return (v0, ExceptionFlags::NONE);
+        }
+
+        // handle denormals and implicit bit
+        let (exponent0, significand0) = Self::normalize(exponent0, significand0);
+        let (exponent1, significand1) = Self::normalize(exponent1, significand1);
+
+        if exponent0 < exponent1 - 1 {
+            // |x| < |y| / 2, so quotient is rounded to zero
+            return (v0, ExceptionFlags::NONE);
+        }
+
+        // the result is always exact and its magnitude is at most |y| / 2;
+        // flip is set if we need to subtract the remainder from |y|, which
+        // changes the sign of the result
+        let (flip, exponent, significand) = if exponent0 == exponent1 - 1 {
+            // |y| / 4 <= |x| < |y|, so quotient is rounded to zero or one
+            if significand0 <= significand1 {
+                return (v0, ExceptionFlags::NONE);
+            }
+            // result has one more fractional bit than |y|
+            (1, exponent1 - 1, (significand1 << 1) - significand0)
+        } else {
+            // generate integer quotient one bit at a time using long
+            // division; we only need to keep the least significant bit of
+            // the quotient to break ties
+            let mut x = significand0;
+            let y = significand1;
+            let mut q = 0;
+            for _ in 0..=(exponent0 - exponent1) {
+                q = (x >= y) as u32;
+                if q == 1 {
+                    x -= y;
+                }
+                x <<= 1;
+            }
+
+            // x holds twice the remainder; round quotient to nearest (ties
+            // to even) by comparing the remainder to |y| / 2
+            if x > y || (x == y && q == 1) {
+                (1, exponent1, y - (x >> 1))
+            } else {
+                (0, exponent1, x >> 1)
+            }
+        };
+
+        let sign = sign0 ^ flip;
+
+        if significand == 0 {
+            return (Self::zero(sign), ExceptionFlags::NONE);
+        }
+
+        // realign decimal point
+        let mut exponent = exponent;
+        let mut significand = significand;
+        while significand & Self::IMPLICIT_BIT == 0 {
+            significand <<= 1;
+            exponent -= 1;
+        }
+
+        let result = if exponent <= 0 {
+            // must convert to denormal number; since the result is exact we
+            // only ever shift out zeros
+            let shift = 1 - exponent;
+            assert!(significand & ((1 << shift) - 1) == 0);
+            Self::pack(sign, 0, significand >> shift)
+        } else {
+            // cut off implicit bit
+            Self::pack(sign, exponent, significand & Self::SIGNIFICAND_MASK)
+        };
+
+        (result, ExceptionFlags::NONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SoftFloat16;
+
+    // reference implementation based on the (exact) truncating remainder of
+    // f64
+    fn rem_f32(x: f32, y: f32) -> f32 {
+        let (x, y) = (x as f64, y as f64);
+        let r = x % y;
+        let n = ((x - r) / y).abs();
+        if 2.0 * r.abs() > y.abs() || (2.0 * r.abs() == y.abs() && n % 2.0 == 1.0) {
+            (r - y.abs().copysign(r)) as f32
+        } else {
+            r as f32
+        }
+    }
+
+    #[test]
+    fn test_rem() {
+        for (v0, v1) in [
+            (0x4500, 0x4000),
+            (0x4700, 0x4000),
+            (0x4600, 0x4000),
+            (0x4200, 0x4000),
+            (0x3c00, 0x4000),
+            (0x3c01, 0x4000),
+            (0xc500, 0x4000),
+            (0x7bff, 0x0001),
+            (0x7bff, 0x0003),
+            (0x7bff, 0x3c01),
+            (0x0001, 0x0002),
+            (0x0003, 0x0002),
+            (0x03ff, 0x0155),
+            (0x0400, 0x03ff),
+            (0x3555, 0x3c00),
+            (0x8000, 0x3c00),
+            (0x3c00, 0x7c00),
+            (0x7c00, 0x3c00),
+            (0x3c00, 0x0000),
+        ] {
+            let x0 = SoftFloat16::from_bits(v0);
+            let x1 = SoftFloat16::from_bits(v1);
+            let y = x0 % x1;
+            let y_f = SoftFloat16::from(rem_f32(f32::from(x0), f32::from(x1)));
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{:?}", (v0, v1));
+                assert!(SoftFloat16::is_nan(y_f), "{:?}", (v0, v1));
+            } else {
+                assert_eq!(
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                    "\n{:?}\n  {:016b}\n  {:016b}",
+                    (v0, v1),
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_rem() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                let x0 = SoftFloat16::from_bits(i);
+                let x1 = SoftFloat16::from_bits(j);
+                let y = x0 % x1;
+                let y_f = SoftFloat16::from(rem_f32(f32::from(x0), f32::from(x1)));
+                if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                    assert!(SoftFloat16::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat16::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat16::to_bits(y),
+                        SoftFloat16::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_all_rem<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0 % x1;
+                let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(rem_f32(
+                    f32::from(x0),
+                    f32::from(x1),
+                ));
+                if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                    assert!(SoftFloat::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat::to_bits(y),
+                        SoftFloat::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_rem_binary8() {
+        check_all_rem::<2, 5>();
+        check_all_rem::<3, 4>();
+        check_all_rem::<4, 3>();
+        check_all_rem::<5, 2>();
+    }
+}
@@ -1,90 +1,179 @@
-use crate::{
-    soft_float16::{NEG_ZERO, POS_ZERO},
-    SoftFloat16,
-};
+use crate::format::Format;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage};
 
 pub trait RoundTiesEven {
     fn round_ties_even(v: Self) -> Self;
 }
 
-impl RoundTiesEven for SoftFloat16 {
+pub trait Round {
+    fn round(v: Self) -> Self;
+}
+
+pub trait Floor {
+    fn floor(v: Self) -> Self;
+}
+
+pub trait Ceil {
+    fn ceil(v: Self) -> Self;
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> RoundTiesEven
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn round_ties_even(v: Self) -> Self {
-        let (sign, exponent, significand) =
-            (Self::sign(v), Self::exponent(v), Self::significand(v));
+        Self::round_ties_even_with_flags(v).0
+    }
+}
 
-        let unbiased_exponent = (exponent as i16) - 15;
+// rounds half-way cases away from zero, like f32::round
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Round
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn round(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::NearestTiesToAway).0
+    }
+}
 
-        if unbiased_exponent < -1 {
-            if sign == 0 {
-                POS_ZERO
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Floor
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn floor(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardNegative).0
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Ceil
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn ceil(v: Self) -> Self {
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardPositive).0
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn round_ties_even_with_flags(v: Self) -> (Self, ExceptionFlags) {
+        Self::round_to_integral_with_flags(v, RoundingMode::NearestTiesToEven)
+    }
+
+    // like roundToIntegral in IEEE 754, sec. 5.9, inexact results do not
+    // raise the inexact exception
+    pub fn round_to_integral_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::round_to_integral(
+            Self::raw(v),
+            rounding_mode,
+        ))
+    }
+
+    // like roundToIntegralExact in IEEE 754, sec. 5.9, inexact results raise
+    // the inexact exception
+    pub fn round_to_integral_exact_with_flags(
+        v: Self,
+        rounding_mode: RoundingMode,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::round_to_integral_exact(
+            Self::raw(v),
+            rounding_mode,
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    // rounds to an integral value in the given rounding mode; inexact results
+    // do not raise the inexact exception
+    pub(crate) fn round_to_integral(
+        v: u128,
+        rounding_mode: RoundingMode,
+    ) -> (u128, ExceptionFlags) {
+        let (sign, exponent, significand) = Self::unpack(v);
+
+        if exponent == Self::EXPONENT_MASK && significand != 0 {
+            return Self::propagate_nan(&[v]);
+        } else if exponent == Self::EXPONENT_MASK || (exponent == 0 && significand == 0) {
+            return (v, ExceptionFlags::NONE);
+        }
+
+        // handle denormals and implicit bit
+        let (exponent, significand) = Self::normalize(exponent, significand);
+        let unbiased_exponent = exponent - Self::BIAS;
+
+        let result = if unbiased_exponent >= MANT_BITS as i32 {
+            // no fractional bits
+            v
+        } else if unbiased_exponent < 0 {
+            // |v| < 1, so the integral part is zero; |v| >= 1/2 only if the
+            // unbiased exponent is -1
+            let guard = unbiased_exponent == -1;
+            let sticky = unbiased_exponent < -1 || significand & Self::SIGNIFICAND_MASK != 0;
+            if rounding_mode.round_up(sign, 0, guard, sticky) {
+                Self::pack(sign, Self::BIAS, 0)
             } else {
-                NEG_ZERO
+                Self::zero(sign)
             }
-        } else if unbiased_exponent >= 10 {
-            v
         } else {
-            let shift = 10 - unbiased_exponent;
-            let exponent = (unbiased_exponent + 15) as u16;
-            let significand = significand | 1 << 10;
+            let shift = MANT_BITS as i32 - unbiased_exponent;
 
             let integer = significand >> shift;
             let fraction = significand & ((1 << shift) - 1);
             let half = 1 << (shift - 1);
 
-            // rounding (to even)
-            let rnd = if fraction < half || fraction == half && integer & 1 == 0 {
-                // round down
-                0
-            } else {
-                // round up
-                1 << shift
-            };
+            let rnd = rounding_mode.round_up(
+                sign,
+                (integer & 1) as u32,
+                fraction & half != 0,
+                fraction & (half - 1) != 0,
+            ) as u128;
 
-            let significand = integer << shift;
+            // cut off implicit bit and allow overflow into exponent
+            Self::pack(sign, exponent, (integer << shift) & Self::SIGNIFICAND_MASK) + (rnd << shift)
+        };
 
-            if significand == 0 && rnd == 0 {
-                if sign == 0 {
-                    POS_ZERO
-                } else {
-                    NEG_ZERO
-                }
-            } else if rnd == 1 << 11 {
-                Self::from_bits(sign << 15 | (exponent + 1) << 10)
-            } else {
-                // cut off implicit bit and allow overflow into exponent
-                Self::from_bits(sign << 15 | (exponent << 10 | significand & ((1 << 10) - 1)) + rnd)
-            }
+        (result, ExceptionFlags::NONE)
+    }
+
+    // like round_to_integral, but inexact results raise the inexact exception
+    pub(crate) fn round_to_integral_exact(
+        v: u128,
+        rounding_mode: RoundingMode,
+    ) -> (u128, ExceptionFlags) {
+        let (result, flags) = Self::round_to_integral(v, rounding_mode);
+        if !Self::is_nan(v) && result != v {
+            (result, flags | ExceptionFlags::INEXACT)
+        } else {
+            (result, flags)
         }
     }
 }
 
 impl RoundTiesEven for f32 {
-    fn round_ties_even(_v: Self) -> Self {
-        unimplemented!();
+    fn round_ties_even(v: Self) -> Self {
+        v.round_ties_even()
     }
 }
 
+// integers are integral already
 impl RoundTiesEven for i32 {
-    fn round_ties_even(_v: Self) -> Self {
-        unimplemented!();
+    fn round_ties_even(v: Self) -> Self {
+        v
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::soft_float16::NAN;
-
     use super::*;
+    use crate::{SoftFloat16, Trunc};
 
     #[test]
     fn test_round() {
-        for v in [0x47ff] {
+        for v in [0x47ff, 0x3800, 0x3e00, 0xbe00, 0x4100] {
             let x = SoftFloat16::from_bits(v);
             let y = SoftFloat16::round_ties_even(x);
             let y_f = SoftFloat16::from(f32::from(x).round_ties_even());
-            if y == NAN || y_f == NAN {
-                assert!(y == NAN, "{}", v);
-                assert!(y_f == NAN, "{}", v);
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{}", v);
+                assert!(SoftFloat16::is_nan(y_f), "{}", v);
             } else {
                 assert_eq!(
                     SoftFloat16::to_bits(y),
@@ -105,9 +194,9 @@ mod tests {
             let x = SoftFloat16::from_bits(i);
             let y = SoftFloat16::round_ties_even(x);
             let y_f = SoftFloat16::from(f32::from(x).round_ties_even());
-            if y == NAN || y_f == NAN {
-                assert!(y == NAN, "{}", i);
-                assert!(y_f == NAN, "{}", i);
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{}", i);
+                assert!(SoftFloat16::is_nan(y_f), "{}", i);
             } else {
                 assert_eq!(
                     SoftFloat16::to_bits(y),
@@ -120,4 +209,135 @@ mod tests {
             }
         }
     }
+
+    fn check_all_round<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+            let y = SoftFloat::round_ties_even(x0);
+            let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0).round_ties_even());
+            if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                assert!(SoftFloat::is_nan(y), "{:?}", i);
+                assert!(SoftFloat::is_nan(y_f), "{:?}", i);
+            } else {
+                assert_eq!(SoftFloat::to_bits(y), SoftFloat::to_bits(y_f), "{:?}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_to_integral() {
+        for (v, expected) in [
+            (2.5f32, [2.0, 3.0, 2.0, 3.0, 2.0]),
+            (-2.5, [-2.0, -3.0, -3.0, -2.0, -2.0]),
+            (0.25, [0.0, 0.0, 0.0, 1.0, 0.0]),
+            (-0.25, [-0.0, -0.0, -1.0, -0.0, -0.0]),
+            (3.75, [4.0, 4.0, 3.0, 4.0, 3.0]),
+            (512.5, [512.0, 513.0, 512.0, 513.0, 512.0]),
+        ] {
+            let x = SoftFloat16::from(v);
+            for (y, expected) in [
+                SoftFloat16::round_ties_even(x),
+                SoftFloat16::round(x),
+                SoftFloat16::floor(x),
+                SoftFloat16::ceil(x),
+                SoftFloat16::trunc(x),
+            ]
+            .into_iter()
+            .zip(expected)
+            {
+                assert_eq!(
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(SoftFloat16::from(expected)),
+                    "{:?}",
+                    v
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_to_integral_exact() {
+        let rm = RoundingMode::TowardNegative;
+        for (v, expected, expected_flags) in [
+            (0x3e00, 0x3c00, ExceptionFlags::INEXACT),
+            (0xb400, 0xbc00, ExceptionFlags::INEXACT),
+            (0x3c00, 0x3c00, ExceptionFlags::NONE),
+            (0x6400, 0x6400, ExceptionFlags::NONE),
+            (0x7c00, 0x7c00, ExceptionFlags::NONE),
+            (0x7e00, 0x7e00, ExceptionFlags::NONE),
+            (0x7d00, 0x7f00, ExceptionFlags::INVALID),
+        ] {
+            let x = SoftFloat16::from_bits(v);
+            let (y, flags) = SoftFloat16::round_to_integral_exact_with_flags(x, rm);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:#x}", v);
+            assert_eq!(flags, expected_flags, "{:#x}", v);
+
+            // same result without raising inexact
+            let (y, flags) = SoftFloat16::round_to_integral_with_flags(x, rm);
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:#x}", v);
+            let expected_flags = if expected_flags == ExceptionFlags::INEXACT {
+                ExceptionFlags::NONE
+            } else {
+                expected_flags
+            };
+            assert_eq!(flags, expected_flags, "{:#x}", v);
+        }
+    }
+
+    fn check_all_round_to_integral<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+            let v = f32::from(x0);
+            for (y, y_f) in [
+                (SoftFloat::round(x0), v.round()),
+                (SoftFloat::floor(x0), v.floor()),
+                (SoftFloat::ceil(x0), v.ceil()),
+            ] {
+                let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(y_f);
+                if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                    assert!(SoftFloat::is_nan(y), "{:?}", i);
+                    assert!(SoftFloat::is_nan(y_f), "{:?}", i);
+                } else {
+                    assert_eq!(SoftFloat::to_bits(y), SoftFloat::to_bits(y_f), "{:?}", i);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_round_to_integral() {
+        for i in 0..=u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let v = f32::from(x);
+            for (y, y_f) in [
+                (SoftFloat16::round(x), v.round()),
+                (SoftFloat16::floor(x), v.floor()),
+                (SoftFloat16::ceil(x), v.ceil()),
+            ] {
+                let y_f = SoftFloat16::from(y_f);
+                if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                    assert!(SoftFloat16::is_nan(y), "{}", i);
+                    assert!(SoftFloat16::is_nan(y_f), "{}", i);
+                } else {
+                    assert_eq!(SoftFloat16::to_bits(y), SoftFloat16::to_bits(y_f), "{}", i);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_round_to_integral_binary8() {
+        check_all_round_to_integral::<2, 5>();
+        check_all_round_to_integral::<3, 4>();
+        check_all_round_to_integral::<4, 3>();
+        check_all_round_to_integral::<5, 2>();
+    }
+
+    #[test]
+    fn test_all_round_binary8() {
+        check_all_round::<2, 5>();
+        check_all_round::<3, 4>();
+        check_all_round::<4, 3>();
+        check_all_round::<5, 2>();
+    }
 }
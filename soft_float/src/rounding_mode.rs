@@ -0,0 +1,151 @@
+// rounding-direction attributes according to IEEE 754, sec. 4.3, plus
+// rounding to odd, which is useful for avoiding double rounding when
+// narrowing in several steps
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    #[default]
+    NearestTiesToEven,
+    NearestTiesToAway,
+    TowardZero,
+    TowardPositive,
+    TowardNegative,
+    ToOdd,
+}
+
+impl RoundingMode {
+    // decides whether a truncated significand needs to be incremented by one
+    // unit in the last place; guard is the most significant bit that has been
+    // cut off, sticky is set if any of the remaining bits were nonzero
+    pub(crate) const fn round_up(self, sign: u32, lsb: u32, guard: bool, sticky: bool) -> bool {
+        let inexact = guard || sticky;
+        match self {
+            RoundingMode::NearestTiesToEven => guard && (sticky || lsb == 1),
+            RoundingMode::NearestTiesToAway => guard,
+            RoundingMode::TowardZero => false,
+            RoundingMode::TowardPositive => inexact && sign == 0,
+            RoundingMode::TowardNegative => inexact && sign == 1,
+            // setting the lsb of an even significand is the same as
+            // incrementing it
+            RoundingMode::ToOdd => inexact && lsb == 0,
+        }
+    }
+
+    // decides whether a result that is too large to be represented becomes
+    // infinity or the largest finite number
+    pub(crate) const fn overflows_to_infinity(self, sign: u32) -> bool {
+        match self {
+            RoundingMode::NearestTiesToEven | RoundingMode::NearestTiesToAway => true,
+            RoundingMode::TowardZero | RoundingMode::ToOdd => false,
+            RoundingMode::TowardPositive => sign == 0,
+            RoundingMode::TowardNegative => sign == 1,
+        }
+    }
+
+    // sign of an exact zero resulting from the sum of two operands with
+    // opposite signs, see IEEE 754, sec. 6.3
+    pub(crate) const fn exact_zero_sign(self) -> u32 {
+        matches!(self, RoundingMode::TowardNegative) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ROUNDING_MODES;
+
+    #[test]
+    fn test_round_up() {
+        // (lsb, guard, sticky) of an exact value and of values below, at and
+        // above the midpoint to the next significand, first for an even then
+        // for an odd significand; expected lists whether to round up
+        let cases = [
+            (0, false, false),
+            (0, false, true),
+            (0, true, false),
+            (0, true, true),
+            (1, false, false),
+            (1, false, true),
+            (1, true, false),
+            (1, true, true),
+        ];
+        for (rounding_mode, positive, negative) in [
+            (
+                RoundingMode::NearestTiesToEven,
+                [0, 0, 0, 1, 0, 0, 1, 1],
+                [0, 0, 0, 1, 0, 0, 1, 1],
+            ),
+            (
+                RoundingMode::NearestTiesToAway,
+                [0, 0, 1, 1, 0, 0, 1, 1],
+                [0, 0, 1, 1, 0, 0, 1, 1],
+            ),
+            (RoundingMode::TowardZero, [0; 8], [0; 8]),
+            (
+                RoundingMode::TowardPositive,
+                [0, 1, 1, 1, 0, 1, 1, 1],
+                [0; 8],
+            ),
+            (
+                RoundingMode::TowardNegative,
+                [0; 8],
+                [0, 1, 1, 1, 0, 1, 1, 1],
+            ),
+            (
+                RoundingMode::ToOdd,
+                [0, 1, 1, 1, 0, 0, 0, 0],
+                [0, 1, 1, 1, 0, 0, 0, 0],
+            ),
+        ] {
+            for (sign, expected) in [(0, positive), (1, negative)] {
+                for ((lsb, guard, sticky), expected) in cases.into_iter().zip(expected) {
+                    assert_eq!(
+                        rounding_mode.round_up(sign, lsb, guard, sticky),
+                        expected == 1,
+                        "{:?}",
+                        (rounding_mode, sign, lsb, guard, sticky)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_overflows_to_infinity() {
+        // whether positive and negative results become infinity, i.e., the
+        // infinity lies in the rounding direction
+        for (rounding_mode, positive, negative) in [
+            (RoundingMode::NearestTiesToEven, true, true),
+            (RoundingMode::NearestTiesToAway, true, true),
+            (RoundingMode::TowardZero, false, false),
+            (RoundingMode::TowardPositive, true, false),
+            (RoundingMode::TowardNegative, false, true),
+            (RoundingMode::ToOdd, false, false),
+        ] {
+            assert_eq!(
+                rounding_mode.overflows_to_infinity(0),
+                positive,
+                "{:?}",
+                rounding_mode
+            );
+            assert_eq!(
+                rounding_mode.overflows_to_infinity(1),
+                negative,
+                "{:?}",
+                rounding_mode
+            );
+        }
+    }
+
+    #[test]
+    fn test_exact_zero_sign() {
+        for rounding_mode in ROUNDING_MODES {
+            let expected = (rounding_mode == RoundingMode::TowardNegative) as u32;
+            assert_eq!(
+                rounding_mode.exact_zero_sign(),
+                expected,
+                "{:?}",
+                rounding_mode
+            );
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// precision to which the results of arithmetic operations on ExtFloat80 are
+// rounded, corresponding to the precision-control field of the x87 control
+// word; results keep the full exponent range of the extended format, so
+// only the number of significand bits (24, 53, or 64 including the integer
+// bit) is reduced
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingPrecision {
+    Single,
+    Double,
+    #[default]
+    Extended,
+}
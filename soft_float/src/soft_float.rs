@@ -0,0 +1,388 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+use crate::format::Format;
+use crate::{ExceptionFlags, RoundingMode, TininessDetection};
+
+// unsigned integer holding the encoding of a SoftFloat
+pub trait Storage: Copy + Debug {
+    const BITS: u32;
+
+    // truncates to the width of the storage type
+    fn from_u128(v: u128) -> Self;
+
+    fn to_u128(v: Self) -> u128;
+}
+
+macro_rules! impl_storage {
+    ($($t:ty),*) => {
+        $(
+            impl Storage for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                fn from_u128(v: u128) -> Self {
+                    v as $t
+                }
+
+                fn to_u128(v: Self) -> u128 {
+                    v as u128
+                }
+            }
+        )*
+    };
+}
+
+impl_storage!(u8, u16, u32, u64, u128);
+
+// binary floating-point number with EXP_BITS exponent and MANT_BITS
+// (explicitly stored) significand bits according to IEEE 754, stored in the
+// least significant bits of T
+#[derive(Clone, Copy)]
+pub struct SoftFloat<T, const EXP_BITS: u32, const MANT_BITS: u32>(T);
+
+pub type SoftFloat16 = SoftFloat<u16, 5, 10>;
+
+impl SoftFloat16 {
+    pub const fn clz(v: u16) -> u16 {
+        if v == 0 {
+            16
+        } else {
+            // branchless binary search
+            // compare https://stackoverflow.com/a/10866821
+            let mut n = 0;
+            let mut v = v;
+            let mut s;
+            s = ((v & 0xFF00 == 0) as u16) << 3;
+            n += s;
+            v <<= s;
+            s = ((v & 0xF000 == 0) as u16) << 2;
+            n += s;
+            v <<= s;
+            s = ((v & 0xC000 == 0) as u16) << 1;
+            n += s;
+            v <<= s;
+            s = (v & 0x8000 == 0) as u16;
+            n += s;
+            n
+        }
+    }
+}
+
+// const versions of construction, field access, conversions, arithmetic and
+// comparisons, e.g., for computing constants and tables at compile time;
+// trait methods are not available in const contexts, so these call the
+// algorithms in Format directly and round to nearest, ties to even, i.e., the
+// results are bit-identical to the ones of the operators and traits
+impl SoftFloat16 {
+    pub const fn from_bits_const(v: u16) -> Self {
+        Self(v)
+    }
+
+    pub const fn to_bits_const(v: Self) -> u16 {
+        v.0
+    }
+
+    pub const fn sign_const(v: Self) -> u16 {
+        Format::<5, 10>::unpack(v.0 as u128).0 as u16
+    }
+
+    pub const fn exponent_const(v: Self) -> u16 {
+        Format::<5, 10>::unpack(v.0 as u128).1 as u16
+    }
+
+    pub const fn significand_const(v: Self) -> u16 {
+        Format::<5, 10>::unpack(v.0 as u128).2 as u16
+    }
+
+    pub const fn from_f32_const(value: f32) -> Self {
+        Self::from_raw_const(Format::<5, 10>::convert::<8, 23>(
+            value.to_bits() as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    // exact
+    pub const fn to_f32_const(v: Self) -> f32 {
+        let (bits, _) = Format::<8, 23>::convert::<5, 10>(
+            v.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        );
+        f32::from_bits(bits as u32)
+    }
+
+    pub const fn from_i32_const(value: i32) -> Self {
+        Self::from_raw_const(Format::<5, 10>::from_integer(
+            (value < 0) as u32,
+            value.unsigned_abs() as u128,
+            RoundingMode::NearestTiesToEven,
+        ))
+    }
+
+    pub const fn from_u32_const(value: u32) -> Self {
+        Self::from_raw_const(Format::<5, 10>::from_integer(
+            0,
+            value as u128,
+            RoundingMode::NearestTiesToEven,
+        ))
+    }
+
+    pub const fn neg_const(v: Self) -> Self {
+        Self(v.0 ^ Format::<5, 10>::SIGN_BIT as u16)
+    }
+
+    pub const fn add_const(v0: Self, v1: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::add(
+            v0.0 as u128,
+            v1.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    pub const fn sub_const(v0: Self, v1: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::sub(
+            v0.0 as u128,
+            v1.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    pub const fn mul_const(v0: Self, v1: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::mul(
+            v0.0 as u128,
+            v1.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    pub const fn div_const(v0: Self, v1: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::div(
+            v0.0 as u128,
+            v1.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    // computes v0 * v1 + v2 with a single rounding
+    pub const fn mul_add_const(v0: Self, v1: Self, v2: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::mul_add(
+            v0.0 as u128,
+            v1.0 as u128,
+            v2.0 as u128,
+            RoundingMode::NearestTiesToEven,
+            TininessDetection::AfterRounding,
+        ))
+    }
+
+    pub const fn sqrt_const(v: Self) -> Self {
+        Self::from_raw_const(Format::<5, 10>::sqrt(
+            v.0 as u128,
+            RoundingMode::NearestTiesToEven,
+        ))
+    }
+
+    pub const fn eq_const(v0: Self, v1: Self) -> bool {
+        Format::<5, 10>::eq(v0.0 as u128, v1.0 as u128).0
+    }
+
+    pub const fn partial_cmp_const(v0: Self, v1: Self) -> Option<Ordering> {
+        Format::<5, 10>::partial_cmp(v0.0 as u128, v1.0 as u128)
+    }
+
+    pub const fn lt_const(v0: Self, v1: Self) -> bool {
+        matches!(Self::partial_cmp_const(v0, v1), Some(Ordering::Less))
+    }
+
+    pub const fn le_const(v0: Self, v1: Self) -> bool {
+        matches!(
+            Self::partial_cmp_const(v0, v1),
+            Some(Ordering::Less | Ordering::Equal)
+        )
+    }
+
+    // the exception flags are dropped
+    const fn from_raw_const((v, _): (u128, ExceptionFlags)) -> Self {
+        Self(v as u16)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    const IS_VALID: () = {
+        let () = Format::<EXP_BITS, MANT_BITS>::IS_VALID;
+        assert!(
+            Format::<EXP_BITS, MANT_BITS>::BITS <= T::BITS,
+            "storage type too narrow"
+        );
+    };
+
+    // preserves all bits, including sign and payload of (signaling) NANs
+    pub fn from_bits(v: T) -> Self {
+        Self::from_raw(T::to_u128(v))
+    }
+
+    pub fn to_bits(v: Self) -> T {
+        v.0
+    }
+
+    pub fn sign(v: Self) -> T {
+        T::from_u128(Format::<EXP_BITS, MANT_BITS>::unpack(Self::raw(v)).0 as u128)
+    }
+
+    pub fn exponent(v: Self) -> T {
+        T::from_u128(Format::<EXP_BITS, MANT_BITS>::unpack(Self::raw(v)).1 as u128)
+    }
+
+    pub fn significand(v: Self) -> T {
+        T::from_u128(Format::<EXP_BITS, MANT_BITS>::unpack(Self::raw(v)).2)
+    }
+
+    // encoding as passed to the algorithms in Format
+    pub(crate) fn raw(v: Self) -> u128 {
+        T::to_u128(v.0)
+    }
+
+    // bits beyond the width of the format are cleared
+    pub(crate) fn from_raw(v: u128) -> Self {
+        let () = Self::IS_VALID;
+        let mask = u128::MAX >> (128 - Format::<EXP_BITS, MANT_BITS>::BITS);
+        Self(T::from_u128(v & mask))
+    }
+
+    pub(crate) fn from_raw_with_flags(
+        (v, flags): (u128, ExceptionFlags),
+    ) -> (Self, ExceptionFlags) {
+        (Self::from_raw(v), flags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::XorShift;
+    use crate::{MulAdd, Sqrt};
+
+    // evaluated at compile time
+    const ONE: SoftFloat16 = SoftFloat16::from_bits_const(0x3c00);
+    const HALF_PI: SoftFloat16 = SoftFloat16::div_const(
+        SoftFloat16::from_f32_const(std::f32::consts::PI),
+        SoftFloat16::from_i32_const(2),
+    );
+    const SQRT_2: SoftFloat16 = SoftFloat16::sqrt_const(SoftFloat16::from_u32_const(2));
+    const RECIPROCALS: [SoftFloat16; 8] = {
+        let mut table = [ONE; 8];
+        let mut i = 1;
+        while i < table.len() {
+            table[i] = SoftFloat16::div_const(ONE, SoftFloat16::from_u32_const(i as u32 + 1));
+            i += 1;
+        }
+        table
+    };
+
+    #[test]
+    fn test_all_clz() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            assert_eq!(SoftFloat16::clz(x.0), x.0.leading_zeros() as u16);
+        }
+    }
+
+    #[test]
+    fn test_const() {
+        let half_pi = SoftFloat16::from(std::f32::consts::PI) / SoftFloat16::from(2);
+        assert_eq!(SoftFloat16::to_bits(HALF_PI), SoftFloat16::to_bits(half_pi));
+        assert_eq!(SoftFloat16::to_bits(HALF_PI), 0x3e48);
+        assert_eq!(SoftFloat16::to_bits(SQRT_2), 0x3da8);
+        for (i, &x) in RECIPROCALS.iter().enumerate() {
+            let y = SoftFloat16::from(1) / SoftFloat16::from(i as u32 + 1);
+            assert_eq!(SoftFloat16::to_bits(x), SoftFloat16::to_bits(y), "{}", i);
+        }
+
+        const FIELDS: (u16, u16, u16) = {
+            let x = SoftFloat16::from_bits_const(0xbe01);
+            (
+                SoftFloat16::sign_const(x),
+                SoftFloat16::exponent_const(x),
+                SoftFloat16::significand_const(x),
+            )
+        };
+        assert_eq!(FIELDS, (1, 0xf, 0x201));
+        const CLZ: u16 = SoftFloat16::clz(0x00ff);
+        assert_eq!(CLZ, 8);
+        const { assert!(SoftFloat16::lt_const(SQRT_2, HALF_PI)) };
+        const { assert!(SoftFloat16::le_const(ONE, SQRT_2)) };
+        const F32: f32 = SoftFloat16::to_f32_const(SQRT_2);
+        assert_eq!(F32, 1.4140625);
+    }
+
+    // the const versions share the algorithms with the generic
+    // implementation, which is tested extensively
+    #[test]
+    fn test_const_like_generic() {
+        let special = [
+            0x0000, 0x8000, 0x0001, 0x03ff, 0x0400, 0x3c00, 0x3c01, 0x7bff, 0x7c00, 0xfc00, 0x7e00,
+            0x7d00, 0xfe01,
+        ];
+        let mut rng = XorShift::new();
+        let mut next = || rng.next_u16();
+        let operands: Vec<_> = (0..3000)
+            .map(|i| special.get(i).copied().unwrap_or_else(&mut next))
+            .collect();
+        for window in operands.windows(3) {
+            let [x0, x1, x2] = [0, 1, 2].map(|i| SoftFloat16::from_bits(window[i]));
+            let bits = SoftFloat16::to_bits;
+            assert_eq!(bits(SoftFloat16::add_const(x0, x1)), bits(x0 + x1));
+            assert_eq!(bits(SoftFloat16::sub_const(x0, x1)), bits(x0 - x1));
+            assert_eq!(bits(SoftFloat16::mul_const(x0, x1)), bits(x0 * x1));
+            assert_eq!(bits(SoftFloat16::div_const(x0, x1)), bits(x0 / x1));
+            assert_eq!(
+                bits(SoftFloat16::mul_add_const(x0, x1, x2)),
+                bits(SoftFloat16::mul_add(x0, x1, x2))
+            );
+            assert_eq!(
+                bits(SoftFloat16::sqrt_const(x0)),
+                bits(SoftFloat16::sqrt(x0))
+            );
+            assert_eq!(bits(SoftFloat16::neg_const(x0)), bits(-x0));
+            assert_eq!(SoftFloat16::eq_const(x0, x1), x0 == x1);
+            assert_eq!(SoftFloat16::lt_const(x0, x1), x0 < x1);
+            assert_eq!(SoftFloat16::le_const(x0, x1), x0 <= x1);
+            assert_eq!(SoftFloat16::partial_cmp_const(x0, x1), x0.partial_cmp(&x1));
+
+            let value = f32::from_bits((window[0] as u32) << 16 | window[1] as u32);
+            assert_eq!(
+                bits(SoftFloat16::from_f32_const(value)),
+                bits(SoftFloat16::from(value))
+            );
+            assert_eq!(
+                SoftFloat16::to_f32_const(x0).to_bits(),
+                f32::from(x0).to_bits()
+            );
+            let value = (window[0] as i32) << (window[1] % 17);
+            assert_eq!(
+                bits(SoftFloat16::from_i32_const(value)),
+                bits(SoftFloat16::from(value))
+            );
+            assert_eq!(
+                bits(SoftFloat16::from_u32_const(value as u32)),
+                bits(SoftFloat16::from(value as u32))
+            );
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let x = SoftFloat::<u8, 4, 3>::from_bits(0b1011_0101);
+        assert_eq!(SoftFloat::sign(x), 1);
+        assert_eq!(SoftFloat::exponent(x), 0b0110);
+        assert_eq!(SoftFloat::significand(x), 0b101);
+
+        // bits beyond the width of the format are ignored
+        let x = SoftFloat::<u16, 4, 3>::from_bits(0xff35);
+        assert_eq!(SoftFloat::to_bits(x), 0x35);
+    }
+}
@@ -0,0 +1,507 @@
+use crate::{RoundingMode, SoftFloat, SoftFloat16, SoftFloat32, SoftFloat64};
+
+// binary128 (quadruple precision); all operations are carried out in
+// software, the host offers no counterpart on stable Rust
+pub type SoftFloat128 = SoftFloat<u128, 15, 112>;
+
+// conversions from the narrower formats are always exact; f32 and f64 are
+// covered by the conversions of SoftFloat
+impl From<SoftFloat16> for SoftFloat128 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat32> for SoftFloat128 {
+    fn from(value: SoftFloat32) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat64> for SoftFloat128 {
+    fn from(value: SoftFloat64) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat128> for SoftFloat16 {
+    fn from(value: SoftFloat128) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat128> for SoftFloat32 {
+    fn from(value: SoftFloat128) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat128> for SoftFloat64 {
+    fn from(value: SoftFloat128) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ExceptionFlags, MulAdd, Sqrt, TininessDetection};
+
+    const RM: RoundingMode = RoundingMode::NearestTiesToEven;
+    const TD: TininessDetection = TininessDetection::AfterRounding;
+
+    // the expected results below have been computed with exact rational
+    // arithmetic
+
+    #[test]
+    fn test_add() {
+        for (v0, v1, expected, expected_flags) in [
+            (
+                0x401f1027c386bbc4cd613e30d8f16adf,
+                0x401c78e57311d8a3c2ce6f447ed4d57b,
+                0x401f3f4471e8f6d945bb0c1968cc058e,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xbff1e4b00741c7a87ce42c8218072e8c,
+                0xbff1c464c324c9859b810e766ec9d286,
+                0xbff2d48a653348970c329d7c43688089,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0xc0103a90cd447e35b8b6d8fe442e3d43,
+                0xc01005b607d4bedc51431193e6c3f339,
+                0xc01120236a8c9e8904fcf5491579183e,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0xc01c6196e1988ad9f06c144a025b413f,
+                0xbff2076fb9d179e06c0fd4f5f8130c42,
+                0xc01c6196e1988b1bcc5a88a87a764534,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xbff37eedf06d3fef701966a0c381e88f,
+                0xbff43802ad45f23d3b1a11df587fd280,
+                0xbff4f779a57c9234f326c52fba40c6c8,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc0110580ed2f89d94a2f20aaf3c64af7,
+                0x401e2f9719999e3fa46d6753ec148cb4,
+                0x401e2f8eed9234c355a315dae6bcee82,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let (x0, x1) = (SoftFloat128::from_bits(v0), SoftFloat128::from_bits(v1));
+            let (y, flags) = SoftFloat128::add_with_flags(x0, x1, RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x?}", (v0, v1));
+            assert_eq!(flags, expected_flags, "{:032x?}", (v0, v1));
+        }
+    }
+
+    #[test]
+    fn test_sub() {
+        for (v0, v1, expected, expected_flags) in [
+            (
+                0xbffce544552b82f6be3edc0a1ef2a4f0,
+                0xbffb81f96c0f3459f79b17aeefba91fc,
+                0xbffc24479f23e8c9c2715032a7155bf2,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0x3ffb7fd6e1ea24c4f9341c68966baea1,
+                0xbff808d6da71144896c8da1964b2d2bc,
+                0x3ffba0f1bd38474e0c0d37abc30208f8,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x400a5dfb2c4a3698aa2ca1af6a107b75,
+                0xc00a8228a9ec0806705fca161622bd79,
+                0x400b7011eb1b1f4f8d4635e2c0199c77,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0xbfeb5eda64ac5db9d707107e855c3844,
+                0x3fdab4104efbc8d60b21fbac78255d68,
+                0xbfeb5edb3eb48537bb72160f83327457,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc025a5ac64c2f2e39403560d97dae38d,
+                0x3fec0326fb695ffb3a1890c78092b4d4,
+                0xc025a5ac64c2f2e39484e98b4c8ae12a,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xbff08c5fdc3bf364eb8ac8ce8a245e6b,
+                0x400ad8f3f3d4e7115804f92283868a29,
+                0xc00ad8f3f437ff086701d25d6638bdcc,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let (x0, x1) = (SoftFloat128::from_bits(v0), SoftFloat128::from_bits(v1));
+            let (y, flags) = SoftFloat128::sub_with_flags(x0, x1, RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x?}", (v0, v1));
+            assert_eq!(flags, expected_flags, "{:032x?}", (v0, v1));
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        for (v0, v1, expected, expected_flags) in [
+            (
+                0xc004a8c244ef7febe8e5b4617589a82b,
+                0xc024623901762741bab9f87ff5059285,
+                0x402a25dd9c80aa95888f8aa8777533d8,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc018c70384c8199921167d8fcf23cae8,
+                0xbff17b290e5e18baf320cd576d14475b,
+                0x400b50f59756f93a0ec31f620bf25e88,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc005f0e63328ad088ded3c9691eb79fa,
+                0xc00b6a175b569643d037cdff7c240d49,
+                0x40125f696d28014266017b9b7907206d,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fd7c9549f9d01298a449ebe89d9bf02,
+                0xc001cdf807295e4299901c0475491bc3,
+                0xbfda9ca43d19c93feb15545ca7f947b7,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3feddc6b2e47dc0e959f3a518cfe5cd1,
+                0x401dee52d1020a15d9ed17e3cc0e95ee,
+                0x400ccbf8794d65064163401b138cd520,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fdb1209ac512b01f18dd1eed77c96c0,
+                0x3fd9c16ec10faa4003ba33db73f7ba8e,
+                0x3fb5e119860c77082083eec638436bf0,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let (x0, x1) = (SoftFloat128::from_bits(v0), SoftFloat128::from_bits(v1));
+            let (y, flags) = SoftFloat128::mul_with_flags(x0, x1, RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x?}", (v0, v1));
+            assert_eq!(flags, expected_flags, "{:032x?}", (v0, v1));
+        }
+    }
+
+    #[test]
+    fn test_div() {
+        for (v0, v1, expected, expected_flags) in [
+            (
+                0x3ff69ff3cc1b0c3e1c07724e44c5b476,
+                0x400328dd2adf559a11cbc2884a5012dc,
+                0x3ff266b215041e6b1ff0d5b1ee1f80cb,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x401a45dda81aa40a2b0b8c12f3b37f32,
+                0xbffc7f1a526eb523b3df44a47467537a,
+                0xc01cb3815c2185f772d8964c142ede80,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fe557e562f5680c4fdf8e1a060cea63,
+                0x3fefe65a40e2a20a1bd7ce734227de21,
+                0x3ff46a07f956bc16d6345a7409c862bc,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc0189b0bf72f2bb83586fca7fa0b8518,
+                0x3fd9257e65b675cd0492c4f539b21c95,
+                0xc03e66891faa53cf7595371fb77c6f60,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3febad9c819d7ca7b46108cc721754ef,
+                0x401cf9c0fa1b1bf13879399bd50e0097,
+                0x3fcdb2eacae3d87cfe11e241ff82ebbd,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc019a604861e02ec39235bc0736a947a,
+                0x4009523dcdaaac43936aa40cacc66a57,
+                0xc00f3f6813793f2996a1f6c097658c02,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let (x0, x1) = (SoftFloat128::from_bits(v0), SoftFloat128::from_bits(v1));
+            let (y, flags) = SoftFloat128::div_with_flags(x0, x1, RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x?}", (v0, v1));
+            assert_eq!(flags, expected_flags, "{:032x?}", (v0, v1));
+        }
+    }
+
+    #[test]
+    fn test_mul_add() {
+        // the first four cases cancel almost completely
+        for (v0, v1, v2, expected, expected_flags) in [
+            (
+                0xc0274c71bcc99ae80f0c8a896d21f4cd,
+                0x3ff2121b4e6f5a940c250a03e023033d,
+                0x401a63f531e9f3863d17551c65923eff,
+                0x3faa4a37a82262d27b84debac1034427,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0x401f8f8b022bc32021615022409a8a78,
+                0xbfdbf65237b4000bd1c51f86973082d6,
+                0x3ffc87fd73dc26f670e6ecf2f152339d,
+                0xbf8a662ec2480667352551ce740160a0,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0x3ff092c934accd781959b9ef58d07674,
+                0xc00e1abb7e0ab2ed31b1c27e976699cc,
+                0x3fffbcd8a34fb1de979e9b9580777908,
+                0xbf8fea53b0be0e94e0f2c4d4fb21b870,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0xc00a282e04a1bde44806aa81e65150b5,
+                0x4000c85f903715c8fcaf4a5acfa6cf3e,
+                0x400c08004416b7fb301fe5dd5aa6f7d3,
+                0xbf9a6e681e551ace81323ea070a3cdac,
+                ExceptionFlags::NONE,
+            ),
+            (
+                0xc007ea1958068a9d8c31406deea3d685,
+                0xc01b8853fc4a447ec49872c67c081bb7,
+                0x3fdf220d15ad9a9d0a57af35b9b81635,
+                0x4024778bcc0057bdbf28b541558f5ebb,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fec449c3685156b89c80c4de9367ed9,
+                0xc001415ad75470808181e84d99a74924,
+                0x40023c354a8d15d81d296588571ceeee,
+                0x40023c35311564d2f843e061de5626ff,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xc024e323b7115c02f44d7e40c78fec45,
+                0x3fe81ab1c52f4fbe8d19821f947810d8,
+                0x3fdcddbd6156c4df12bccdcb6816de06,
+                0xc00e0ac26bbf3c24c649655990818ab1,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0xbfe91d5c57450e6520012170d418f7af,
+                0xc022139f60c73494ed192da3c82ad589,
+                0xc01df3c614ed204990e32e8239455353,
+                0xc01df3c57b4f334c88a6a5ac9ae32182,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let x0 = SoftFloat128::from_bits(v0);
+            let x1 = SoftFloat128::from_bits(v1);
+            let x2 = SoftFloat128::from_bits(v2);
+            let (y, flags) = SoftFloat128::mul_add_with_flags(x0, x1, x2, RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x?}", (v0, v1, v2));
+            assert_eq!(flags, expected_flags, "{:032x?}", (v0, v1, v2));
+        }
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for (v, expected, expected_flags) in [
+            (
+                0x40000000000000000000000000000000,
+                0x3fff6a09e667f3bcc908b2fb1366ea95,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x400588c7907f96694ba955f3e4096150,
+                0x40023d1942392ba7ab0ef0f2c1311317,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fe51b9446f57327e592067375305db7,
+                0x3ff20d6fdcd35ede86abe55342361e9e,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fdc9d19032b73284bb57b5cd3e89d32,
+                0x3fedcbe5d8f3767c9d8e66cf407fe5ab,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fd8d37c1d775b7c69dd649317788b95,
+                0x3febe93c830b432baa2615ee4f508408,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x3fdcfcf7c91752a33d589cab301ba988,
+                0x3fedfe7b5102efa3f9d606b2735bc956,
+                ExceptionFlags::INEXACT,
+            ),
+            (
+                0x400c2ad9736ebf511d95389b297a21d7,
+                0x4005872a84310e7ea418b2eeb94df50d,
+                ExceptionFlags::INEXACT,
+            ),
+        ] {
+            let x = SoftFloat128::from_bits(v);
+            let (y, flags) = SoftFloat128::sqrt_with_flags(x, RM);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{:032x}", v);
+            assert_eq!(flags, expected_flags, "{:032x}", v);
+        }
+    }
+
+    #[test]
+    fn test_special() {
+        let one = SoftFloat128::from(1.0);
+        let three = SoftFloat128::from(3.0);
+        assert_eq!(SoftFloat128::to_bits(one), 0x3fff << 112);
+        assert_eq!(
+            SoftFloat128::to_bits(one / three),
+            0x3ffd5555555555555555555555555555
+        );
+
+        let max = SoftFloat128::from_bits(0x7ffeffffffffffffffffffffffffffff);
+        let (y, flags) = SoftFloat128::add_with_flags(max, max, RM, TD);
+        assert_eq!(SoftFloat128::to_bits(y), 0x7fff << 112);
+        assert_eq!(flags, ExceptionFlags::OVERFLOW | ExceptionFlags::INEXACT);
+
+        // halving the smallest denormal number is a tie, rounding to zero;
+        // 3/4 of it rounds up
+        let min = SoftFloat128::from_bits(1);
+        for (v, expected) in [(0.5, 0), (0.75, 1)] {
+            let (y, flags) = SoftFloat128::mul_with_flags(min, SoftFloat128::from(v), RM, TD);
+            assert_eq!(SoftFloat128::to_bits(y), expected, "{}", v);
+            assert_eq!(flags, ExceptionFlags::UNDERFLOW | ExceptionFlags::INEXACT);
+        }
+
+        // rem is exact even for far apart exponents
+        let (y, flags) = SoftFloat128::rem_with_flags(max, three);
+        assert_eq!(f64::from(y), -1.0);
+        assert_eq!(flags, ExceptionFlags::NONE);
+    }
+
+    // a thousand f64 operands from all binades
+    fn operands() -> impl Iterator<Item = f64> {
+        (0..1000_u64).map(|i| f64::from_bits(i.wrapping_mul(0x0015_5555_5555_5557) ^ i << 63))
+    }
+
+    // rounding the binary128 result of +, -, *, / and sqrt to binary64 is
+    // innocuous (113 >= 2 * 53 + 2), so the result must match the host
+    #[test]
+    fn test_f64() {
+        let check = |y: SoftFloat128, y_f: f64, args: (f64, f64)| {
+            let y = f64::from(y);
+            if y.is_nan() || y_f.is_nan() {
+                assert!(y.is_nan() && y_f.is_nan(), "{:?}", args);
+            } else {
+                assert_eq!(y.to_bits(), y_f.to_bits(), "{:?}", args);
+            }
+        };
+
+        for x0_f in operands() {
+            let x0 = SoftFloat128::from(x0_f);
+            check(SoftFloat128::sqrt(x0), x0_f.sqrt(), (x0_f, 0.0));
+            for x1_f in operands().step_by(9) {
+                let x1 = SoftFloat128::from(x1_f);
+                check(x0 + x1, x0_f + x1_f, (x0_f, x1_f));
+                check(x0 - x1, x0_f - x1_f, (x0_f, x1_f));
+                check(x0 / x1, x0_f / x1_f, (x0_f, x1_f));
+                assert_eq!(x0.partial_cmp(&x1), x0_f.partial_cmp(&x1_f));
+
+                // products of f64 numbers are exact; the error of the
+                // rounded f64 product is exactly what the fused
+                // multiply-add of the host computes
+                let p = x0 * x1;
+                let p_f = x0_f * x1_f;
+                check(p, p_f, (x0_f, x1_f));
+                if p_f.is_finite() && p_f != 0.0 && p_f.abs() > f64::MIN_POSITIVE * 2.0_f64.powi(53)
+                {
+                    check(
+                        p - SoftFloat128::from(p_f),
+                        x0_f.mul_add(x1_f, -p_f),
+                        (x0_f, x1_f),
+                    );
+                    let y = SoftFloat128::mul_add(x0, x1, SoftFloat128::from(-p_f));
+                    assert_eq!(
+                        SoftFloat128::to_bits(y),
+                        SoftFloat128::to_bits(p - SoftFloat128::from(p_f))
+                    );
+                }
+
+                // the remainder is exact in both formats
+                let (z0, z1) = (SoftFloat64::from(x0_f), SoftFloat64::from(x1_f));
+                let r = SoftFloat64::from(x0 % x1);
+                let r_64 = z0 % z1;
+                if !SoftFloat64::is_nan(r) || !SoftFloat64::is_nan(r_64) {
+                    assert_eq!(
+                        SoftFloat64::to_bits(r),
+                        SoftFloat64::to_bits(r_64),
+                        "{:?}",
+                        (x0_f, x1_f)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_host() {
+        for x_f in operands() {
+            let x = SoftFloat128::from(x_f);
+            if x_f.is_nan() {
+                assert!(f64::from(x).is_nan());
+                continue;
+            }
+            assert_eq!(f64::from(x).to_bits(), x_f.to_bits());
+            assert_eq!(SoftFloat64::to_bits(SoftFloat64::from(x)), x_f.to_bits());
+            assert_eq!(
+                SoftFloat128::to_bits(SoftFloat128::from(SoftFloat64::from(x_f))),
+                SoftFloat128::to_bits(x)
+            );
+            assert_eq!(f32::from(x).to_bits(), (x_f as f32).to_bits());
+            let y = SoftFloat128::from(x_f as f32);
+            assert_eq!(
+                SoftFloat128::to_bits(SoftFloat128::from(SoftFloat32::from(x_f as f32))),
+                SoftFloat128::to_bits(y)
+            );
+        }
+    }
+
+    #[test]
+    fn test_all_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftFloat128::from(x);
+            if SoftFloat16::is_nan(x) {
+                assert!(SoftFloat128::is_nan(y), "{}", i);
+                continue;
+            }
+            assert_eq!(SoftFloat16::to_bits(SoftFloat16::from(y)), i);
+            assert_eq!(f32::from(y).to_bits(), f32::from(x).to_bits());
+
+            // conversion of numbers next to the midpoint between x and its
+            // successor, which only binary128 can represent
+            if i & 0x7fff >= 0x7bff {
+                continue;
+            }
+            let lo = SoftFloat128::from(x);
+            let hi = SoftFloat128::from(SoftFloat16::from_bits(i + 1));
+            let mid =
+                SoftFloat128::mul_add(lo, SoftFloat128::from(0.5), hi * SoftFloat128::from(0.5));
+            let even = if i & 1 == 0 { i } else { i + 1 };
+            let next_down = SoftFloat128::from_bits(SoftFloat128::to_bits(mid) - 1);
+            let next_up = SoftFloat128::from_bits(SoftFloat128::to_bits(mid) + 1);
+            for (value, expected) in [(next_down, i), (mid, even), (next_up, i + 1)] {
+                let y = SoftFloat16::from(value);
+                assert_eq!(SoftFloat16::to_bits(y), expected, "{}", i);
+            }
+        }
+    }
+}
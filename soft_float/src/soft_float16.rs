@@ -1,75 +1,335 @@
-use std::fmt;
+use crate::{f16, SoftFloat16};
 
-#[derive(Clone, Copy, Debug)]
-pub struct SoftFloat16(u16);
+// characteristics of binary16, named like the ones of f32
+impl SoftFloat16 {
+    pub const RADIX: u32 = 2;
 
-pub const POS_INFINITY: SoftFloat16 = SoftFloat16(0x7c00);
-pub const NEG_INFINITY: SoftFloat16 = SoftFloat16(0xfc00);
-pub const NAN: SoftFloat16 = SoftFloat16(0x7e00);
-pub const POS_ZERO: SoftFloat16 = SoftFloat16(0x0);
-pub const NEG_ZERO: SoftFloat16 = SoftFloat16(0x8000);
+    // significand bits, including the implicit bit
+    pub const MANTISSA_DIGITS: u32 = 11;
 
-impl SoftFloat16 {
-    pub fn clz(v: u16) -> u16 {
-        if v == 0 {
-            16
-        } else {
-            // branchless binary search
-            // compare https://stackoverflow.com/a/10866821
-            let mut n = 0;
-            let mut v = v;
-            let mut s;
-            s = ((v & 0xFF00 == 0) as u16) << 3;
-            n += s;
-            v <<= s;
-            s = ((v & 0xF000 == 0) as u16) << 2;
-            n += s;
-            v <<= s;
-            s = ((v & 0xC000 == 0) as u16) << 1;
-            n += s;
-            v <<= s;
-            s = (v & 0x8000 == 0) as u16;
-            n += s;
-            n
-        }
-    }
+    // decimal digits that survive the round trip decimal -> SoftFloat16 ->
+    // decimal, i.e., floor((MANTISSA_DIGITS - 1) * log10(2))
+    pub const DIGITS: u32 = 3;
 
-    pub fn from_bits(v: u16) -> Self {
-        let v = Self(v);
-        // map all possible NANs to a single representation
-        if Self::exponent(v) == 0x1F && Self::significand(v) != 0 {
-            NAN
-        } else {
-            v
-        }
-    }
+    // difference between 1 and the next larger number, 2^-10
+    pub const EPSILON: SoftFloat16 = f16!("0x1p-10");
 
-    pub fn to_bits(v: Self) -> u16 {
-        v.0
-    }
+    pub const MIN: SoftFloat16 = f16!(-65504);
 
-    pub fn sign(v: Self) -> u16 {
-        v.0 >> 15
-    }
+    // smallest normal number, 2^-14
+    pub const MIN_POSITIVE: SoftFloat16 = f16!("0x1p-14");
 
-    pub fn exponent(v: Self) -> u16 {
-        (v.0 >> 10) & 0x1F
-    }
+    // smallest denormal number, 2^-24
+    pub const MIN_SUBNORMAL: SoftFloat16 = f16!("0x1p-24");
 
-    pub fn significand(v: Self) -> u16 {
-        v.0 & 0x3FF
-    }
+    pub const MAX: SoftFloat16 = f16!(65504);
+
+    // one more than the exponents of MIN_POSITIVE and MAX, as the significand
+    // is taken to be in [0.5, 1) like C's FLT_MIN_EXP and FLT_MAX_EXP
+    pub const MIN_EXP: i32 = -13;
+    pub const MAX_EXP: i32 = 16;
+
+    // the smallest and largest powers of ten that are normal numbers
+    pub const MIN_10_EXP: i32 = -4;
+    pub const MAX_10_EXP: i32 = 4;
+
+    // the default NAN, which invalid operations return
+    pub const NAN: SoftFloat16 = f16!(nan);
+
+    pub const INFINITY: SoftFloat16 = f16!(inf);
+
+    pub const NEG_INFINITY: SoftFloat16 = f16!(-inf);
+
+    pub const ZERO: SoftFloat16 = f16!(0);
+
+    pub const NEG_ZERO: SoftFloat16 = f16!(-0);
+}
+
+// mathematical constants, correctly rounded to SoftFloat16 from their decimal
+// expansions, compare std::f32::consts
+pub mod consts {
+    use crate::{f16, SoftFloat16};
+
+    // Archimedes' constant (π)
+    pub const PI: SoftFloat16 = f16!(3.14159265358979323846264338327950288);
+
+    // the full circle constant (τ = 2π)
+    pub const TAU: SoftFloat16 = f16!(6.28318530717958647692528676655900577);
+
+    // π/2
+    pub const FRAC_PI_2: SoftFloat16 = f16!(1.57079632679489661923132169163975144);
+
+    // π/3
+    pub const FRAC_PI_3: SoftFloat16 = f16!(1.04719755119659774615421446109316763);
+
+    // π/4
+    pub const FRAC_PI_4: SoftFloat16 = f16!(0.785398163397448309615660845819875721);
+
+    // π/6
+    pub const FRAC_PI_6: SoftFloat16 = f16!(0.523598775598298873077107230546583814);
+
+    // π/8
+    pub const FRAC_PI_8: SoftFloat16 = f16!(0.392699081698724154807830422909937861);
+
+    // 1/π
+    pub const FRAC_1_PI: SoftFloat16 = f16!(0.318309886183790671537767526745028724);
+
+    // 2/π
+    pub const FRAC_2_PI: SoftFloat16 = f16!(0.636619772367581343075535053490057448);
+
+    // 2/sqrt(π)
+    pub const FRAC_2_SQRT_PI: SoftFloat16 = f16!(1.12837916709551257389615890312154517);
+
+    // sqrt(2)
+    pub const SQRT_2: SoftFloat16 = f16!(1.41421356237309504880168872420969808);
+
+    // 1/sqrt(2)
+    pub const FRAC_1_SQRT_2: SoftFloat16 = f16!(0.707106781186547524400844362104849039);
+
+    // Euler's number (e)
+    pub const E: SoftFloat16 = f16!(2.71828182845904523536028747135266250);
+
+    // log_2(e)
+    pub const LOG2_E: SoftFloat16 = f16!(1.44269504088896340735992468100189214);
+
+    // log_2(10)
+    pub const LOG2_10: SoftFloat16 = f16!(3.32192809488736234787031942948939018);
+
+    // log_10(e)
+    pub const LOG10_E: SoftFloat16 = f16!(0.434294481903251827651128918916605082);
+
+    // log_10(2)
+    pub const LOG10_2: SoftFloat16 = f16!(0.301029995663981195213738894724493027);
+
+    // ln(2)
+    pub const LN_2: SoftFloat16 = f16!(0.693147180559945309417232121458176568);
+
+    // ln(10)
+    pub const LN_10: SoftFloat16 = f16!(2.30258509299404568401799145468436421);
 }
 
 #[cfg(test)]
 mod tests {
+    use super::consts::*;
     use super::*;
+    use crate::{RoundingMode, SoftFloat128};
+    use std::f64::consts as host;
+
+    fn bits(v: SoftFloat16) -> u16 {
+        SoftFloat16::to_bits(v)
+    }
+
+    #[test]
+    fn test_limits() {
+        let one = f32::from(f16!(1));
+        assert_eq!(f32::from(SoftFloat16::EPSILON), 2.0f32.powi(-10));
+        assert_eq!(
+            bits(SoftFloat16::from(one + f32::from(SoftFloat16::EPSILON))),
+            bits(f16!(1)) + 1
+        );
+        assert_eq!(bits(SoftFloat16::MAX), 0x7bff);
+        assert_eq!(bits(SoftFloat16::MIN), 0xfbff);
+        assert_eq!(bits(SoftFloat16::MIN_POSITIVE), 0x0400);
+        assert_eq!(bits(SoftFloat16::MIN_SUBNORMAL), 0x0001);
+        assert_eq!(bits(SoftFloat16::INFINITY), 0x7c00);
+        assert_eq!(bits(SoftFloat16::NEG_INFINITY), 0xfc00);
+        assert_eq!(bits(SoftFloat16::ZERO), 0x0000);
+        assert_eq!(bits(SoftFloat16::NEG_ZERO), 0x8000);
+        assert_eq!(
+            bits(SoftFloat16::NAN),
+            bits(SoftFloat16::sqrt_const(f16!(-1)))
+        );
+
+        // the same relations as between the constants of f32
+        fn check(
+            (radix, mantissa_digits, digits): (u32, u32, u32),
+            (min_exp, max_exp, min_10_exp, max_10_exp): (i32, i32, i32, i32),
+            (epsilon, min_positive, max): (f64, f64, f64),
+        ) {
+            assert_eq!(radix, 2);
+            assert_eq!(
+                digits,
+                ((mantissa_digits - 1) as f64 * std::f64::consts::LOG10_2) as u32
+            );
+            assert_eq!(epsilon, 2.0f64.powi(1 - mantissa_digits as i32));
+            assert_eq!(min_positive, 2.0f64.powi(min_exp - 1));
+            assert_eq!(max, (2.0 - epsilon) * 2.0f64.powi(max_exp - 1));
+            assert!(10.0f64.powi(min_10_exp) >= min_positive);
+            assert!(10.0f64.powi(min_10_exp - 1) < min_positive);
+            assert!(10.0f64.powi(max_10_exp) <= max);
+            assert!(10.0f64.powi(max_10_exp + 1) > max);
+        }
+        check(
+            (f32::RADIX, f32::MANTISSA_DIGITS, f32::DIGITS),
+            (f32::MIN_EXP, f32::MAX_EXP, f32::MIN_10_EXP, f32::MAX_10_EXP),
+            (
+                f32::EPSILON as f64,
+                f32::MIN_POSITIVE as f64,
+                f32::MAX as f64,
+            ),
+        );
+        check(
+            (
+                SoftFloat16::RADIX,
+                SoftFloat16::MANTISSA_DIGITS,
+                SoftFloat16::DIGITS,
+            ),
+            (
+                SoftFloat16::MIN_EXP,
+                SoftFloat16::MAX_EXP,
+                SoftFloat16::MIN_10_EXP,
+                SoftFloat16::MAX_10_EXP,
+            ),
+            (
+                f64::from(f32::from(SoftFloat16::EPSILON)),
+                f64::from(f32::from(SoftFloat16::MIN_POSITIVE)),
+                f64::from(f32::from(SoftFloat16::MAX)),
+            ),
+        );
+        assert_eq!(
+            f32::from(SoftFloat16::MIN_SUBNORMAL),
+            f32::from(SoftFloat16::MIN_POSITIVE) * f32::from(SoftFloat16::EPSILON)
+        );
+        assert_eq!(f32::from(SoftFloat16::MIN), -f32::from(SoftFloat16::MAX));
+    }
 
     #[test]
-    fn test_all_clz() {
-        for i in 0..u16::MAX {
-            let x = SoftFloat16::from_bits(i);
-            assert_eq!(SoftFloat16::clz(x.0), x.0.leading_zeros() as u16);
+    fn test_consts() {
+        // 50 significant digits, rounded to binary128 first; as the two
+        // rounding modes agree, the binary128 number is not halfway between two
+        // SoftFloat16, so there is no double rounding
+        for (v, expected, s) in [
+            (
+                PI,
+                0x4248,
+                "3.1415926535897932384626433832795028841971693993751",
+            ),
+            (
+                TAU,
+                0x4648,
+                "6.2831853071795864769252867665590057683943387987502",
+            ),
+            (
+                FRAC_PI_2,
+                0x3e48,
+                "1.5707963267948966192313216916397514420985846996876",
+            ),
+            (
+                FRAC_PI_3,
+                0x3c30,
+                "1.0471975511965977461542144610931676280657231331250",
+            ),
+            (
+                FRAC_PI_4,
+                0x3a48,
+                "0.78539816339744830961566084581987572104929234984378",
+            ),
+            (
+                FRAC_PI_6,
+                0x3830,
+                "0.52359877559829887307710723054658381403286156656252",
+            ),
+            (
+                FRAC_PI_8,
+                0x3648,
+                "0.39269908169872415480783042290993786052464617492189",
+            ),
+            (
+                FRAC_1_PI,
+                0x3518,
+                "0.31830988618379067153776752674502872406891929148091",
+            ),
+            (
+                FRAC_2_PI,
+                0x3918,
+                "0.63661977236758134307553505349005744813783858296183",
+            ),
+            (
+                FRAC_2_SQRT_PI,
+                0x3c83,
+                "1.1283791670955125738961589031215451716881012586580",
+            ),
+            (
+                SQRT_2,
+                0x3da8,
+                "1.4142135623730950488016887242096980785696718753769",
+            ),
+            (
+                FRAC_1_SQRT_2,
+                0x39a8,
+                "0.70710678118654752440084436210484903928483593768847",
+            ),
+            (
+                E,
+                0x4170,
+                "2.7182818284590452353602874713526624977572470937000",
+            ),
+            (
+                LOG2_E,
+                0x3dc5,
+                "1.4426950408889634073599246810018921374266459541530",
+            ),
+            (
+                LOG2_10,
+                0x42a5,
+                "3.3219280948873623478703194294893901758648313930246",
+            ),
+            (
+                LOG10_E,
+                0x36f3,
+                "0.43429448190325182765112891891660508229439700580367",
+            ),
+            (
+                LOG10_2,
+                0x34d1,
+                "0.30102999566398119521373889472449302676818988146211",
+            ),
+            (
+                LN_2,
+                0x398c,
+                "0.69314718055994530941723212145817656807550013436026",
+            ),
+            (
+                LN_10,
+                0x409b,
+                "2.3025850929940456840179914546843642076011014886288",
+            ),
+        ] {
+            assert_eq!(bits(v), expected, "{}", s);
+
+            let v_128 = s.parse::<SoftFloat128>().unwrap();
+            for rounding_mode in [
+                RoundingMode::NearestTiesToEven,
+                RoundingMode::NearestTiesToAway,
+            ] {
+                let y = SoftFloat16::from_soft_float_with_rounding(v_128, rounding_mode);
+                assert_eq!(bits(y), expected, "{}", s);
+            }
+        }
+
+        // the same as rounding the constants of f64
+        for (v, v_f) in [
+            (PI, host::PI),
+            (TAU, host::TAU),
+            (FRAC_PI_2, host::FRAC_PI_2),
+            (FRAC_PI_3, host::FRAC_PI_3),
+            (FRAC_PI_4, host::FRAC_PI_4),
+            (FRAC_PI_6, host::FRAC_PI_6),
+            (FRAC_PI_8, host::FRAC_PI_8),
+            (FRAC_1_PI, host::FRAC_1_PI),
+            (FRAC_2_PI, host::FRAC_2_PI),
+            (FRAC_2_SQRT_PI, host::FRAC_2_SQRT_PI),
+            (SQRT_2, host::SQRT_2),
+            (FRAC_1_SQRT_2, host::FRAC_1_SQRT_2),
+            (E, host::E),
+            (LOG2_E, host::LOG2_E),
+            (LOG2_10, host::LOG2_10),
+            (LOG10_E, host::LOG10_E),
+            (LOG10_2, host::LOG10_2),
+            (LN_2, host::LN_2),
+            (LN_10, host::LN_10),
+        ] {
+            assert_eq!(bits(v), bits(SoftFloat16::from(v_f)), "{}", v_f);
         }
     }
 }
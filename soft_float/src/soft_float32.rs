@@ -0,0 +1,106 @@
+use crate::{RoundingMode, SoftFloat, SoftFloat16};
+
+// binary32, i.e., the same format as f32 but with all operations carried out
+// in software, so results are bit-exact regardless of the host
+pub type SoftFloat32 = SoftFloat<u32, 8, 23>;
+
+// conversion is always exact
+impl From<SoftFloat16> for SoftFloat32 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat32> for SoftFloat16 {
+    fn from(value: SoftFloat32) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::host_tests;
+    use crate::{RoundTiesEven, Sqrt, Trunc};
+
+    // special values and their neighbors, most of which are unlikely to be
+    // hit by random numbers
+    const SPECIAL_VALUES: [u32; 16] = [
+        0x00000000, 0x00000001, 0x007fffff, 0x00800000, 0x00800001, 0x33800000, 0x3f7fffff,
+        0x3f800000, 0x3f800001, 0x3fffffff, 0x4b000000, 0x4b7fffff, 0x7f7fffff, 0x7f800000,
+        0x7fc00000, 0x7f800001,
+    ];
+
+    host_tests!(SoftFloat32, f32, u32, SPECIAL_VALUES);
+
+    #[test]
+    #[ignore]
+    fn test_all_sqrt() {
+        for i in 0..u32::MAX {
+            let x = SoftFloat32::from_bits(i);
+            check(SoftFloat32::sqrt(x), f32::from_bits(i).sqrt(), (i, 0, 0));
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_round() {
+        for i in 0..u32::MAX {
+            let x = SoftFloat32::from_bits(i);
+            let x_f = f32::from_bits(i);
+            check(
+                SoftFloat32::round_ties_even(x),
+                x_f.round_ties_even(),
+                (i, 0, 0),
+            );
+            check(SoftFloat32::trunc(x), x_f.trunc(), (i, 0, 0));
+        }
+    }
+
+    #[test]
+    fn test_host() {
+        for i in operands(100000) {
+            let x = SoftFloat32::from_bits(i);
+            let x_f = f32::from_bits(i);
+            check(SoftFloat32::from(x_f), x_f, (i, 0, 0));
+            check(SoftFloat32::from(x_f as f64), x_f, (i, 0, 0));
+            check(SoftFloat32::from(f32::from(x)), x_f, (i, 0, 0));
+            let y = f64::from(x);
+            if x_f.is_nan() {
+                assert!(y.is_nan(), "{:08x}", i);
+            } else {
+                assert_eq!(y.to_bits(), (x_f as f64).to_bits(), "{:08x}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftFloat32::from(x);
+            check(y, f32::from(x), (i as u32, 0, 0));
+            // round trip
+            if !SoftFloat16::is_nan(x) {
+                assert_eq!(SoftFloat16::to_bits(SoftFloat16::from(y)), i);
+            }
+        }
+
+        for i in operands(100000) {
+            let x = SoftFloat32::from_bits(i);
+            let y = SoftFloat16::from(x);
+            let y_f = SoftFloat16::from(f32::from_bits(i));
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{:08x}", i);
+                assert!(SoftFloat16::is_nan(y_f), "{:08x}", i);
+            } else {
+                assert_eq!(
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                    "{:08x}",
+                    i
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{RoundingMode, SoftFloat, SoftFloat16, SoftFloat32};
+
+// binary64, i.e., the same format as f64 but with all operations carried out
+// in software, so results are bit-exact regardless of the host
+pub type SoftFloat64 = SoftFloat<u64, 11, 52>;
+
+// conversion is always exact
+impl From<SoftFloat16> for SoftFloat64 {
+    fn from(value: SoftFloat16) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat64> for SoftFloat16 {
+    fn from(value: SoftFloat64) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+// conversion is always exact
+impl From<SoftFloat32> for SoftFloat64 {
+    fn from(value: SoftFloat32) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl From<SoftFloat64> for SoftFloat32 {
+    fn from(value: SoftFloat64) -> Self {
+        Self::from_soft_float_with_rounding(value, RoundingMode::NearestTiesToEven)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::host_tests;
+
+    // special values and their neighbors, most of which are unlikely to be
+    // hit by random numbers
+    const SPECIAL_VALUES: [u64; 16] = [
+        0x0000000000000000,
+        0x0000000000000001,
+        0x000fffffffffffff,
+        0x0010000000000000,
+        0x0010000000000001,
+        0x3ca0000000000000,
+        0x3fefffffffffffff,
+        0x3ff0000000000000,
+        0x3ff0000000000001,
+        0x3fffffffffffffff,
+        0x4330000000000000,
+        0x433fffffffffffff,
+        0x7fefffffffffffff,
+        0x7ff0000000000000,
+        0x7ff8000000000000,
+        0x7ff0000000000001,
+    ];
+
+    host_tests!(SoftFloat64, f64, u64, SPECIAL_VALUES);
+
+    #[test]
+    fn test_host() {
+        for i in operands(100000) {
+            let x = SoftFloat64::from_bits(i);
+            let x_f = f64::from_bits(i);
+            check(SoftFloat64::from(x_f), x_f, (i, 0, 0));
+            check(SoftFloat64::from(f64::from(x)), x_f, (i, 0, 0));
+            let y = f32::from(x);
+            if x_f.is_nan() {
+                assert!(y.is_nan(), "{:016x}", i);
+            } else {
+                assert_eq!(y.to_bits(), (x_f as f32).to_bits(), "{:016x}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_softfloat32() {
+        for i in operands(100000) {
+            let x = SoftFloat64::from_bits(i);
+            let y = SoftFloat32::from(x);
+            let y_f = f64::from_bits(i) as f32;
+            if SoftFloat32::is_nan(y) || y_f.is_nan() {
+                assert!(SoftFloat32::is_nan(y), "{:016x}", i);
+                assert!(y_f.is_nan(), "{:016x}", i);
+            } else {
+                assert_eq!(SoftFloat32::to_bits(y), y_f.to_bits(), "{:016x}", i);
+                // round trip
+                let z = SoftFloat64::from(y);
+                assert_eq!(
+                    SoftFloat64::to_bits(z),
+                    (y_f as f64).to_bits(),
+                    "{:016x}",
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_softfloat16() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftFloat64::from(x);
+            check(y, f32::from(x) as f64, (i as u64, 0, 0));
+            // round trip
+            if !SoftFloat16::is_nan(x) {
+                assert_eq!(SoftFloat16::to_bits(SoftFloat16::from(y)), i);
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use crate::format::Format;
+use crate::u256::U256;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
+
+pub trait Sqrt {
+    fn sqrt(v: Self) -> Self;
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Sqrt
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
+    fn sqrt(v: Self) -> Self {
+        Self::sqrt_with_rounding(v, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn sqrt_with_rounding(v: Self, rounding_mode: RoundingMode) -> Self {
+        Self::sqrt_with_flags(v, rounding_mode).0
+    }
+
+    pub fn sqrt_with_flags(v: Self, rounding_mode: RoundingMode) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::sqrt(
+            Self::raw(v),
+            rounding_mode,
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn sqrt(v: u128, rounding_mode: RoundingMode) -> (u128, ExceptionFlags) {
+        let (sign, exponent, significand) = Self::unpack(v);
+
+        if exponent == Self::EXPONENT_MASK && significand != 0 {
+            // sqrt(NAN)
+            return Self::propagate_nan(&[v]);
+        } else if exponent == 0 && significand == 0 {
+            // sqrt(+0) or sqrt(-0)
+            return (v, ExceptionFlags::NONE);
+        } else if sign == 1 {
+            // sqrt(-x)
+            return (Self::NAN, ExceptionFlags::INVALID);
+        } else if exponent == Self::EXPONENT_MASK {
+            // sqrt(oo)
+            return (v, ExceptionFlags::NONE);
+        }
+
+        // handle denormals and implicit bit
+        let (exponent, significand) = Self::normalize(exponent, significand);
+
+        // make unbiased exponent even so it can be halved; the significand
+        // then looks like 1.x{MANT_BITS} or 1x.x{MANT_BITS}
+        let unbiased_exponent = exponent - Self::BIAS;
+        let (unbiased_exponent, significand) = if unbiased_exponent & 1 != 0 {
+            (unbiased_exponent - 1, significand << 1)
+        } else {
+            (unbiased_exponent, significand)
+        };
+
+        // the square root of the radicand looks like 1.x{MANT_BITS} with
+        // three additional guard, round, sticky bits, i.e., MANT_BITS + 4
+        // bits in total, so the radicand requires twice as many bits
+        let radicand = U256::shift_left(U256::from_u128(significand), MANT_BITS + 6);
+        assert!(U256::compare(radicand, U256::shift_left(U256::ONE, 2 * (MANT_BITS + 4))).is_lt());
+
+        // generate root one bit at a time using the digit-by-digit method
+        let mut x = radicand;
+        let mut r = U256::ZERO;
+        let mut bit = U256::shift_left(U256::ONE, 2 * (MANT_BITS + 3));
+        while !U256::is_zero(bit) {
+            let t = U256::sum(r, bit);
+            if U256::compare(x, t).is_ge() {
+                x = U256::difference(x, t);
+                r = U256::sum(U256::shift_right(r, 1), bit);
+            } else {
+                r = U256::shift_right(r, 1);
+            }
+            bit = U256::shift_right(bit, 2);
+        }
+
+        // any nonzero remainder ends up in the sticky bit
+        let sticky = !U256::is_zero(x) as u128;
+        let significand = U256::to_u128(r) | sticky;
+        assert!(significand & (Self::IMPLICIT_BIT << 3) != 0);
+        assert!(significand < Self::IMPLICIT_BIT << (1 + 3));
+
+        // results can only be tiny for formats whose bias is at most
+        // MANT_BITS (binary16, e.g., has no tiny results: the smallest
+        // denormal number 2^-24 has root 2^-12); tininess is then detected
+        // after rounding
+        let exponent = (unbiased_exponent >> 1) + Self::BIAS;
+
+        Self::round_and_pack(
+            0,
+            exponent,
+            significand,
+            rounding_mode,
+            TininessDetection::AfterRounding,
+        )
+    }
+}
+
+impl Sqrt for f32 {
+    fn sqrt(v: Self) -> Self {
+        v.sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{check_exact, check_rounding, ROUNDING_MODES};
+    use crate::{SoftFloat16, SoftFloat32};
+
+    #[test]
+    fn test_sqrt() {
+        for v in [
+            0x0000, 0x8000, 0x0001, 0x0002, 0x03ff, 0x0400, 0x3c00, 0x3c01, 0x4000, 0x4400, 0x7bff,
+            0x7c00, 0xfc00, 0xbc00, 0x7e00,
+        ] {
+            let x = SoftFloat16::from_bits(v);
+            let y = SoftFloat16::sqrt(x);
+            let y_f = SoftFloat16::from(f32::from(x).sqrt());
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{:?}", v);
+                assert!(SoftFloat16::is_nan(y_f), "{:?}", v);
+            } else {
+                assert_eq!(
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                    "\n{}\n  {:016b}\n  {:016b}",
+                    v,
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_sqrt() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let y = SoftFloat16::sqrt(x);
+            let y_f = SoftFloat16::from(f32::from(x).sqrt());
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{}", i);
+                assert!(SoftFloat16::is_nan(y_f), "{}", i);
+            } else {
+                assert_eq!(
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                    "\n{}\n  {:016b}\n  {:016b}",
+                    i,
+                    SoftFloat16::to_bits(y),
+                    SoftFloat16::to_bits(y_f),
+                );
+            }
+        }
+    }
+
+    fn check_all_sqrt<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+            let y = SoftFloat::sqrt(x0);
+            let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0).sqrt());
+            if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                assert!(SoftFloat::is_nan(y), "{:?}", i);
+                assert!(SoftFloat::is_nan(y_f), "{:?}", i);
+            } else {
+                assert_eq!(SoftFloat::to_bits(y), SoftFloat::to_bits(y_f), "{:?}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_sqrt_binary8() {
+        check_all_sqrt::<2, 5>();
+        check_all_sqrt::<3, 4>();
+        check_all_sqrt::<4, 3>();
+        check_all_sqrt::<5, 2>();
+    }
+
+    #[test]
+    fn test_sqrt_f32() {
+        for v in [0.0f32, -0.0, 2.0, 1e-40, f32::MAX, f32::INFINITY] {
+            let y = <f32 as Sqrt>::sqrt(v);
+            let y_f = f32::from(Sqrt::sqrt(SoftFloat32::from(v)));
+            assert_eq!(y.to_bits(), y_f.to_bits(), "{:?}", v);
+        }
+        assert!(<f32 as Sqrt>::sqrt(-1.0).is_nan());
+    }
+
+    #[test]
+    fn test_all_sqrt_rounding() {
+        for i in 0..u16::MAX {
+            let x = SoftFloat16::from_bits(i);
+            let v = f32::from(x) as f64;
+            for rounding_mode in ROUNDING_MODES {
+                let y = SoftFloat16::sqrt_with_rounding(x, rounding_mode);
+                let nearest = SoftFloat16::sqrt(x);
+                if !v.is_finite() || v <= 0.0 {
+                    check_exact(y, nearest, rounding_mode, &[i]);
+                } else {
+                    let compare = |c: f64| v.total_cmp(&(c * c));
+                    check_rounding(y, nearest, compare, rounding_mode, &[i]);
+                }
+            }
+        }
+    }
+}
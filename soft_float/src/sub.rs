@@ -1,20 +1,59 @@
 use std::ops::Sub;
 
-use crate::SoftFloat16;
+use crate::format::Format;
+use crate::{ExceptionFlags, RoundingMode, SoftFloat, Storage, TininessDetection};
 
-impl Sub for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Sub
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        self + -other
+        Self::sub_with_rounding(self, other, RoundingMode::NearestTiesToEven)
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    pub fn sub_with_rounding(v0: Self, v1: Self, rounding_mode: RoundingMode) -> Self {
+        Self::sub_with_flags(v0, v1, rounding_mode, TininessDetection::default()).0
+    }
+
+    pub fn sub_with_flags(
+        v0: Self,
+        v1: Self,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (Self, ExceptionFlags) {
+        Self::from_raw_with_flags(Format::<EXP_BITS, MANT_BITS>::sub(
+            Self::raw(v0),
+            Self::raw(v1),
+            rounding_mode,
+            tininess_detection,
+        ))
+    }
+}
+
+impl<const EXP_BITS: u32, const MANT_BITS: u32> Format<EXP_BITS, MANT_BITS> {
+    pub(crate) const fn sub(
+        v0: u128,
+        v1: u128,
+        rounding_mode: RoundingMode,
+        tininess_detection: TininessDetection,
+    ) -> (u128, ExceptionFlags) {
+        if Self::is_nan(v0) || Self::is_nan(v1) {
+            // negating would flip the sign of a NAN in v1
+            return Self::propagate_nan(&[v0, v1]);
+        }
+
+        Self::add(v0, v1 ^ Self::SIGN_BIT, rounding_mode, tininess_detection)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::soft_float16::NAN;
-
     use super::*;
+    use crate::testing::{check_exact, check_rounding, XorShift, ROUNDING_MODES};
+    use crate::SoftFloat16;
 
     #[test]
     #[ignore]
@@ -25,9 +64,9 @@ mod tests {
                 let x1 = SoftFloat16::from_bits(j);
                 let y = x0 - x1;
                 let y_f = SoftFloat16::from(f32::from(x0) - f32::from(x1));
-                if y == NAN || y_f == NAN {
-                    assert!(y == NAN, "{:?}", (i, j));
-                    assert!(y_f == NAN, "{:?}", (i, j));
+                if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                    assert!(SoftFloat16::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat16::is_nan(y_f), "{:?}", (i, j));
                 } else {
                     assert_eq!(
                         SoftFloat16::to_bits(y),
@@ -39,4 +78,88 @@ mod tests {
             }
         }
     }
+
+    fn check_all_sub<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            for j in 0..=u8::MAX {
+                let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+                let x1 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(j);
+                let y = x0 - x1;
+                let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0) - f32::from(x1));
+                if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                    assert!(SoftFloat::is_nan(y), "{:?}", (i, j));
+                    assert!(SoftFloat::is_nan(y_f), "{:?}", (i, j));
+                } else {
+                    assert_eq!(
+                        SoftFloat::to_bits(y),
+                        SoftFloat::to_bits(y_f),
+                        "{:?}",
+                        (i, j)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_sub_binary8() {
+        check_all_sub::<2, 5>();
+        check_all_sub::<3, 4>();
+        check_all_sub::<4, 3>();
+        check_all_sub::<5, 2>();
+    }
+
+    fn check_sub_rounding(i: u16, j: u16, rounding_mode: RoundingMode) {
+        let x0 = SoftFloat16::from_bits(i);
+        let x1 = SoftFloat16::from_bits(j);
+        let y = SoftFloat16::sub_with_rounding(x0, x1, rounding_mode);
+        let nearest = x0 - x1;
+        let exact = f32::from(x0) as f64 - f32::from(x1) as f64;
+        if exact == 0.0 && !(i & 0x7fff == 0 && j & 0x7fff == 0 && i != j) {
+            // exact zero from operands with the same sign
+            let expected = (rounding_mode.exact_zero_sign() << 15) as u16;
+            assert_eq!(SoftFloat16::to_bits(y), expected, "{:?}", (i, j));
+        } else if !exact.is_finite() {
+            check_exact(y, nearest, rounding_mode, &[i, j]);
+        } else {
+            check_rounding(y, nearest, |c| exact.total_cmp(&c), rounding_mode, &[i, j]);
+        }
+    }
+
+    #[test]
+    fn test_sub_rounding() {
+        for rounding_mode in ROUNDING_MODES {
+            // exact zeros, overflow and tiny subtrahends
+            check_sub_rounding(0x3c00, 0x3c00, rounding_mode);
+            check_sub_rounding(0x0000, 0x0000, rounding_mode);
+            check_sub_rounding(0x8000, 0x0000, rounding_mode);
+            check_sub_rounding(0x7bff, 0xfbff, rounding_mode);
+            check_sub_rounding(0xfbff, 0x7bff, rounding_mode);
+            check_sub_rounding(0x3c00, 0x0001, rounding_mode);
+            check_sub_rounding(0x3c00, 0x8001, rounding_mode);
+        }
+    }
+
+    #[test]
+    fn test_random_sub_rounding() {
+        let mut rng = XorShift::new();
+        for _ in 0..100_000 {
+            let (i, j) = (rng.next_u16(), rng.next_u16());
+            for rounding_mode in ROUNDING_MODES {
+                check_sub_rounding(i, j, rounding_mode);
+            }
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn test_all_sub_rounding() {
+        for i in 0..u16::MAX {
+            for j in 0..u16::MAX {
+                for rounding_mode in ROUNDING_MODES {
+                    check_sub_rounding(i, j, rounding_mode);
+                }
+            }
+        }
+    }
 }
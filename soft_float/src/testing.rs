@@ -0,0 +1,281 @@
+// helpers shared by the tests of several modules
+
+use std::cmp::Ordering;
+
+use crate::{RoundingMode, SoftFloat16};
+
+// xorshift64 (Marsaglia 2003) for sampling operands where exhaustive tests
+// are not feasible; the fixed seed keeps failures reproducible
+pub(crate) struct XorShift(u64);
+
+impl XorShift {
+    pub(crate) fn new() -> Self {
+        Self(0x2545f4914f6cdd1d)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    // the high bits, which are better distributed than the low ones
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    pub(crate) fn next_u16(&mut self) -> u16 {
+        (self.next_u64() >> 48) as u16
+    }
+}
+
+// all rounding modes, for the tests of the operations taking one
+pub(crate) const ROUNDING_MODES: [RoundingMode; 6] = [
+    RoundingMode::NearestTiesToEven,
+    RoundingMode::NearestTiesToAway,
+    RoundingMode::TowardZero,
+    RoundingMode::TowardPositive,
+    RoundingMode::TowardNegative,
+    RoundingMode::ToOdd,
+];
+
+fn value(v: SoftFloat16) -> f64 {
+    if SoftFloat16::exponent(v) == 0x1F {
+        // treat infinity like the next power of two after the largest
+        // finite number, which is where rounding to nearest overflows
+        if SoftFloat16::sign(v) == 0 {
+            65536.0
+        } else {
+            -65536.0
+        }
+    } else {
+        f32::from(v) as f64
+    }
+}
+
+fn next_up(v: SoftFloat16) -> SoftFloat16 {
+    let bits = SoftFloat16::to_bits(v);
+    if bits & 0x7fff == 0 {
+        SoftFloat16::from_bits(0x0001)
+    } else if bits >> 15 == 0 {
+        SoftFloat16::from_bits(bits + 1)
+    } else {
+        SoftFloat16::from_bits(bits - 1)
+    }
+}
+
+fn next_down(v: SoftFloat16) -> SoftFloat16 {
+    let bits = SoftFloat16::to_bits(v);
+    if bits & 0x7fff == 0 {
+        SoftFloat16::from_bits(0x8001)
+    } else if bits >> 15 == 0 {
+        SoftFloat16::from_bits(bits - 1)
+    } else {
+        SoftFloat16::from_bits(bits + 1)
+    }
+}
+
+// reference implementation; given the correctly rounded result for
+// rounding to nearest (ties to even) and a function comparing the exact
+// result to arbitrary (dyadic) numbers, determines the two neighbors of
+// the exact result and picks one according to the rounding mode
+fn reference(
+    nearest: SoftFloat16,
+    compare: impl Fn(f64) -> Ordering,
+    rounding_mode: RoundingMode,
+) -> SoftFloat16 {
+    let ordering = compare(value(nearest));
+    let (lo, hi) = if SoftFloat16::exponent(nearest) == 0x1F {
+        // overflow of a finite result
+        if SoftFloat16::sign(nearest) == 0 {
+            (SoftFloat16::from_bits(0x7bff), nearest)
+        } else {
+            (nearest, SoftFloat16::from_bits(0xfbff))
+        }
+    } else if ordering == Ordering::Equal {
+        return nearest;
+    } else if ordering == Ordering::Greater {
+        (nearest, next_up(nearest))
+    } else {
+        (next_down(nearest), nearest)
+    };
+    let (smaller, larger) = if SoftFloat16::to_bits(lo) & 0x7fff < SoftFloat16::to_bits(hi) & 0x7fff
+    {
+        (lo, hi)
+    } else {
+        (hi, lo)
+    };
+    match rounding_mode {
+        RoundingMode::NearestTiesToEven => nearest,
+        RoundingMode::NearestTiesToAway => {
+            if compare((value(lo) + value(hi)) / 2.0) == Ordering::Equal {
+                larger
+            } else {
+                nearest
+            }
+        }
+        RoundingMode::TowardZero => smaller,
+        RoundingMode::TowardPositive => hi,
+        RoundingMode::TowardNegative => lo,
+        RoundingMode::ToOdd => {
+            if SoftFloat16::to_bits(lo) & 1 == 1 {
+                lo
+            } else {
+                hi
+            }
+        }
+    }
+}
+
+// checks results that are exact or special (zero, infinity, NAN) and
+// hence don't depend on the rounding mode
+pub(crate) fn check_exact(
+    y: SoftFloat16,
+    y_f: SoftFloat16,
+    rounding_mode: RoundingMode,
+    args: &[u16],
+) {
+    if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+        assert!(SoftFloat16::is_nan(y), "{:?}", (args, rounding_mode));
+        assert!(SoftFloat16::is_nan(y_f), "{:?}", (args, rounding_mode));
+    } else {
+        assert_eq!(
+            SoftFloat16::to_bits(y),
+            SoftFloat16::to_bits(y_f),
+            "{:?}",
+            (args, rounding_mode)
+        );
+    }
+}
+
+// checks y against the reference for the exact result described by compare
+pub(crate) fn check_rounding(
+    y: SoftFloat16,
+    nearest: SoftFloat16,
+    compare: impl Fn(f64) -> Ordering,
+    rounding_mode: RoundingMode,
+    args: &[u16],
+) {
+    if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(nearest) {
+        assert!(SoftFloat16::is_nan(y), "{:?}", (args, rounding_mode));
+        assert!(SoftFloat16::is_nan(nearest), "{:?}", (args, rounding_mode));
+    } else {
+        let y_f = reference(nearest, compare, rounding_mode);
+        assert_eq!(
+            SoftFloat16::to_bits(y),
+            SoftFloat16::to_bits(y_f),
+            "{:?}",
+            (args, rounding_mode)
+        );
+    }
+}
+
+// comparison of a SoftFloat type with the host float type of the same format
+// for the operations that the host implements correctly rounded; defines
+// operands() and check() for further tests of the calling module
+macro_rules! host_tests {
+    ($soft:ty, $host:ty, $bits:ty, $special_values:expr) => {
+        // all combinations of special values (both signs) followed by
+        // pseudo-random numbers
+        fn operands(n: usize) -> impl Iterator<Item = $bits> {
+            let sign_bit: $bits = 1 << (<$bits>::BITS - 1);
+            let special = $special_values
+                .into_iter()
+                .flat_map(move |v| [v, v | sign_bit]);
+            let mut rng = $crate::testing::XorShift::new();
+            let random = (0..n).map(move |_| (rng.next_u64() >> (64 - <$bits>::BITS)) as $bits);
+            special.chain(random)
+        }
+
+        fn check(y: $soft, y_f: $host, args: ($bits, $bits, $bits)) {
+            if <$soft>::is_nan(y) || y_f.is_nan() {
+                assert!(<$soft>::is_nan(y), "{:x?}", args);
+                assert!(y_f.is_nan(), "{:x?}", args);
+            } else {
+                assert_eq!(<$soft>::to_bits(y), y_f.to_bits(), "{:x?}", args);
+            }
+        }
+
+        fn check_binary(op: impl Fn($soft, $soft) -> $soft, op_f: impl Fn($host, $host) -> $host) {
+            for i in operands(300) {
+                for j in operands(300) {
+                    let (x0, x1) = (<$soft>::from_bits(i), <$soft>::from_bits(j));
+                    let y_f = op_f(<$host>::from_bits(i), <$host>::from_bits(j));
+                    check(op(x0, x1), y_f, (i, j, 0));
+                }
+            }
+        }
+
+        #[test]
+        fn test_add() {
+            check_binary(|x0, x1| x0 + x1, |x0, x1| x0 + x1);
+        }
+
+        #[test]
+        fn test_sub() {
+            check_binary(|x0, x1| x0 - x1, |x0, x1| x0 - x1);
+        }
+
+        #[test]
+        fn test_mul() {
+            check_binary(|x0, x1| x0 * x1, |x0, x1| x0 * x1);
+        }
+
+        #[test]
+        fn test_div() {
+            check_binary(|x0, x1| x0 / x1, |x0, x1| x0 / x1);
+        }
+
+        #[test]
+        fn test_cmp() {
+            for i in operands(300) {
+                for j in operands(300) {
+                    let (x0, x1) = (<$soft>::from_bits(i), <$soft>::from_bits(j));
+                    let (x0_f, x1_f) = (<$host>::from_bits(i), <$host>::from_bits(j));
+                    assert_eq!(
+                        x0.partial_cmp(&x1),
+                        x0_f.partial_cmp(&x1_f),
+                        "{:x?}",
+                        (i, j)
+                    );
+                    assert_eq!(x0 == x1, x0_f == x1_f, "{:x?}", (i, j));
+                }
+            }
+        }
+
+        #[test]
+        fn test_mul_add() {
+            for i in operands(40) {
+                for j in operands(40) {
+                    for k in operands(40) {
+                        let x0 = <$soft>::from_bits(i);
+                        let x1 = <$soft>::from_bits(j);
+                        let x2 = <$soft>::from_bits(k);
+                        let y_f = <$host>::from_bits(i)
+                            .mul_add(<$host>::from_bits(j), <$host>::from_bits(k));
+                        check($crate::MulAdd::mul_add(x0, x1, x2), y_f, (i, j, k));
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_unary() {
+            for i in operands(100000) {
+                let x = <$soft>::from_bits(i);
+                let x_f = <$host>::from_bits(i);
+                check(-x, -x_f, (i, 0, 0));
+                check($crate::Sqrt::sqrt(x), x_f.sqrt(), (i, 0, 0));
+                check(
+                    $crate::RoundTiesEven::round_ties_even(x),
+                    x_f.round_ties_even(),
+                    (i, 0, 0),
+                );
+                check($crate::Trunc::trunc(x), x_f.trunc(), (i, 0, 0));
+            }
+        }
+    };
+}
+
+pub(crate) use host_tests;
@@ -0,0 +1,11 @@
+// a result is tiny if it is nonzero and below the smallest normal number,
+// either before rounding or after rounding as if the exponent range were
+// unbounded, see IEEE 754, sec. 7.5; x86 detects tininess after rounding,
+// while, e.g., ARM and RISC-V detect it before rounding; the choice only
+// affects whether the underflow exception is raised, never the result
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TininessDetection {
+    BeforeRounding,
+    #[default]
+    AfterRounding,
+}
@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+use crate::{SoftFloat, Storage};
+
+// SoftFloat ordered by totalOrder (see SoftFloat::total_cmp), which makes it
+// Eq, Ord and Hash, e.g., for sorting and deduplication, or as key of BTreeMap
+// and HashMap; unlike with the comparisons of SoftFloat, -0 < +0, and each
+// NAN equals itself (but no other NAN encoding)
+#[derive(Clone, Copy, Debug)]
+pub struct TotalOrder<F>(pub F);
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> PartialEq
+    for TotalOrder<SoftFloat<T, EXP_BITS, MANT_BITS>>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Eq
+    for TotalOrder<SoftFloat<T, EXP_BITS, MANT_BITS>>
+{
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> PartialOrd
+    for TotalOrder<SoftFloat<T, EXP_BITS, MANT_BITS>>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Ord
+    for TotalOrder<SoftFloat<T, EXP_BITS, MANT_BITS>>
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        SoftFloat::total_cmp(self.0, other.0)
+    }
+}
+
+// numbers are equal in totalOrder iff their encodings are
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Hash
+    for TotalOrder<SoftFloat<T, EXP_BITS, MANT_BITS>>
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        SoftFloat::raw(self.0).hash(state);
+    }
+}
+
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> SoftFloat<T, EXP_BITS, MANT_BITS> {
+    // sorts in totalOrder, so negative NANs come first and positive ones last
+    pub fn sort_total(values: &mut [Self]) {
+        values.sort_unstable_by(|v0, v1| Self::total_cmp(*v0, *v1));
+    }
+
+    // the values must be sorted in totalOrder, e.g., by sort_total; compare
+    // slice::binary_search
+    pub fn binary_search_total(values: &[Self], value: Self) -> Result<usize, usize> {
+        values.binary_search_by(|v| Self::total_cmp(*v, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap, HashSet};
+
+    use super::*;
+    use crate::{f16, SoftFloat16};
+
+    #[test]
+    fn test_sort_total() {
+        let mut values = [
+            f16!(1.5),
+            f16!(nan),
+            f16!(-0.0),
+            f16!(-inf),
+            f16!(0),
+            SoftFloat16::from_bits(0xfe00),
+            f16!(-1.5),
+            f16!(65504),
+            SoftFloat16::from_bits(0x7c01),
+        ];
+        SoftFloat16::sort_total(&mut values);
+        assert_eq!(
+            values.map(SoftFloat16::to_bits),
+            [0xfe00, 0xfc00, 0xbe00, 0x8000, 0x0000, 0x3e00, 0x7bff, 0x7c01, 0x7e00]
+        );
+
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(1.5)), Ok(5));
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(0)), Ok(4));
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(-0.0)), Ok(3));
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(nan)), Ok(8));
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(1)), Err(5));
+        assert_eq!(SoftFloat16::binary_search_total(&values, f16!(inf)), Err(7));
+    }
+
+    #[test]
+    fn test_total_order() {
+        let values = [
+            f16!(0),
+            f16!(-0.0),
+            f16!(nan),
+            f16!(nan),
+            f16!(1),
+            f16!(1.0),
+        ];
+
+        let set: BTreeSet<_> = values.into_iter().map(TotalOrder).collect();
+        assert_eq!(
+            set.iter()
+                .map(|v| SoftFloat16::to_bits(v.0))
+                .collect::<Vec<_>>(),
+            [0x8000, 0x0000, 0x3c00, 0x7e00]
+        );
+
+        let set: HashSet<_> = values.into_iter().map(TotalOrder).collect();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&TotalOrder(f16!(-0.0))));
+        assert!(!set.contains(&TotalOrder(SoftFloat16::from_bits(0x7e01))));
+
+        let mut counts = HashMap::new();
+        for v in values {
+            *counts.entry(TotalOrder(v)).or_insert(0) += 1;
+        }
+        assert_eq!(counts[&TotalOrder(f16!(nan))], 2);
+        assert_eq!(counts[&TotalOrder(f16!(1))], 2);
+
+        assert!(TotalOrder(f16!(-0.0)) < TotalOrder(f16!(0)));
+        assert!(TotalOrder(f16!(inf)) < TotalOrder(f16!(nan)));
+        assert_eq!(TotalOrder(f16!(nan)), TotalOrder(f16!(nan)));
+    }
+}
@@ -1,59 +1,27 @@
-use crate::{
-    soft_float16::{NEG_ZERO, POS_ZERO},
-    SoftFloat16,
-};
+use crate::{RoundingMode, SoftFloat, Storage};
 
 pub trait Trunc {
     fn trunc(v: Self) -> Self;
 }
 
-impl Trunc for SoftFloat16 {
+impl<T: Storage, const EXP_BITS: u32, const MANT_BITS: u32> Trunc
+    for SoftFloat<T, EXP_BITS, MANT_BITS>
+{
     fn trunc(v: Self) -> Self {
-        let (sign, exponent, significand) =
-            (Self::sign(v), Self::exponent(v), Self::significand(v));
-
-        let unbiased_exponent = (exponent as i16) - 15;
-
-        if unbiased_exponent < -1 {
-            if sign == 0 {
-                POS_ZERO
-            } else {
-                NEG_ZERO
-            }
-        } else if unbiased_exponent >= 10 {
-            v
-        } else {
-            let shift = 10 - unbiased_exponent;
-            let exponent = (unbiased_exponent + 15) as u16;
-            let significand = significand | 1 << 10;
-
-            let integer = significand >> shift;
-            let significand = integer << shift;
-
-            if significand == 0 {
-                if sign == 0 {
-                    POS_ZERO
-                } else {
-                    NEG_ZERO
-                }
-            } else {
-                Self::from_bits(sign << 15 | exponent << 10 | significand & ((1 << 10) - 1))
-            }
-        }
+        Self::round_to_integral_with_flags(v, RoundingMode::TowardZero).0
     }
 }
 
 impl Trunc for f32 {
-    fn trunc(_v: Self) -> Self {
-        unreachable!();
+    fn trunc(v: Self) -> Self {
+        v.trunc()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::soft_float16::NAN;
-
     use super::*;
+    use crate::SoftFloat16;
 
     #[test]
     #[ignore]
@@ -62,9 +30,9 @@ mod tests {
             let x = SoftFloat16::from_bits(i);
             let y = SoftFloat16::trunc(x);
             let y_f = SoftFloat16::from(f32::from(x).trunc());
-            if y == NAN || y_f == NAN {
-                assert!(y == NAN, "{}", i);
-                assert!(y_f == NAN, "{}", i);
+            if SoftFloat16::is_nan(y) || SoftFloat16::is_nan(y_f) {
+                assert!(SoftFloat16::is_nan(y), "{}", i);
+                assert!(SoftFloat16::is_nan(y_f), "{}", i);
             } else {
                 assert_eq!(
                     SoftFloat16::to_bits(y),
@@ -77,4 +45,26 @@ mod tests {
             }
         }
     }
+
+    fn check_all_trunc<const EXP_BITS: u32, const MANT_BITS: u32>() {
+        for i in 0..=u8::MAX {
+            let x0 = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from_bits(i);
+            let y = SoftFloat::trunc(x0);
+            let y_f = SoftFloat::<u8, EXP_BITS, MANT_BITS>::from(f32::from(x0).trunc());
+            if SoftFloat::is_nan(y) || SoftFloat::is_nan(y_f) {
+                assert!(SoftFloat::is_nan(y), "{:?}", i);
+                assert!(SoftFloat::is_nan(y_f), "{:?}", i);
+            } else {
+                assert_eq!(SoftFloat::to_bits(y), SoftFloat::to_bits(y_f), "{:?}", i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_trunc_binary8() {
+        check_all_trunc::<2, 5>();
+        check_all_trunc::<3, 4>();
+        check_all_trunc::<4, 3>();
+        check_all_trunc::<5, 2>();
+    }
 }
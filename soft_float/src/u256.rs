@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+
+// unsigned 256-bit integer, wide enough to hold the exact product of two
+// significands of up to 128 bits; used where intermediate results of the
+// multiplication, fused multiply-add and square root need twice as many bits
+// as the format, which for binary128 exceeds u128
+//
+// all operations are const fns instead of operators, which are not available
+// in const contexts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct U256 {
+    hi: u128,
+    lo: u128,
+}
+
+impl U256 {
+    pub(crate) const ZERO: Self = Self { hi: 0, lo: 0 };
+
+    pub(crate) const ONE: Self = Self { hi: 0, lo: 1 };
+
+    pub(crate) const fn from_u128(v: u128) -> Self {
+        Self { hi: 0, lo: v }
+    }
+
+    // full product of two u128, computed from 64-bit halves
+    pub(crate) const fn widening_mul(v0: u128, v1: u128) -> Self {
+        let mask = u64::MAX as u128;
+        let (v0_hi, v0_lo) = (v0 >> 64, v0 & mask);
+        let (v1_hi, v1_lo) = (v1 >> 64, v1 & mask);
+
+        let lo_lo = v0_lo * v1_lo;
+        let lo_hi = v0_lo * v1_hi;
+        let hi_lo = v0_hi * v1_lo;
+        let hi_hi = v0_hi * v1_hi;
+
+        // sum of the middle partial products, at most 3 * 2^64
+        let mid = (lo_lo >> 64) + (lo_hi & mask) + (hi_lo & mask);
+
+        Self {
+            hi: hi_hi + (lo_hi >> 64) + (hi_lo >> 64) + (mid >> 64),
+            lo: (mid << 64) | (lo_lo & mask),
+        }
+    }
+
+    // the value must fit into u128
+    pub(crate) const fn to_u128(v: Self) -> u128 {
+        assert!(v.hi == 0);
+        v.lo
+    }
+
+    pub(crate) const fn leading_zeros(v: Self) -> u32 {
+        if v.hi != 0 {
+            v.hi.leading_zeros()
+        } else {
+            128 + v.lo.leading_zeros()
+        }
+    }
+
+    pub(crate) const fn is_zero(v: Self) -> bool {
+        v.hi == 0 && v.lo == 0
+    }
+
+    pub(crate) const fn compare(v0: Self, v1: Self) -> Ordering {
+        if v0.hi < v1.hi || (v0.hi == v1.hi && v0.lo < v1.lo) {
+            Ordering::Less
+        } else if v0.hi == v1.hi && v0.lo == v1.lo {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    // panics on overflow like the primitive types (in debug builds)
+    pub(crate) const fn sum(v0: Self, v1: Self) -> Self {
+        let (lo, carry) = v0.lo.overflowing_add(v1.lo);
+        Self {
+            hi: v0.hi + v1.hi + carry as u128,
+            lo,
+        }
+    }
+
+    pub(crate) const fn difference(v0: Self, v1: Self) -> Self {
+        let (lo, borrow) = v0.lo.overflowing_sub(v1.lo);
+        Self {
+            hi: v0.hi - v1.hi - borrow as u128,
+            lo,
+        }
+    }
+
+    pub(crate) const fn and(v0: Self, v1: Self) -> Self {
+        Self {
+            hi: v0.hi & v1.hi,
+            lo: v0.lo & v1.lo,
+        }
+    }
+
+    pub(crate) const fn or(v0: Self, v1: Self) -> Self {
+        Self {
+            hi: v0.hi | v1.hi,
+            lo: v0.lo | v1.lo,
+        }
+    }
+
+    // unlike the primitive types, shifting by the width or more yields zero
+    pub(crate) const fn shift_left(v: Self, shift: u32) -> Self {
+        match shift {
+            0 => v,
+            1..=127 => Self {
+                hi: (v.hi << shift) | (v.lo >> (128 - shift)),
+                lo: v.lo << shift,
+            },
+            128..=255 => Self {
+                hi: v.lo << (shift - 128),
+                lo: 0,
+            },
+            _ => Self::ZERO,
+        }
+    }
+
+    pub(crate) const fn shift_right(v: Self, shift: u32) -> Self {
+        match shift {
+            0 => v,
+            1..=127 => Self {
+                hi: v.hi >> shift,
+                lo: (v.lo >> shift) | (v.hi << (128 - shift)),
+            },
+            128..=255 => Self {
+                hi: 0,
+                lo: v.hi >> (shift - 128),
+            },
+            _ => Self::ZERO,
+        }
+    }
+
+    // shifts right and ORs all bits shifted out into the least significant
+    // bit, compare shift_right_sticky
+    pub(crate) const fn shift_right_sticky(v: Self, shift: i32) -> Self {
+        assert!(shift >= 0);
+        let shift = if shift > 256 { 256 } else { shift as u32 };
+        let shifted = Self::shift_right(v, shift);
+        let sticky = Self::compare(v, Self::shift_left(shifted, shift)).is_ne();
+        Self::or(shifted, Self::from_u128(sticky as u128))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widening_mul() {
+        for (v0, v1) in [
+            (0, 0),
+            (3, 5),
+            (u64::MAX as u128, u64::MAX as u128),
+            (1 << 100, 1 << 27),
+            (0x1234_5678_9abc_def0_1234, 0xfedc_ba98_7654),
+        ] {
+            let y = U256::widening_mul(v0, v1);
+            assert_eq!(U256::to_u128(y), v0 * v1, "{:?}", (v0, v1));
+        }
+
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        let y = U256::widening_mul(u128::MAX, u128::MAX);
+        assert_eq!(y.hi, u128::MAX - 1);
+        assert_eq!(y.lo, 1);
+
+        let y = U256::widening_mul(1 << 127, 1 << 127);
+        assert_eq!(y, U256::shift_left(U256::ONE, 254));
+    }
+
+    #[test]
+    fn test_shift() {
+        let x = U256 {
+            hi: 0x0123,
+            lo: 0x8000_0000_0000_0000_0000_0000_0000_0001,
+        };
+        assert_eq!(
+            U256::shift_left(x, 1),
+            U256 {
+                hi: 0x0247,
+                lo: 0x0000_0000_0000_0000_0000_0000_0000_0002
+            }
+        );
+        assert_eq!(U256::shift_left(x, 128), U256 { hi: x.lo, lo: 0 });
+        assert_eq!(U256::shift_left(x, 256), U256::ZERO);
+        assert_eq!(U256::shift_right(U256::shift_left(x, 4), 4), x);
+        assert_eq!(U256::shift_right(x, 128), U256::from_u128(0x0123));
+        assert_eq!(U256::shift_right(x, 129), U256::from_u128(0x0091));
+
+        assert_eq!(
+            U256::shift_right_sticky(x, 128),
+            U256::from_u128(0x0123 | 1)
+        );
+        assert_eq!(
+            U256::shift_right_sticky(U256::shift_left(x, 128), 128),
+            U256::from_u128(x.lo)
+        );
+        assert_eq!(U256::shift_right_sticky(x, 300), U256::ONE);
+    }
+
+    #[test]
+    fn test_add_sub() {
+        let x = U256::from_u128(u128::MAX);
+        let y = U256::sum(x, U256::ONE);
+        assert_eq!(y, U256::shift_left(U256::ONE, 128));
+        assert_eq!(U256::difference(y, U256::ONE), x);
+        assert_eq!(U256::compare(y, x), Ordering::Greater);
+        assert_eq!(U256::compare(x, y), Ordering::Less);
+        assert_eq!(U256::compare(x, x), Ordering::Equal);
+        assert_eq!(U256::difference(y, x), U256::ONE);
+        assert_eq!(U256::leading_zeros(x), 128);
+        assert_eq!(U256::leading_zeros(y), 127);
+        assert_eq!(U256::leading_zeros(U256::ZERO), 256);
+        assert!(U256::is_zero(U256::and(x, U256::shift_left(x, 128))));
+        assert_eq!(
+            U256::or(x, y),
+            U256 {
+                hi: 1,
+                lo: u128::MAX
+            }
+        );
+    }
+}